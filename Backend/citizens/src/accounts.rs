@@ -17,6 +17,11 @@ pub mod core_account {
     declare_id!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
 }
 
+pub mod orao_program {
+    use super::*;
+    declare_id!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
+}
+
 //////////// helper functions for checking accounts ////////////////
 
 pub fn check_core_key<'a>(account_info: &'a AccountInfo<'a>) -> ProgramResult {
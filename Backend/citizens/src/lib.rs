@@ -4,6 +4,7 @@ pub mod entrypoint;
 pub mod instruction;
 pub mod plugins;
 pub mod processor;
+pub mod randomness;
 pub mod resolve_mission;
 pub mod start_mission;
 pub mod state;
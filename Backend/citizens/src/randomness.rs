@@ -0,0 +1,164 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{accounts, state};
+
+// Randomness for missions, read through the same provider abstraction as the launch program:
+// - Orao VRF: the fulfilled Orao request the user committed to when starting the mission
+// - Local: the hashed account StartMission writes itself off mainnet. The user can grind its
+//   inputs, so it is only accepted where Orao is not available
+// - Deterministic: only compiled into builds with the `test-randomness` feature
+
+pub const RANDOMNESS_REQUEST_SEED: &[u8] = b"orao-vrf-randomness-request";
+
+// sha256("account:Randomness")[..8]
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [188, 96, 216, 248, 93, 94, 49, 112];
+// sha256("account:RandomnessV2")[..8]
+const RANDOMNESS_V2_DISCRIMINATOR: [u8; 8] = [139, 239, 184, 215, 227, 86, 191, 226];
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RandomnessResponse {
+    pub pubkey: Pubkey,
+    pub randomness: [u8; 64],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Randomness {
+    pub seed: [u8; 32],
+    pub randomness: [u8; 64],
+    pub responses: Vec<RandomnessResponse>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum Request {
+    Pending {
+        client: Pubkey,
+        seed: [u8; 32],
+        responses: Vec<RandomnessResponse>,
+    },
+    Fulfilled {
+        client: Pubkey,
+        seed: [u8; 32],
+        randomness: [u8; 64],
+    },
+}
+
+pub trait RandomnessProvider {
+    // 64 bytes of randomness, failing until the source has produced them
+    fn randomness(&self) -> Result<[u8; 64], ProgramError>;
+}
+
+pub struct OraoProvider<'a, 'b> {
+    pub request: &'a AccountInfo<'b>,
+}
+
+impl RandomnessProvider for OraoProvider<'_, '_> {
+    fn randomness(&self) -> Result<[u8; 64], ProgramError> {
+        let data = self.request.try_borrow_data()?;
+        if data.len() < 8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (discriminator, mut body) = data.split_at(8);
+
+        let randomness = if discriminator == RANDOMNESS_DISCRIMINATOR {
+            let request = Randomness::deserialize(&mut body)?;
+            if request.randomness == [0; 64] {
+                None
+            } else {
+                Some(request.randomness)
+            }
+        } else if discriminator == RANDOMNESS_V2_DISCRIMINATOR {
+            match Request::deserialize(&mut body)? {
+                Request::Pending { .. } => None,
+                Request::Fulfilled { randomness, .. } => Some(randomness),
+            }
+        } else {
+            msg!("Account is not an Orao randomness request");
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        randomness.ok_or_else(|| {
+            msg!(
+                "Randomness request {} has not been fulfilled yet",
+                self.request.key
+            );
+            ProgramError::InvalidAccountData
+        })
+    }
+}
+
+pub struct LocalProvider<'a, 'b> {
+    pub randoms: &'a AccountInfo<'b>,
+}
+
+impl RandomnessProvider for LocalProvider<'_, '_> {
+    fn randomness(&self) -> Result<[u8; 64], ProgramError> {
+        let data = self.randoms.try_borrow_data()?;
+        if data.len() < 104 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut randomness = [0u8; 64];
+        randomness.copy_from_slice(&data[40..104]);
+        Ok(randomness)
+    }
+}
+
+#[cfg(feature = "test-randomness")]
+pub struct DeterministicProvider {
+    pub seed: Pubkey,
+    pub consumer: Pubkey,
+}
+
+#[cfg(feature = "test-randomness")]
+impl RandomnessProvider for DeterministicProvider {
+    fn randomness(&self) -> Result<[u8; 64], ProgramError> {
+        let mut randomness = [0u8; 64];
+        for (i, chunk) in randomness.chunks_mut(32).enumerate() {
+            let hash = solana_program::hash::hashv(&[
+                self.seed.as_ref(),
+                self.consumer.as_ref(),
+                &[i as u8],
+            ]);
+            chunk.copy_from_slice(hash.as_ref());
+        }
+        Ok(randomness)
+    }
+}
+
+// The randomness source behind `account`, which must be the one recorded in `expected_address`
+pub fn load_provider<'a, 'b>(
+    program_id: &Pubkey,
+    account: &'a AccountInfo<'b>,
+    expected_address: &Pubkey,
+    consumer: &Pubkey,
+) -> Result<Box<dyn RandomnessProvider + 'a>, ProgramError> {
+    #[cfg(feature = "test-randomness")]
+    if account.key == &solana_program::system_program::ID {
+        return Ok(Box::new(DeterministicProvider {
+            seed: *expected_address,
+            consumer: *consumer,
+        }));
+    }
+    #[cfg(not(feature = "test-randomness"))]
+    let _ = consumer;
+
+    if account.key != expected_address {
+        msg!(
+            "expected randoms account {} {}",
+            expected_address,
+            account.key
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if account.owner == &accounts::orao_program::ID {
+        return Ok(Box::new(OraoProvider { request: account }));
+    }
+
+    if account.owner == program_id && state::NETWORK != state::Network::Mainnet {
+        return Ok(Box::new(LocalProvider { randoms: account }));
+    }
+
+    msg!("Account {} is not a randomness source", account.key);
+    Err(ProgramError::IllegalOwner)
+}
@@ -1,4 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use std::convert::TryInto;
 
 use mpl_core::instructions::TransferV1CpiBuilder;
 use solana_program::{
@@ -9,6 +10,7 @@ use solana_program::{
 use crate::{
     accounts,
     instruction::accounts::ResolveMissionAccounts,
+    randomness,
     state::{MissionStatus, UserData},
     utils::{self, send_citizen_to_cook},
 };
@@ -119,7 +121,15 @@ pub fn resolve_mission<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>])
         return Err(ProgramError::InvalidAccountData);
     }
 
-    let mut seed = u64::try_from_slice(&ctx.accounts.randoms.data.borrow()[56..64])?;
+    let random_bytes = randomness::load_provider(
+        program_id,
+        ctx.accounts.randoms,
+        &user_data.randoms_address,
+        ctx.accounts.user_data.key,
+    )?
+    .randomness()?;
+
+    let mut seed = u64::from_le_bytes(random_bytes[16..24].try_into().unwrap());
     if seed == 0 {
         msg!("invalid seed");
         return Err(ProgramError::InvalidAccountData);
@@ -196,9 +206,12 @@ pub fn resolve_mission<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>])
 
     user_data.serialize(&mut &mut ctx.accounts.user_data.data.borrow_mut()[..])?;
 
-    let randoms_lamports = **ctx.accounts.randoms.try_borrow_lamports()?;
-    **ctx.accounts.randoms.try_borrow_mut_lamports()? = 0;
-    **ctx.accounts.user.try_borrow_mut_lamports()? += randoms_lamports;
+    // only the local randoms account is ours to close, orao requests belong to orao
+    if ctx.accounts.randoms.owner == program_id {
+        let randoms_lamports = **ctx.accounts.randoms.try_borrow_lamports()?;
+        **ctx.accounts.randoms.try_borrow_mut_lamports()? = 0;
+        **ctx.accounts.user.try_borrow_mut_lamports()? += randoms_lamports;
+    }
 
     msg!("Mission Resolved");
 
@@ -10,6 +10,7 @@ use solana_program::{
 use crate::{
     accounts,
     instruction::{accounts::StartMissionAccounts, StartMissionArgs},
+    randomness,
     state::{self, MissionStatus, SummaryData, UserData},
     utils,
 };
@@ -54,12 +55,17 @@ pub fn start_mission<'a>(
     )
     .unwrap();
 
+    // on mainnet the user requests orao randomness for the seed themselves, otherwise we write it
+    let randoms_owner = if state::NETWORK == state::Network::Mainnet {
+        &accounts::orao_program::ID
+    } else {
+        program_id
+    };
     let random_bump = accounts::check_program_data_account(
         ctx.accounts.randoms,
-        program_id,
-        vec![b"orao-vrf-randomness-request", &args.seed],
-    )
-    .unwrap();
+        randoms_owner,
+        vec![randomness::RANDOMNESS_REQUEST_SEED, &args.seed],
+    )?;
 
     accounts::check_system_program_key(ctx.accounts.system_program)?;
 
@@ -182,15 +188,48 @@ pub fn start_mission<'a>(
     summary_data.serialize(&mut &mut ctx.accounts.summary.data.borrow_mut()[..])?;
 
     // handle the randoms
+    if state::NETWORK != state::Network::Mainnet {
+        write_local_randoms(
+            ctx.accounts.user,
+            ctx.accounts.randoms,
+            program_id,
+            random_bump,
+            &args,
+        )?;
+    }
+
+    // transfer the citizen
+    msg!("transfer citizen");
+    let _transfer = TransferV1CpiBuilder::new(ctx.accounts.core_program)
+        .asset(ctx.accounts.asset)
+        .authority(Some(ctx.accounts.user))
+        .payer(ctx.accounts.user)
+        .new_owner(ctx.accounts.pda)
+        .collection(Some(ctx.accounts.collection))
+        .invoke_signed(&[&[&accounts::SOL_SEED.to_le_bytes(), &[pda_bump_seed]]])?;
+
+    msg!("mission started");
+
+    Ok(())
+}
+
+// the fallback where orao is not available: a hash of the seed, clock and user, which the user can grind
+fn write_local_randoms<'a>(
+    user: &'a AccountInfo<'a>,
+    randoms: &'a AccountInfo<'a>,
+    program_id: &Pubkey,
+    random_bump: u8,
+    args: &StartMissionArgs,
+) -> ProgramResult {
     // create the account
     msg!("create randoms account");
     utils::create_program_account(
-        ctx.accounts.user,
-        ctx.accounts.randoms,
+        user,
+        randoms,
         program_id,
         random_bump,
         104,
-        vec![b"orao-vrf-randomness-request", &args.seed],
+        vec![randomness::RANDOMNESS_REQUEST_SEED, &args.seed],
     )?;
 
     let mut seed_values = state::SeedStruct {
@@ -204,14 +243,12 @@ pub fn start_mission<'a>(
     seed_values.seed_prices[4] = Clock::get()?.slot;
     seed_values.seed_prices[5] = Clock::get()?.unix_timestamp as u64;
 
-    seed_values.seed_prices[6] =
-        u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[..8].try_into().unwrap());
-    seed_values.seed_prices[7] =
-        u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[8..16].try_into().unwrap());
+    seed_values.seed_prices[6] = u64::from_le_bytes(user.key.to_bytes()[..8].try_into().unwrap());
+    seed_values.seed_prices[7] = u64::from_le_bytes(user.key.to_bytes()[8..16].try_into().unwrap());
     seed_values.seed_prices[8] =
-        u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[16..24].try_into().unwrap());
+        u64::from_le_bytes(user.key.to_bytes()[16..24].try_into().unwrap());
     seed_values.seed_prices[9] =
-        u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[24..32].try_into().unwrap());
+        u64::from_le_bytes(user.key.to_bytes()[24..32].try_into().unwrap());
 
     let vec_to_hash = unsafe { utils::any_as_u8_slice(&seed_values) };
     let hash = &(Sha256::new().chain_update(vec_to_hash).finalize()[..32]);
@@ -220,19 +257,7 @@ pub fn start_mission<'a>(
     hash_array[72..104].copy_from_slice(&hash[..32]);
 
     msg!("serialize randoms");
-    hash_array.serialize(&mut &mut ctx.accounts.randoms.data.borrow_mut()[..])?;
-
-    // transfer the citizen
-    msg!("transfer citizen");
-    let _transfer = TransferV1CpiBuilder::new(ctx.accounts.core_program)
-        .asset(ctx.accounts.asset)
-        .authority(Some(ctx.accounts.user))
-        .payer(ctx.accounts.user)
-        .new_owner(ctx.accounts.pda)
-        .collection(Some(ctx.accounts.collection))
-        .invoke_signed(&[&[&accounts::SOL_SEED.to_le_bytes(), &[pda_bump_seed]]])?;
-
-    msg!("mission started");
+    hash_array.serialize(&mut &mut randoms.data.borrow_mut()[..])?;
 
     Ok(())
 }
@@ -0,0 +1,145 @@
+use crate::state::{Error, Result};
+use sha2::{Digest, Sha256};
+use solana_program::{native_token::sol_to_lamports, pubkey::Pubkey};
+use std::collections::HashSet;
+use std::fs;
+use std::str::FromStr;
+
+// must match launch::plugins in the program
+const ALLOWLIST_LEAF: u8 = 0;
+const ALLOWLIST_NODE: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowlistEntry {
+    pub wallet: Pubkey,
+    pub cap: u64, // lamports, 0 = the launch's default cap
+}
+
+pub fn allowlist_leaf(wallet: &Pubkey, cap: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ALLOWLIST_LEAF]);
+    hasher.update(wallet.to_bytes());
+    hasher.update(cap.to_le_bytes());
+    hasher.finalize().into()
+}
+
+pub fn allowlist_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update([ALLOWLIST_NODE]);
+    hasher.update(first);
+    hasher.update(second);
+    hasher.finalize().into()
+}
+
+// Reads `wallet[,cap]` rows, caps are in SOL and an empty cap uses the launch's default cap.
+// Blank lines, lines starting with # and a `wallet` header row are skipped.
+pub fn read_allowlist_csv(path: &str) -> Result<Vec<AllowlistEntry>> {
+    let contents = fs::read_to_string(path).map_err(Error::ConfigReadError)?;
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.to_lowercase().starts_with("wallet") {
+            continue;
+        }
+
+        let mut columns = line.split(',').map(str::trim);
+        let wallet = columns.next().unwrap_or_default();
+        let wallet = Pubkey::from_str(wallet)
+            .map_err(|_| Error::InvalidConfig(format!("line {}: invalid wallet {}", line_number + 1, wallet)))?;
+
+        let cap = match columns.next() {
+            None | Some("") => 0,
+            Some(cap) => {
+                let sol: f64 = cap
+                    .parse()
+                    .map_err(|_| Error::InvalidConfig(format!("line {}: invalid cap {}", line_number + 1, cap)))?;
+                sol_to_lamports(sol)
+            }
+        };
+
+        if !seen.insert(wallet) {
+            return Err(Error::InvalidConfig(format!("line {}: duplicate wallet {}", line_number + 1, wallet)));
+        }
+        entries.push(AllowlistEntry { wallet, cap });
+    }
+
+    if entries.is_empty() {
+        return Err(Error::InvalidConfig(format!("{} has no allowlist entries", path)));
+    }
+
+    Ok(entries)
+}
+
+// Every level of the tree from the leaves up to the root, an odd node is carried up unhashed
+pub struct AllowlistTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl AllowlistTree {
+    pub fn new(entries: &[AllowlistEntry]) -> Self {
+        let mut levels = vec![entries.iter().map(|entry| allowlist_leaf(&entry.wallet, entry.cap)).collect::<Vec<_>>()];
+
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => allowlist_node(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        AllowlistTree { levels }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    // sibling hashes of the entry at `index`, passed as AllowlistProof.proof
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+            index /= 2;
+        }
+        proof
+    }
+
+    pub fn verify(&self, entry: &AllowlistEntry, proof: &[[u8; 32]]) -> bool {
+        proof
+            .iter()
+            .fold(allowlist_leaf(&entry.wallet, entry.cap), |node, sibling| allowlist_node(&node, sibling))
+            == self.root()
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Prints the root to store in the launch's MerkleAllowlist plugin and every wallet's cap and proof
+pub fn build_allowlist(csv_path: &str) -> Result<()> {
+    let entries = read_allowlist_csv(csv_path)?;
+    let tree = AllowlistTree::new(&entries);
+
+    println!("root {}", to_hex(&tree.root()));
+    for (index, entry) in entries.iter().enumerate() {
+        let proof = tree.proof(index);
+        assert!(tree.verify(entry, &proof));
+        let proof: Vec<String> = proof.iter().map(|node| to_hex(node)).collect();
+        println!("{} {} {}", entry.wallet, entry.cap, proof.join(","));
+    }
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+pub mod allowlist;
 pub mod state;
 
 use crate::state::{ArenaInstruction, Result};
@@ -45,6 +46,14 @@ fn main() {
             std::process::exit(1);
         }
     }
+
+    // allowlist <csv>: merkle root and per wallet proofs for a launch allowlist
+    if function == "allowlist" {
+        if let Err(err) = allowlist::build_allowlist(&args[3]) {
+            eprintln!("{:?}", err);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn init(key_file: &String) -> Result<()> {
@@ -50,6 +50,61 @@ pub fn check_fees_account<'a>(account_info: &'a AccountInfo<'a>) -> ProgramResul
     return Ok(());
 }
 
+/// Reads the program's data account, checking it is the program's own
+pub fn load_program_data(program_id: &Pubkey, program_data: &AccountInfo) -> Result<state::ProgramData, ProgramError> {
+    let (expected_program_data, _bump_seed) = Pubkey::find_program_address(&[&DATA_SEED.to_le_bytes()], program_id);
+    if program_data.key != &expected_program_data {
+        msg!("expected program data account {}", expected_program_data);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if program_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    state::ProgramData::load(&program_data.try_borrow_data()?)
+}
+
+/// Reads the fee schedule in force from the program's data account
+pub fn load_fee_config(program_id: &Pubkey, program_data: &AccountInfo) -> Result<state::FeeConfig, ProgramError> {
+    Ok(load_program_data(program_id, program_data)?.get_fee_config())
+}
+
+/// Checks the signer is the admin recorded in the program's data account
+pub fn check_admin(program_id: &Pubkey, program_data: &AccountInfo, admin: &AccountInfo) -> ProgramResult {
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let expected_admin = load_program_data(program_id, program_data)?.get_admin();
+    if admin.key != &expected_admin {
+        msg!("expected admin {} {}", expected_admin, admin.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Checks protocol fees are being paid to the fee schedule's recipient
+pub fn check_fee_recipient(fee_config: &state::FeeConfig, account_info: &AccountInfo) -> ProgramResult {
+    if account_info.key != &fee_config.recipient {
+        msg!("expected fee recipient {} {}", fee_config.recipient, account_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Checks protocol fees paid in WSOL go to the fee schedule's recipient's WSOL account
+pub fn check_fee_recipient_wsol(fee_config: &state::FeeConfig, account_info: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+    let expected_token_account = get_associated_token_address_with_program_id(&fee_config.recipient, &wrapped_sol_mint_account::ID, token_program.key);
+    if account_info.key != &expected_token_account {
+        msg!("expected fee recipient WSOL account {} {}", expected_token_account, account_info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
 pub mod wrapped_sol_mint_account {
     use super::*;
     declare_id!("So11111111111111111111111111111111111111112");
@@ -90,6 +145,12 @@ pub mod orao_program {
     declare_id!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
 }
 
+// commits and reveals commit-reveal randomness on networks without orao
+pub mod randomness_authority {
+    use super::*;
+    declare_id!("FxVpjJ5AGY6cfCwZQP5v8QBfS4J2NPa62HbGh1Fu2LpD");
+}
+
 pub fn get_expected_raydium_key() -> Pubkey {
     if state::NETWORK == state::Network::Devnet {
         return raydium_dev::ID;
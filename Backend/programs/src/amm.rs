@@ -1,11 +1,49 @@
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
     pubkey::Pubkey,
 };
 use borsh::{BorshSerialize, BorshDeserialize};
+use spl_token_2022::{extension::StateWithExtensions, state::{Account, Mint}};
+
+use crate::{accounts, amm_plugins::{self, AMMPlugin}, trade_to_earn::TradeToEarn, utils::token};
+
+/// Fee kept by the pool on constant product swaps, in basis points
+pub const COOK_AMM_FEE_BPS: u64 = 25;
+
+/// Liquidity locked in the pool by the first deposit, so the share price can never be reset to zero
+pub const MINIMUM_LIQUIDITY: u64 = 1000;
+
+/// Seconds between two stored TWAP observations
+pub const OBSERVATION_INTERVAL: i64 = 300;
+/// Stored TWAP observations, so windows of at least NUM_OBSERVATIONS * OBSERVATION_INTERVAL are always covered
+pub const NUM_OBSERVATIONS: usize = 48;
+
+/// Snapshot of the cumulative price, used as the start of a TWAP window
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub price_cumulative_x64: u128,
+}
 
 // AMM struct for state management
+//
+// Byte layout for external readers (borsh, little endian):
+//   0    base_token_mint .. 128 lp_token_mint (5 x 32 bytes)
+//   160  u16  fee_rate
+//   162  u64  total_liquidity
+//   170  u64  start_time
+//   178  u128 last_price_x64, SOL lamports per raw base unit as Q64.64
+//   194  u128 price_cumulative_x64, sum of last_price_x64 * seconds, wraps on overflow
+//   210  i64  last_update
+//   218  u16  observation_index, slot of the newest observation
+//   220  NUM_OBSERVATIONS x (i64 timestamp, u128 price_cumulative_x64)
+//   1372 trade_to_earn: u64 total_tokens, u32 first_reward_date, u32 last_reward_date
+//   1388 plugins: u32 count, then one borsh AMMPlugin per entry (LiquidityScaling: u8 tag 0, u16 scalar, u64 threshold, u8 active)
+// Pools created before the oracle only have the first 178 bytes until their next swap or AddTradeRewards.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct AMM {
     pub base_token_mint: Pubkey,
@@ -16,9 +54,19 @@ pub struct AMM {
     pub fee_rate: u16,
     pub total_liquidity: u64,
     pub start_time: u64,
+    pub last_price_x64: u128,
+    pub price_cumulative_x64: u128,
+    pub last_update: i64,
+    pub observation_index: u16,
+    pub observations: [Observation; NUM_OBSERVATIONS],
+    pub trade_to_earn: TradeToEarn,
+    pub plugins: Vec<AMMPlugin>,
 }
 
 impl AMM {
+    /// Size of the state without any plugins
+    pub const LEN: usize = 32 * 5 + 2 + 8 + 8 + 16 + 16 + 8 + 2 + NUM_OBSERVATIONS * 24 + 16 + 4;
+
     pub fn new(
         base_token_mint: Pubkey,
         quote_token_mint: Pubkey,
@@ -36,8 +84,110 @@ impl AMM {
             fee_rate,
             total_liquidity: 0,
             start_time: 0,
+            last_price_x64: 0,
+            price_cumulative_x64: 0,
+            last_update: 0,
+            observation_index: 0,
+            observations: [Observation::default(); NUM_OBSERVATIONS],
+            trade_to_earn: TradeToEarn::default(),
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Reads the AMM state, treating the oracle and plugins of accounts created before them as empty
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut padded = data.to_vec();
+        if padded.len() < Self::LEN {
+            padded.resize(Self::LEN, 0);
+        }
+        Ok(Self::deserialize(&mut &padded[..])?)
+    }
+
+    /// Writes the AMM state back, only as much of it as the account holds.
+    /// Plugins are never truncated, the account has to be grown to `size` first.
+    pub fn store(&self, data: &mut [u8]) -> ProgramResult {
+        let encoded = borsh::to_vec(self)?;
+        if !self.plugins.is_empty() && data.len() < encoded.len() {
+            msg!("AMM account holds {} bytes, its plugins need {}", data.len(), encoded.len());
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        let len = data.len().min(encoded.len());
+        data[..len].copy_from_slice(&encoded[..len]);
+        Ok(())
+    }
+
+    /// Bytes the state takes up including its plugins
+    pub fn size(&self) -> Result<usize, ProgramError> {
+        Ok(borsh::to_vec(self)?.len())
+    }
+
+    /// Accumulates the price that held since the last update and records `price_x64` as the new price
+    pub fn update_oracle(&mut self, now: i64, price_x64: u128) {
+        if self.last_update != 0 && now > self.last_update {
+            let elapsed = (now - self.last_update) as u128;
+            self.price_cumulative_x64 = self.price_cumulative_x64.wrapping_add(self.last_price_x64.wrapping_mul(elapsed));
+        }
+        self.last_update = self.last_update.max(now);
+        self.last_price_x64 = price_x64;
+
+        let newest = self.observations[self.observation_index as usize];
+        if newest.timestamp == 0 || self.last_update - newest.timestamp >= OBSERVATION_INTERVAL {
+            if newest.timestamp != 0 {
+                self.observation_index = ((self.observation_index as usize + 1) % NUM_OBSERVATIONS) as u16;
+            }
+            self.observations[self.observation_index as usize] = Observation {
+                timestamp: self.last_update,
+                price_cumulative_x64: self.price_cumulative_x64,
+            };
         }
     }
+
+    /// Time weighted price (Q64.64) over at least the last `window` seconds, with the seconds actually covered.
+    /// None if the oracle has no observation that old.
+    pub fn get_twap(&self, now: i64, window: u32) -> Option<(u128, i64)> {
+        if self.last_update == 0 || now < self.last_update {
+            return None;
+        }
+        if window == 0 {
+            return Some((self.last_price_x64, 0));
+        }
+
+        let elapsed = (now - self.last_update) as u128;
+        let cumulative_now = self.price_cumulative_x64.wrapping_add(self.last_price_x64.wrapping_mul(elapsed));
+
+        let start = self
+            .observations
+            .iter()
+            .filter(|observation| observation.timestamp != 0 && observation.timestamp <= now - window as i64)
+            .max_by_key(|observation| observation.timestamp)?;
+
+        let seconds = now - start.timestamp;
+        Some((cumulative_now.wrapping_sub(start.price_cumulative_x64) / seconds as u128, seconds))
+    }
+}
+
+/// Return data of GetTWAP
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TWAPData {
+    pub price_x64: u128, // SOL lamports per raw base unit as Q64.64
+    pub seconds: i64,    // seconds the average covers, at least the requested window
+    pub price: f64,      // SOL per whole base token
+}
+
+/// SOL lamports per raw base unit as Q64.64, the price the oracle accumulates
+pub fn get_price_x64(quote_amount: u64, base_amount: u64) -> Option<u128> {
+    if base_amount == 0 {
+        return None;
+    }
+    Some(((quote_amount as u128) << 64) / base_amount as u128)
+}
+
+/// Price of one whole base token in whole quote tokens
+pub fn get_price(amm_base_amount: u64, amm_quote_amount: u64, base_decimals: u8, quote_decimals: u8) -> f64 {
+    let base_float_amount = amm_base_amount as f64 / 10_f64.powi(base_decimals as i32);
+    let quote_float_amount = amm_quote_amount as f64 / 10_f64.powi(quote_decimals as i32);
+
+    quote_float_amount / base_float_amount
 }
 
 pub fn get_amm_seeds(base_token_mint: Pubkey, quote_token_mint: Pubkey, amm_seed_keys: &mut Vec<Pubkey>) {
@@ -52,6 +202,14 @@ pub fn get_amm_seeds(base_token_mint: Pubkey, quote_token_mint: Pubkey, amm_seed
     }
 }
 
+/// Seeds, address and bump of the Cook AMM pool pairing `base_mint` with wrapped SOL
+pub fn get_cook_amm(base_mint: &Pubkey, program_id: &Pubkey) -> (Vec<Pubkey>, Pubkey, u8) {
+    let mut amm_seed_keys: Vec<Pubkey> = Vec::new();
+    get_amm_seeds(*base_mint, accounts::wrapped_sol_mint_account::ID, &mut amm_seed_keys);
+    let (amm, bump) = Pubkey::find_program_address(&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM"], program_id);
+    (amm_seed_keys, amm, bump)
+}
+
 pub fn create_amm(
     _user: &AccountInfo,
     _amm_pool: &AccountInfo,
@@ -72,6 +230,7 @@ pub fn create_amm(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn init_cook_amm_data<'a>(
     user: &AccountInfo<'a>,
     amm: &AccountInfo<'a>,
@@ -80,6 +239,7 @@ pub fn init_cook_amm_data<'a>(
     amm_seed_keys: &[Pubkey],
     amm_provider_bytes: &[u8],
     system_program: &AccountInfo<'a>,
+    plugins: Vec<AMMPlugin>,
 ) -> ProgramResult {
     use solana_program::{msg, program_error::ProgramError, program::{invoke, invoke_signed}, rent::Rent, sysvar::Sysvar, system_instruction};
     use borsh::BorshSerialize;
@@ -106,9 +266,9 @@ pub fn init_cook_amm_data<'a>(
     msg!("🔨 Creating AMM account using create_account (simplest approach)...");
     
     // Calculate AMM account size - use fixed size for simplicity
-    // AMM struct: 32 + 32 + 32 + 32 + 32 + 2 + 8 + 8 = 178 bytes
+    // AMM struct: 178 bytes of pool fields plus the TWAP oracle and the creator's plugins, see AMM::LEN
     // But use serialization to get exact size
-    let amm_instance = AMM::new(
+    let mut amm_instance = AMM::new(
         *user.key, // Placeholder - will be set properly later
         *user.key, // Placeholder
         *user.key, // Placeholder
@@ -116,12 +276,13 @@ pub fn init_cook_amm_data<'a>(
         *user.key, // Placeholder
         0, // fee_rate
     );
+    amm_instance.plugins = plugins;
     let account_size = match amm_instance.try_to_vec() {
         Ok(data) => data.len(),
         Err(_) => {
             // Fallback to calculated size if serialization fails
             msg!("⚠️ Serialization failed, using calculated size");
-            178 // 32*5 + 2 + 8 + 8
+            AMM::LEN
         }
     };
     
@@ -227,6 +388,13 @@ pub fn init_cook_amm_data<'a>(
             } else {
                 msg!("✅ AMM account has sufficient rent ({} >= {})", amm_lamports_after, rent_minimum);
             }
+            
+            if !amm_instance.plugins.is_empty() {
+                let mut amm_state = AMM::load(&amm.try_borrow_data()?)?;
+                amm_state.plugins = amm_instance.plugins;
+                amm_state.store(&mut amm.try_borrow_mut_data()?)?;
+                msg!("✅ AMM plugins: {:?}", amm_state.plugins);
+            }
         }
         Err(e) => {
             msg!("❌ Failed to create AMM account: {:?}", e);
@@ -245,8 +413,587 @@ pub fn init_cook_amm_data<'a>(
     Ok(())
 }
 
+/// Grows an AMM account created with an older layout to AMM::LEN, with the payer covering the extra rent
+pub fn resize_amm_data<'a>(payer: &AccountInfo<'a>, amm: &AccountInfo<'a>, system_program: &AccountInfo<'a>) -> ProgramResult {
+    resize_amm_account(payer, amm, system_program, AMM::LEN)
+}
+
+/// Grows an AMM account to at least `len` bytes, with the payer covering the extra rent
+pub fn resize_amm_account<'a>(payer: &AccountInfo<'a>, amm: &AccountInfo<'a>, system_program: &AccountInfo<'a>, len: usize) -> ProgramResult {
+    use solana_program::{rent::Rent, system_instruction, sysvar::Sysvar};
+
+    if amm.data_len() >= len {
+        return Ok(());
+    }
+
+    let required_lamports = Rent::get()?.minimum_balance(len);
+    let current_lamports = **amm.try_borrow_lamports()?;
+    if required_lamports > current_lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, amm.key, required_lamports - current_lamports),
+            &[payer.clone(), amm.clone(), system_program.clone()],
+        )?;
+    }
+
+    msg!("Resizing AMM account from {} to {} bytes", amm.data_len(), len);
+    amm.realloc(len, true)
+}
+
+/// Feeds a trade price into the pool's TWAP oracle
+pub fn record_oracle_price(amm: &AccountInfo, now: i64, price_x64: u128) -> ProgramResult {
+    let mut amm_data = amm.try_borrow_mut_data()?;
+    let mut amm_state = AMM::load(&amm_data)?;
+    amm_state.update_oracle(now, price_x64);
+    amm_state.store(&mut amm_data)
+}
+
 pub fn create_lp_mint(_user: &AccountInfo, _amm: &AccountInfo) -> ProgramResult {
     // Stub implementation
     Ok(())
 }
 
+
+/// Constant product output for `amount_in`, with `fee_bps` taken from the input
+pub fn get_amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u64) -> Option<u64> {
+    if reserve_in == 0 || reserve_out == 0 || fee_bps >= 10_000 {
+        return None;
+    }
+
+    let amount_in_after_fee = amount_in as u128 * (10_000 - fee_bps) as u128 / 10_000;
+    let amount_out = (reserve_out as u128 * amount_in_after_fee) / (reserve_in as u128 + amount_in_after_fee);
+    u64::try_from(amount_out).ok()
+}
+
+fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Amounts to deposit out of the desired (base, quote) so the pool ratio is kept.
+/// The excess of whichever side is over the ratio is left with the user.
+pub fn get_deposit_amounts(base_desired: u64, quote_desired: u64, reserve_base: u64, reserve_quote: u64) -> Option<(u64, u64)> {
+    if reserve_base == 0 || reserve_quote == 0 {
+        return Some((base_desired, quote_desired));
+    }
+
+    let quote_optimal = u64::try_from(base_desired as u128 * reserve_quote as u128 / reserve_base as u128).ok()?;
+    if quote_optimal <= quote_desired {
+        return Some((base_desired, quote_optimal));
+    }
+
+    let base_optimal = u64::try_from(quote_desired as u128 * reserve_base as u128 / reserve_quote as u128).ok()?;
+    Some((base_optimal, quote_desired))
+}
+
+/// Liquidity locked by reserves seeded before any deposit, which mint no LP tokens
+pub fn get_seeded_liquidity(reserve_base: u64, reserve_quote: u64) -> u64 {
+    u64::try_from(integer_sqrt(reserve_base as u128 * reserve_quote as u128)).unwrap_or(u64::MAX).max(MINIMUM_LIQUIDITY)
+}
+
+/// LP tokens minted for a deposit that the pool received, as (minted, new total liquidity).
+/// `total_liquidity` includes the locked minimum liquidity. Reserves seeded before any
+/// deposit (instant launch pools) are locked as the initial liquidity of the pool.
+pub fn get_liquidity_minted(base_in: u64, quote_in: u64, reserve_base: u64, reserve_quote: u64, total_liquidity: u64) -> Option<(u64, u64)> {
+    let (minted, total) = if total_liquidity == 0 && reserve_base == 0 && reserve_quote == 0 {
+        let liquidity = integer_sqrt(base_in as u128 * quote_in as u128) as u64;
+        (liquidity.checked_sub(MINIMUM_LIQUIDITY)?, liquidity)
+    } else {
+        if reserve_base == 0 || reserve_quote == 0 {
+            return None;
+        }
+        let supply = if total_liquidity == 0 {
+            get_seeded_liquidity(reserve_base, reserve_quote)
+        } else {
+            total_liquidity
+        };
+        let from_base = base_in as u128 * supply as u128 / reserve_base as u128;
+        let from_quote = quote_in as u128 * supply as u128 / reserve_quote as u128;
+        let minted = u64::try_from(from_base.min(from_quote)).ok()?;
+        (minted, supply.checked_add(minted)?)
+    };
+
+    if minted == 0 {
+        return None;
+    }
+    Some((minted, total))
+}
+
+/// Pro-rata share of both reserves for burning `lp_amount` out of `lp_supply`, as (base, quote)
+pub fn get_withdraw_amounts(lp_amount: u64, lp_supply: u64, reserve_base: u64, reserve_quote: u64) -> Option<(u64, u64)> {
+    if lp_amount == 0 || lp_amount > lp_supply {
+        return None;
+    }
+
+    let base = reserve_base as u128 * lp_amount as u128 / lp_supply as u128;
+    let quote = reserve_quote as u128 * lp_amount as u128 / lp_supply as u128;
+    Some((u64::try_from(base).ok()?, u64::try_from(quote).ok()?))
+}
+
+/// Reads the balance of a pool vault, checking it is a token account for `mint` owned by `owner`
+pub fn unpack_reserve(data: &[u8], owner: &Pubkey, mint: &Pubkey) -> Result<u64, ProgramError> {
+    let vault = StateWithExtensions::<Account>::unpack(data)?;
+    if vault.base.owner != *owner || vault.base.mint != *mint {
+        msg!("Vault is not a {} account owned by {}", mint, owner);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(vault.base.amount)
+}
+
+fn get_decimals(mint: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = mint.try_borrow_data()?;
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base.decimals)
+}
+
+/// Accounts of a Cook AMM venue, in the order they are passed to the program.
+/// Cook pools always pair a launch token (base) against wrapped SOL (quote).
+pub struct CookVenue<'a, 'b> {
+    pub amm: &'b AccountInfo<'a>,
+    pub amm_base: &'b AccountInfo<'a>,
+    pub amm_quote: &'b AccountInfo<'a>,
+}
+
+impl<'a, 'b> CookVenue<'a, 'b> {
+    pub const NUM_ACCOUNTS: usize = 3;
+
+    pub fn from_accounts(venue_accounts: &'b [AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        if venue_accounts.len() < Self::NUM_ACCOUNTS {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(CookVenue {
+            amm: &venue_accounts[0],
+            amm_base: &venue_accounts[1],
+            amm_quote: &venue_accounts[2],
+        })
+    }
+
+    /// Checks the pool accounts for the pair and returns the AMM seeds, bump and base mint
+    pub fn validate(&self, program_id: &Pubkey, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<(Vec<Pubkey>, u8, Pubkey), ProgramError> {
+        let wsol_mint = accounts::wrapped_sol_mint_account::ID;
+        let base_mint = if *output_mint == wsol_mint {
+            *input_mint
+        } else if *input_mint == wsol_mint {
+            *output_mint
+        } else {
+            msg!("Cook AMM pools only trade against wrapped SOL");
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        let mut amm_seed_keys: Vec<Pubkey> = Vec::new();
+        get_amm_seeds(base_mint, wsol_mint, &mut amm_seed_keys);
+        let (expected_amm, amm_bump) =
+            Pubkey::find_program_address(&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM"], program_id);
+        if self.amm.key != &expected_amm || self.amm.owner != program_id {
+            msg!("expected Cook AMM {}", expected_amm);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (expected_amm_base, _) = Pubkey::find_program_address(&[&self.amm.key.to_bytes(), b"amm_base"], program_id);
+        if self.amm_base.key != &expected_amm_base {
+            msg!("expected amm_base {}", expected_amm_base);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // the WSOL vault is the AMM's associated token account under whichever token program owns the native mint
+        if self.amm_quote.owner != &spl_token::id() && self.amm_quote.owner != &spl_token_2022::id() {
+            msg!("amm_quote is not a token account");
+            return Err(ProgramError::IllegalOwner);
+        }
+        let expected_amm_quote =
+            spl_associated_token_account::get_associated_token_address_with_program_id(self.amm.key, &wsol_mint, self.amm_quote.owner);
+        if self.amm_quote.key != &expected_amm_quote {
+            msg!("expected amm_quote {}", expected_amm_quote);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok((amm_seed_keys, amm_bump, base_mint))
+    }
+
+    /// Pool reserves as (base, quote), read from the vault token accounts
+    pub fn reserves(&self, base_mint: &Pubkey) -> Result<(u64, u64), ProgramError> {
+        let base = unpack_reserve(&self.amm_base.try_borrow_data()?, self.amm.key, base_mint)?;
+        let quote = unpack_reserve(&self.amm_quote.try_borrow_data()?, self.amm.key, &accounts::wrapped_sol_mint_account::ID)?;
+        Ok((base, quote))
+    }
+
+    fn reserves_in_out(&self, base_mint: &Pubkey, input_mint: &Pubkey) -> Result<(u64, u64), ProgramError> {
+        let (base, quote) = self.reserves(base_mint)?;
+        if input_mint == base_mint {
+            Ok((base, quote))
+        } else {
+            Ok((quote, base))
+        }
+    }
+
+    fn vaults_in_out(&self, base_mint: &Pubkey, input_mint: &Pubkey) -> (&'b AccountInfo<'a>, &'b AccountInfo<'a>) {
+        if input_mint == base_mint {
+            (self.amm_base, self.amm_quote)
+        } else {
+            (self.amm_quote, self.amm_base)
+        }
+    }
+
+    /// Constant product output for `amount_in`, priced in chunks while the pool's liquidity scaling is active.
+    /// With `persist` the plugin is switched off in the AMM state once the pool reaches its threshold.
+    fn amount_out(&self, base_mint: &Pubkey, input_mint: &Pubkey, amount_in: u64, reserve_in: u64, reserve_out: u64, persist: bool) -> Result<u64, ProgramError> {
+        let mut amm_state = AMM::load(&self.amm.try_borrow_data()?)?;
+        let plugins = amm_state.plugins.clone();
+
+        let side = if input_mint == base_mint { 1 } else { 0 };
+        let (quote_reserve, base_reserve) = if side == 0 { (reserve_in, reserve_out) } else { (reserve_out, reserve_in) };
+
+        let amount_out = match amm_plugins::check_liquidity_scaling(&mut amm_state.plugins, quote_reserve) {
+            Some(liquidity_plugin) => {
+                amm_plugins::calculate_chunked_output(amount_in, side, quote_reserve, base_reserve, COOK_AMM_FEE_BPS as u16, &liquidity_plugin)
+            }
+            None => get_amount_out(amount_in, reserve_in, reserve_out, COOK_AMM_FEE_BPS).ok_or(ProgramError::ArithmeticOverflow)?,
+        };
+
+        if persist && amm_state.plugins != plugins {
+            amm_state.store(&mut self.amm.try_borrow_mut_data()?)?;
+        }
+
+        Ok(amount_out)
+    }
+
+    /// Amount the user would receive for `amount_in`, after pool and token 2022 transfer fees
+    pub fn quote(&self, program_id: &Pubkey, input_mint: &AccountInfo<'a>, output_mint: &AccountInfo<'a>, amount_in: u64) -> Result<u64, ProgramError> {
+        let (_, _, base_mint) = self.validate(program_id, input_mint.key, output_mint.key)?;
+        let (reserve_in, reserve_out) = self.reserves_in_out(&base_mint, input_mint.key)?;
+
+        let amount_in = token::get_amount_post_transfer_fee(amount_in, input_mint)?;
+        let amount_out = self.amount_out(&base_mint, input_mint.key, amount_in, reserve_in, reserve_out, false)?;
+        token::get_amount_post_transfer_fee(amount_out, output_mint)
+    }
+
+    /// Trades `amount_in` against the pool: the user pays into one vault and the AMM pays out of the other.
+    /// The output is priced from the amount the vault actually received.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        &self,
+        program_id: &Pubkey,
+        user: &AccountInfo<'a>,
+        input_mint: &AccountInfo<'a>,
+        output_mint: &AccountInfo<'a>,
+        user_input_account: &AccountInfo<'a>,
+        user_output_account: &AccountInfo<'a>,
+        input_token_program: &AccountInfo<'a>,
+        output_token_program: &AccountInfo<'a>,
+        amount_in: u64,
+    ) -> Result<u64, ProgramError> {
+        let (amm_seed_keys, amm_bump, base_mint) = self.validate(program_id, input_mint.key, output_mint.key)?;
+        let (reserve_in, reserve_out) = self.reserves_in_out(&base_mint, input_mint.key)?;
+        let (vault_in, vault_out) = self.vaults_in_out(&base_mint, input_mint.key);
+
+        invoke(
+            &spl_token_2022::instruction::transfer_checked(
+                input_token_program.key,
+                user_input_account.key,
+                input_mint.key,
+                vault_in.key,
+                user.key,
+                &[],
+                amount_in,
+                get_decimals(input_mint)?,
+            )?,
+            &[user_input_account.clone(), input_mint.clone(), vault_in.clone(), user.clone(), input_token_program.clone()],
+        )?;
+
+        let received = token::get_token_balance(vault_in).saturating_sub(reserve_in);
+        let amount_out = self.amount_out(&base_mint, input_mint.key, received, reserve_in, reserve_out, true)?;
+        if amount_out == 0 {
+            msg!("Cook AMM swap output is zero");
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        invoke_signed(
+            &spl_token_2022::instruction::transfer_checked(
+                output_token_program.key,
+                vault_out.key,
+                output_mint.key,
+                user_output_account.key,
+                self.amm.key,
+                &[],
+                amount_out,
+                get_decimals(output_mint)?,
+            )?,
+            &[vault_out.clone(), output_mint.clone(), user_output_account.clone(), self.amm.clone(), output_token_program.clone()],
+            &[&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM", &[amm_bump]]],
+        )?;
+
+        Ok(amount_out)
+    }
+
+    /// Checks the pool accounts for a launch token and returns its (base, quote) reserves
+    pub fn checked_reserves(&self, program_id: &Pubkey, base_mint: &Pubkey) -> Result<(u64, u64), ProgramError> {
+        self.validate(program_id, base_mint, &accounts::wrapped_sol_mint_account::ID)?;
+        self.reserves(base_mint)
+    }
+
+    /// Total LP supply of the pool including the locked minimum liquidity
+    pub fn total_liquidity(&self) -> Result<u64, ProgramError> {
+        Ok(AMM::load(&self.amm.try_borrow_data()?)?.total_liquidity)
+    }
+
+    pub fn set_total_liquidity(&self, total_liquidity: u64) -> ProgramResult {
+        let mut amm_data = self.amm.try_borrow_mut_data()?;
+        let mut amm_state = AMM::load(&amm_data)?;
+        amm_state.total_liquidity = total_liquidity;
+        amm_state.store(&mut amm_data)
+    }
+
+    /// Mints `amount` LP tokens of the pool to `destination`, signed by the AMM
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_liquidity(
+        &self,
+        program_id: &Pubkey,
+        base_mint: &Pubkey,
+        lp_token_mint: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        lp_token_program: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let (amm_seed_keys, amm_bump, _) = self.validate(program_id, base_mint, &accounts::wrapped_sol_mint_account::ID)?;
+        invoke_signed(
+            &spl_token_2022::instruction::mint_to_checked(
+                lp_token_program.key,
+                lp_token_mint.key,
+                destination.key,
+                self.amm.key,
+                &[],
+                amount,
+                get_decimals(lp_token_mint)?,
+            )?,
+            &[lp_token_mint.clone(), destination.clone(), self.amm.clone(), lp_token_program.clone()],
+            &[&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM", &[amm_bump]]],
+        )
+    }
+
+    /// Pays `base_out` and `quote_out` from the pool vaults to the user, signed by the AMM
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw(
+        &self,
+        program_id: &Pubkey,
+        base_mint: &AccountInfo<'a>,
+        quote_mint: &AccountInfo<'a>,
+        user_base_account: &AccountInfo<'a>,
+        user_quote_account: &AccountInfo<'a>,
+        base_token_program: &AccountInfo<'a>,
+        quote_token_program: &AccountInfo<'a>,
+        base_out: u64,
+        quote_out: u64,
+    ) -> ProgramResult {
+        let (amm_seed_keys, amm_bump, _) = self.validate(program_id, base_mint.key, quote_mint.key)?;
+        let amm_signer: &[&[u8]] = &[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM", &[amm_bump]];
+
+        for (vault, mint, destination, token_program, amount) in [
+            (self.amm_base, base_mint, user_base_account, base_token_program, base_out),
+            (self.amm_quote, quote_mint, user_quote_account, quote_token_program, quote_out),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            invoke_signed(
+                &spl_token_2022::instruction::transfer_checked(
+                    token_program.key,
+                    vault.key,
+                    mint.key,
+                    destination.key,
+                    self.amm.key,
+                    &[],
+                    amount,
+                    get_decimals(mint)?,
+                )?,
+                &[vault.clone(), mint.clone(), destination.clone(), self.amm.clone(), token_program.clone()],
+                &[amm_signer],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Burns `amount` of the base tokens held by the pool, signed by the AMM
+    pub fn burn_base(&self, program_id: &Pubkey, base_mint: &AccountInfo<'a>, base_token_program: &AccountInfo<'a>, amount: u64) -> ProgramResult {
+        let (amm_seed_keys, amm_bump, _) = self.validate(program_id, base_mint.key, &accounts::wrapped_sol_mint_account::ID)?;
+        invoke_signed(
+            &spl_token_2022::instruction::burn_checked(
+                base_token_program.key,
+                self.amm_base.key,
+                base_mint.key,
+                self.amm.key,
+                &[],
+                amount,
+                get_decimals(base_mint)?,
+            )?,
+            &[self.amm_base.clone(), base_mint.clone(), self.amm.clone(), base_token_program.clone()],
+            &[&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM", &[amm_bump]]],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::program_pack::Pack;
+    use spl_token_2022::state::AccountState;
+
+    fn vault_data(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; Account::LEN];
+        Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn test_amount_out() {
+        // 0.25% fee on a 1 SOL : 1M token pool
+        let out = get_amount_out(10_000_000, 1_000_000_000, 1_000_000_000_000, COOK_AMM_FEE_BPS).unwrap();
+        assert_eq!(out, 9_876_482_091);
+        assert_eq!(get_amount_out(1, 0, 1_000, COOK_AMM_FEE_BPS), None);
+        assert!(get_amount_out(u64::MAX, 1_000, 1_000, COOK_AMM_FEE_BPS).unwrap() < 1_000);
+    }
+
+    #[test]
+    fn test_first_deposit_locks_minimum_liquidity() {
+        assert_eq!(integer_sqrt(1_000_000_000_000_000_000), 1_000_000_000);
+        assert_eq!(integer_sqrt(99), 9);
+
+        // empty pool: sqrt of the deposit with the minimum locked away
+        assert_eq!(get_deposit_amounts(4_000_000, 1_000_000, 0, 0), Some((4_000_000, 1_000_000)));
+        assert_eq!(get_liquidity_minted(4_000_000, 1_000_000, 0, 0, 0), Some((2_000_000 - MINIMUM_LIQUIDITY, 2_000_000)));
+        assert_eq!(get_liquidity_minted(1_000, 1_000, 0, 0, 0), None);
+    }
+
+    #[test]
+    fn test_deposit_keeps_pool_ratio() {
+        let amm = Pubkey::new_unique();
+        let base_mint = Pubkey::new_unique();
+        let wsol_mint = accounts::wrapped_sol_mint_account::ID;
+        let reserve_base = unpack_reserve(&vault_data(&amm, &base_mint, 4_000_000), &amm, &base_mint).unwrap();
+        let reserve_quote = unpack_reserve(&vault_data(&amm, &wsol_mint, 1_000_000), &amm, &wsol_mint).unwrap();
+        let total_liquidity = 2_000_000;
+
+        // too much quote offered: base is used in full and the extra quote stays with the user
+        let (base_in, quote_in) = get_deposit_amounts(400_000, 500_000, reserve_base, reserve_quote).unwrap();
+        assert_eq!((base_in, quote_in), (400_000, 100_000));
+        assert_eq!(
+            get_liquidity_minted(base_in, quote_in, reserve_base, reserve_quote, total_liquidity),
+            Some((200_000, 2_200_000))
+        );
+
+        // too much base offered
+        assert_eq!(get_deposit_amounts(4_000_000, 10_000, reserve_base, reserve_quote), Some((40_000, 10_000)));
+
+        // an off ratio deposit only earns the share of its smaller side
+        assert_eq!(
+            get_liquidity_minted(4_000_000, 10_000, reserve_base, reserve_quote, total_liquidity),
+            Some((20_000, 2_020_000))
+        );
+    }
+
+    #[test]
+    fn test_seeded_pool_locks_existing_reserves() {
+        // reserves without any LP supply belong to nobody, they become the locked liquidity
+        assert_eq!(get_liquidity_minted(400_000, 100_000, 4_000_000, 1_000_000, 0), Some((200_000, 2_200_000)));
+        assert_eq!(get_liquidity_minted(400_000, 100_000, 4_000_000, 0, 0), None);
+    }
+
+    #[test]
+    fn test_amm_layout() {
+        let amm = AMM::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), 25);
+        assert_eq!(borsh::to_vec(&amm).unwrap().len(), AMM::LEN);
+
+        // pools created before the oracle keep their fields and start with an empty oracle
+        let mut old_account = borsh::to_vec(&amm).unwrap()[..178].to_vec();
+        let mut loaded = AMM::load(&old_account).unwrap();
+        assert_eq!(loaded.fee_rate, 25);
+        assert_eq!(loaded.last_update, 0);
+
+        loaded.total_liquidity = 7;
+        loaded.update_oracle(1_000, 1);
+        loaded.store(&mut old_account).unwrap();
+        assert_eq!(AMM::load(&old_account).unwrap().total_liquidity, 7);
+        assert_eq!(AMM::load(&old_account).unwrap().last_update, 0);
+
+        // plugins follow the fixed fields and need the account grown to fit them
+        let mut with_plugin = amm.clone();
+        with_plugin.plugins.push(AMMPlugin::LiquidityScaling(amm_plugins::LiquidityScaling::default()));
+        assert_eq!(with_plugin.size().unwrap(), AMM::LEN + 12);
+        let mut account = vec![0u8; AMM::LEN];
+        assert_eq!(with_plugin.store(&mut account), Err(ProgramError::AccountDataTooSmall));
+        account.resize(with_plugin.size().unwrap(), 0);
+        with_plugin.store(&mut account).unwrap();
+        assert_eq!(AMM::load(&account).unwrap().plugins, with_plugin.plugins);
+        assert!(AMM::load(&old_account).unwrap().plugins.is_empty());
+    }
+
+    #[test]
+    fn test_twap() {
+        let mut amm = AMM::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default(), 25);
+        let one = get_price_x64(1, 1).unwrap();
+        assert_eq!(amm.get_twap(1_000, 60), None);
+
+        amm.update_oracle(1_000, one);
+        amm.update_oracle(1_600, 3 * one);
+        // a price pushed up for one block barely moves the average
+        amm.update_oracle(1_900, 1_000 * one);
+        amm.update_oracle(1_901, 3 * one);
+
+        assert_eq!(amm.get_twap(1_901, 0), Some((3 * one, 0)));
+        // 600s at 1, 300s at 3, 1s at 1000, 99s at 3
+        assert_eq!(amm.get_twap(2_000, 1_000), Some((2_797 * one / 1_000, 1_000)));
+        // the window starts at the newest observation old enough to cover it
+        assert_eq!(amm.get_twap(2_000, 200), Some((((300 * 3 + 1_000 + 99 * 3) * one) / 400, 400)));
+        assert_eq!(amm.get_twap(2_000, 1_001), None);
+
+        // observations are only stored every OBSERVATION_INTERVAL seconds and the oldest are overwritten
+        for step in 1..=(NUM_OBSERVATIONS as i64 + 2) {
+            amm.update_oracle(2_000 + step * OBSERVATION_INTERVAL, one);
+        }
+        assert!(amm.observations.iter().all(|observation| observation.timestamp > 1_900));
+        assert_eq!(amm.get_twap(amm.last_update, (NUM_OBSERVATIONS as u32 - 1) * OBSERVATION_INTERVAL as u32).unwrap().0, one);
+    }
+
+    #[test]
+    fn test_price() {
+        assert_eq!(get_price(2_000_000_000, 1_000_000_000, 9, 9), 0.5);
+        assert_eq!(get_price(1_000_000, 1_000_000_000, 6, 9), 1.0);
+        assert_eq!(get_price_x64(3, 2), Some(3u128 << 63));
+        assert_eq!(get_price_x64(1, 0), None);
+    }
+
+    #[test]
+    fn test_withdraw_amounts() {
+        // a quarter of the supply takes a quarter of each side, rounded down
+        assert_eq!(get_withdraw_amounts(250, 1_000, 1_000_001, 4_003), Some((250_000, 1_000)));
+        assert_eq!(get_withdraw_amounts(1_000, 1_000, 1_000_001, 4_003), Some((1_000_001, 4_003)));
+        assert_eq!(get_withdraw_amounts(0, 1_000, 1_000, 1_000), None);
+        assert_eq!(get_withdraw_amounts(1_001, 1_000, 1_000, 1_000), None);
+        assert_eq!(get_withdraw_amounts(u64::MAX, u64::MAX, u64::MAX, 1), Some((u64::MAX, 1)));
+    }
+
+    #[test]
+    fn test_unpack_reserve() {
+        let amm = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let data = vault_data(&amm, &mint, 42);
+
+        assert_eq!(unpack_reserve(&data, &amm, &mint).unwrap(), 42);
+        assert!(unpack_reserve(&data, &Pubkey::new_unique(), &mint).is_err());
+        assert!(unpack_reserve(&data, &amm, &Pubkey::new_unique()).is_err());
+        assert!(unpack_reserve(&data[..10], &amm, &mint).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+/**
+ * Optional Cook AMM plugins, kept as a borsh Vec<AMMPlugin> at the end of the AMM account.
+ *
+ * LiquidityScaling protects thin pools right after launch. While the quote reserve is below
+ * `threshold`, trades are priced in chunks with the input scaled by
+ * MIN(1, quote_reserve * scalar / 10 / threshold), so early buyers get fewer tokens (and sellers
+ * more SOL) until the pool has built up liquidity. The plugin switches itself off for good the
+ * first time the quote reserve reaches the threshold.
+ */
+use std::collections::HashMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, native_token::LAMPORTS_PER_SOL, program_error::ProgramError};
+
+use crate::amm::{self, AMM};
+
+/// Scalar and threshold creators get when opting in at launch
+pub const DEFAULT_LIQUIDITY_SCALAR: u16 = 15;
+pub const DEFAULT_LIQUIDITY_THRESHOLD: u64 = 10 * LAMPORTS_PER_SOL;
+
+/// Smallest fraction of the input a trade is priced with, however thin the pool
+const MIN_SCALING: f64 = 0.0002;
+
+// when liquidity is low the number of tokens a user gets is reduced to help build up liquidity
+// they get only MIN(1, scalar * original_output / threshold)
+#[derive(Copy, Clone, BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq)]
+pub struct LiquidityScaling {
+    pub scalar: u16,
+    pub threshold: u64,
+    pub active: u8,
+}
+
+impl Default for LiquidityScaling {
+    fn default() -> Self {
+        LiquidityScaling {
+            scalar: DEFAULT_LIQUIDITY_SCALAR,
+            threshold: DEFAULT_LIQUIDITY_THRESHOLD,
+            active: 1,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Hash, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum AMMPluginType {
+    LiquidityScaling,
+}
+
+/// Definition of the plugin variants
+#[repr(C)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum AMMPlugin {
+    /// Scaled down fills while the quote reserve is below a threshold
+    LiquidityScaling(LiquidityScaling),
+}
+
+impl From<&AMMPlugin> for AMMPluginType {
+    fn from(plugin: &AMMPlugin) -> Self {
+        match plugin {
+            AMMPlugin::LiquidityScaling(_) => AMMPluginType::LiquidityScaling,
+        }
+    }
+}
+
+pub fn get_amm_plugin_map(plugin_vec: &[AMMPlugin]) -> HashMap<AMMPluginType, AMMPlugin> {
+    let mut map: HashMap<AMMPluginType, AMMPlugin> = HashMap::new();
+
+    for plugin in plugin_vec.iter().cloned() {
+        map.insert(AMMPluginType::from(&plugin), plugin);
+    }
+
+    map
+}
+
+/// The pool's liquidity scaling if it is still active, switching it off once `quote_reserve` reaches the threshold
+pub fn check_liquidity_scaling(plugins: &mut [AMMPlugin], quote_reserve: u64) -> Option<LiquidityScaling> {
+    let AMMPlugin::LiquidityScaling(liquidity_plugin) = plugins
+        .iter_mut()
+        .find(|plugin| AMMPluginType::from(&**plugin) == AMMPluginType::LiquidityScaling)?;
+
+    if liquidity_plugin.active == 0 {
+        return None;
+    }
+
+    if quote_reserve >= liquidity_plugin.threshold {
+        msg!("Pool reached {} lamports of liquidity, switching off liquidity scaling", liquidity_plugin.threshold);
+        liquidity_plugin.active = 0;
+        return None;
+    }
+
+    Some(*liquidity_plugin)
+}
+
+/// Fraction of the input a trade is priced with at the given quote reserve
+pub fn get_scaling_factor(quote_amount: u64, liquidity_plugin: &LiquidityScaling) -> f64 {
+    if quote_amount >= liquidity_plugin.threshold {
+        return 1.0;
+    }
+
+    let scaling = (quote_amount as f64 * liquidity_plugin.scalar as f64 / 10.0) / (liquidity_plugin.threshold as f64);
+    scaling.clamp(MIN_SCALING, 1.0)
+}
+
+/// Calculate output amount for a trade using chunked calculation, rescaling after every chunk as the reserve grows.
+/// side: 0 for buy (input quote, output base), 1 for sell (input base, output quote). fee in basis points.
+pub fn calculate_chunked_output(
+    input_amount: u64,
+    side: u8,
+    amm_quote_amount: u64,
+    amm_base_amount: u64,
+    fee: u16,
+    liquidity_plugin: &LiquidityScaling,
+) -> u64 {
+    if input_amount == 0 {
+        return 0;
+    }
+
+    let max_chunks: u64 = 50;
+    let min_chunk_size: u64 = if side == 0 { 100 } else { 100000 };
+    let chunks = std::cmp::min(input_amount / min_chunk_size + 1, max_chunks);
+
+    let chunk_size = (input_amount as f64) / (chunks as f64);
+    let mut current_quote = amm_quote_amount;
+    let mut current_base = amm_base_amount;
+    let mut total_output: u64 = 0;
+
+    for _ in 0..chunks {
+        let scaling = get_scaling_factor(current_quote, liquidity_plugin);
+        let input_fees = chunk_size * (fee as f64) / 100.0 / 100.0;
+        let input_ex_fees = chunk_size - input_fees;
+        if side == 0 {
+            // Buy: input quote, output base
+            let effective_input = input_ex_fees * scaling;
+            let output = (effective_input * (current_base as f64) / ((current_quote as f64) + effective_input)) as u64;
+
+            total_output = total_output.saturating_add(output);
+            current_quote = current_quote.saturating_add(chunk_size as u64);
+            current_base = current_base.saturating_sub(output);
+        } else {
+            // Sell: input base, output quote
+            let effective_input = input_ex_fees / scaling;
+            let output = (effective_input * (current_quote as f64) / (effective_input + (current_base as f64))) as u64;
+
+            total_output = total_output.saturating_add(output);
+            current_quote = current_quote.saturating_sub(output);
+            current_base = current_base.saturating_add(chunk_size as u64);
+        }
+    }
+
+    total_output
+}
+
+/// Scales an output that is not priced from the pool reserves, such as a bonding curve buy
+pub fn scale_output(amount_out: u64, quote_reserve: u64, liquidity_plugin: &LiquidityScaling) -> u64 {
+    (amount_out as f64 * get_scaling_factor(quote_reserve, liquidity_plugin)) as u64
+}
+
+/// Adds a plugin to a pool, growing the AMM account to fit it with the payer covering the extra rent
+pub fn add_amm_plugin<'a>(payer: &AccountInfo<'a>, amm_account: &AccountInfo<'a>, system_program: &AccountInfo<'a>, plugin: AMMPlugin) -> ProgramResult {
+    let mut amm_state = AMM::load(&amm_account.try_borrow_data()?)?;
+    if get_amm_plugin_map(&amm_state.plugins).contains_key(&AMMPluginType::from(&plugin)) {
+        msg!("Pool already has the {:?} plugin", AMMPluginType::from(&plugin));
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    amm_state.plugins.push(plugin);
+    amm::resize_amm_account(payer, amm_account, system_program, amm_state.size()?)?;
+    amm_state.store(&mut amm_account.try_borrow_mut_data()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaling_factor() {
+        let plugin = LiquidityScaling::default();
+        assert_eq!(get_scaling_factor(plugin.threshold, &plugin), 1.0);
+        assert_eq!(get_scaling_factor(0, &plugin), MIN_SCALING);
+        // 1 SOL of 10 at a scalar of 1.5 prices trades with 15% of their input
+        assert!((get_scaling_factor(LAMPORTS_PER_SOL, &plugin) - 0.15).abs() < 1e-9);
+        assert_eq!(get_scaling_factor(7 * LAMPORTS_PER_SOL, &plugin), 1.0);
+    }
+
+    #[test]
+    fn test_chunked_output() {
+        let plugin = LiquidityScaling::default();
+        let base = 1_000_000_000_000;
+        let buy = LAMPORTS_PER_SOL / 10;
+
+        // above the threshold the chunks add up to a plain constant product trade, less the fees kept between chunks
+        let deep_quote = 20 * LAMPORTS_PER_SOL;
+        let plain = amm::get_amount_out(buy, deep_quote, base, 25).unwrap();
+        let chunked = calculate_chunked_output(buy, 0, deep_quote, base, 25, &plugin);
+        assert!(chunked <= plain && chunked > plain - plain / 1000);
+
+        // a thin pool hands out a fraction of the tokens and pays sellers back for it
+        let thin_quote = LAMPORTS_PER_SOL;
+        let plain = amm::get_amount_out(buy, thin_quote, base, 25).unwrap();
+        let scaled = calculate_chunked_output(buy, 0, thin_quote, base, 25, &plugin);
+        assert!(scaled < plain / 5);
+        assert!(calculate_chunked_output(scaled, 1, thin_quote, base, 25, &plugin) > amm::get_amount_out(scaled, base, thin_quote, 25).unwrap());
+
+        assert_eq!(calculate_chunked_output(0, 0, thin_quote, base, 25, &plugin), 0);
+    }
+
+    #[test]
+    fn test_scaling_switches_off_at_threshold() {
+        let mut plugins = vec![AMMPlugin::LiquidityScaling(LiquidityScaling::default())];
+        assert_eq!(check_liquidity_scaling(&mut plugins, LAMPORTS_PER_SOL), Some(LiquidityScaling::default()));
+        assert_eq!(check_liquidity_scaling(&mut plugins, DEFAULT_LIQUIDITY_THRESHOLD), None);
+
+        // once off it stays off, even if liquidity is pulled again
+        assert_eq!(check_liquidity_scaling(&mut plugins, LAMPORTS_PER_SOL), None);
+        assert_eq!(plugins, vec![AMMPlugin::LiquidityScaling(LiquidityScaling { active: 0, ..LiquidityScaling::default() })]);
+
+        assert_eq!(check_liquidity_scaling(&mut [], 0), None);
+    }
+}
@@ -0,0 +1,260 @@
+/**
+ * OHLCV candles for Cook AMM pools, kept in one fixed size price_data account per AMM.
+ *
+ * PDA seeds: [amm, 0u32 (little endian), b"TimeSeries"]
+ *
+ * Layout (little endian, no borsh length prefixes so indexers can read it directly):
+ *   0    u8   account_type (AccountType::TimeSeries)
+ *   1    u8   layout version (CANDLES_VERSION)
+ *   2    RESOLUTIONS.len() rings of RING_LEN bytes, in RESOLUTIONS order:
+ *          0  u32  resolution in seconds
+ *          4  u16  head, slot of the newest candle
+ *          6  u16  number of candles written, at most CANDLES_PER_RESOLUTION
+ *          8  CANDLES_PER_RESOLUTION slots of CANDLE_LEN bytes:
+ *               0   i64  bucket start (unix seconds, multiple of the resolution)
+ *               8   f32  open
+ *               12  f32  high
+ *               16  f32  low
+ *               20  f32  close
+ *               24  f32  volume (base token, ui units)
+ *
+ * Prices are SOL per whole token. Once a ring is full the oldest candle is overwritten.
+ */
+use solana_program::{account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar};
+
+use crate::{amm, state, utils};
+
+pub const CANDLES_VERSION: u8 = 1;
+pub const RESOLUTIONS: [u32; 4] = [60, 15 * 60, 60 * 60, 24 * 60 * 60];
+pub const CANDLES_PER_RESOLUTION: usize = 64;
+pub const CANDLE_LEN: usize = 28;
+const RING_HEADER_LEN: usize = 8;
+pub const RING_LEN: usize = RING_HEADER_LEN + CANDLES_PER_RESOLUTION * CANDLE_LEN;
+const HEADER_LEN: usize = 2;
+pub const PRICE_DATA_LEN: usize = HEADER_LEN + RESOLUTIONS.len() * RING_LEN;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub timestamp: i64,
+    pub open: f32,
+    pub high: f32,
+    pub low: f32,
+    pub close: f32,
+    pub volume: f32,
+}
+
+impl Candle {
+    fn read(data: &[u8]) -> Self {
+        let f = |offset: usize| f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        Candle {
+            timestamp: i64::from_le_bytes(data[0..8].try_into().unwrap()),
+            open: f(8),
+            high: f(12),
+            low: f(16),
+            close: f(20),
+            volume: f(24),
+        }
+    }
+
+    fn write(&self, data: &mut [u8]) {
+        data[0..8].copy_from_slice(&self.timestamp.to_le_bytes());
+        for (offset, value) in [(8, self.open), (12, self.high), (16, self.low), (20, self.close), (24, self.volume)] {
+            data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+pub fn get_price_data_address(amm: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&amm.to_bytes(), &0u32.to_le_bytes(), b"TimeSeries"], program_id)
+}
+
+fn ring_offset(index: usize) -> usize {
+    HEADER_LEN + index * RING_LEN
+}
+
+/// Writes an empty store into a freshly created account
+pub fn init_price_data(data: &mut [u8]) -> ProgramResult {
+    if data.len() < PRICE_DATA_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    data[..PRICE_DATA_LEN].fill(0);
+    data[0] = state::AccountType::TimeSeries as u8;
+    data[1] = CANDLES_VERSION;
+    for (index, resolution) in RESOLUTIONS.iter().enumerate() {
+        let offset = ring_offset(index);
+        data[offset..offset + 4].copy_from_slice(&resolution.to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Folds a trade into the current candle of every resolution, opening a new one when its bucket has passed
+pub fn record_trade(data: &mut [u8], now: i64, price: f32, volume: f32) -> ProgramResult {
+    if data.len() < PRICE_DATA_LEN || data[0] != state::AccountType::TimeSeries as u8 || data[1] != CANDLES_VERSION {
+        msg!("price data account is not a version {} candle store", CANDLES_VERSION);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    for (index, resolution) in RESOLUTIONS.iter().enumerate() {
+        let offset = ring_offset(index);
+        let head = u16::from_le_bytes([data[offset + 4], data[offset + 5]]) as usize;
+        let count = u16::from_le_bytes([data[offset + 6], data[offset + 7]]) as usize;
+        let bucket = now - now.rem_euclid(*resolution as i64);
+
+        let slot = |slot: usize| offset + RING_HEADER_LEN + slot * CANDLE_LEN;
+        let current = Candle::read(&data[slot(head)..slot(head) + CANDLE_LEN]);
+
+        if count > 0 && current.timestamp == bucket {
+            let updated = Candle {
+                high: current.high.max(price),
+                low: current.low.min(price),
+                close: price,
+                volume: current.volume + volume,
+                ..current
+            };
+            updated.write(&mut data[slot(head)..slot(head) + CANDLE_LEN]);
+            continue;
+        }
+
+        // trades are ordered by the clock, a stale bucket can only come from clock drift
+        if count > 0 && bucket < current.timestamp {
+            continue;
+        }
+
+        let next = if count == 0 { 0 } else { (head + 1) % CANDLES_PER_RESOLUTION };
+        let candle = Candle {
+            timestamp: bucket,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+        };
+        candle.write(&mut data[slot(next)..slot(next) + CANDLE_LEN]);
+
+        let count = (count + 1).min(CANDLES_PER_RESOLUTION);
+        data[offset + 4..offset + 6].copy_from_slice(&(next as u16).to_le_bytes());
+        data[offset + 6..offset + 8].copy_from_slice(&(count as u16).to_le_bytes());
+    }
+
+    Ok(())
+}
+
+/// Candles of one resolution, oldest first
+pub fn get_candles(data: &[u8], resolution: u32) -> Result<Vec<Candle>, ProgramError> {
+    let index = RESOLUTIONS.iter().position(|r| *r == resolution).ok_or(ProgramError::InvalidArgument)?;
+    if data.len() < PRICE_DATA_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    let offset = ring_offset(index);
+    let head = u16::from_le_bytes([data[offset + 4], data[offset + 5]]) as usize;
+    let count = u16::from_le_bytes([data[offset + 6], data[offset + 7]]) as usize;
+
+    Ok((0..count)
+        .map(|age| {
+            let slot = (head + CANDLES_PER_RESOLUTION - (count - 1 - age)) % CANDLES_PER_RESOLUTION;
+            let start = offset + RING_HEADER_LEN + slot * CANDLE_LEN;
+            Candle::read(&data[start..start + CANDLE_LEN])
+        })
+        .collect())
+}
+
+/// Records a Cook AMM trade in the pool's price_data account, creating the account on first use
+pub fn update_price_data<'a>(
+    payer: &AccountInfo<'a>,
+    price_data: &AccountInfo<'a>,
+    amm: &Pubkey,
+    program_id: &Pubkey,
+    sol_amount: u64,
+    token_amount: u64,
+    token_decimals: u8,
+) -> ProgramResult {
+    let (expected_price_data, bump_seed) = get_price_data_address(amm, program_id);
+    if price_data.key != &expected_price_data {
+        msg!("expected price data account {}", expected_price_data);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if price_data.data_is_empty() {
+        utils::create_program_account(
+            payer,
+            price_data,
+            program_id,
+            bump_seed,
+            PRICE_DATA_LEN,
+            vec![&amm.to_bytes(), &0u32.to_le_bytes(), b"TimeSeries"],
+        )?;
+        init_price_data(&mut price_data.try_borrow_mut_data()?)?;
+    } else if price_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if token_amount == 0 {
+        return Ok(());
+    }
+
+    let price = amm::get_price(token_amount, sol_amount, token_decimals, 9);
+    let tokens = token_amount as f64 / 10f64.powi(token_decimals as i32);
+    let now = Clock::get()?.unix_timestamp;
+
+    record_trade(&mut price_data.try_borrow_mut_data()?, now, price as f32, tokens as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_store() -> Vec<u8> {
+        let mut data = vec![0u8; PRICE_DATA_LEN];
+        init_price_data(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_trades_fold_into_buckets() {
+        let mut data = new_store();
+        record_trade(&mut data, 120, 1.0, 10.0).unwrap();
+        record_trade(&mut data, 150, 3.0, 5.0).unwrap();
+        record_trade(&mut data, 179, 0.5, 1.0).unwrap();
+        record_trade(&mut data, 185, 2.0, 2.0).unwrap();
+
+        let minutes = get_candles(&data, 60).unwrap();
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(
+            minutes[0],
+            Candle { timestamp: 120, open: 1.0, high: 3.0, low: 0.5, close: 0.5, volume: 16.0 }
+        );
+        assert_eq!(minutes[1], Candle { timestamp: 180, open: 2.0, high: 2.0, low: 2.0, close: 2.0, volume: 2.0 });
+
+        let days = get_candles(&data, 24 * 60 * 60).unwrap();
+        assert_eq!(days, vec![Candle { timestamp: 0, open: 1.0, high: 3.0, low: 0.5, close: 2.0, volume: 18.0 }]);
+    }
+
+    #[test]
+    fn test_ring_overwrites_oldest() {
+        let mut data = new_store();
+        let total = CANDLES_PER_RESOLUTION as i64 + 6;
+        for minute in 0..total {
+            record_trade(&mut data, minute * 60, minute as f32, 1.0).unwrap();
+        }
+
+        let minutes = get_candles(&data, 60).unwrap();
+        assert_eq!(minutes.len(), CANDLES_PER_RESOLUTION);
+        assert_eq!(minutes[0].timestamp, 6 * 60);
+        assert_eq!(minutes.last().unwrap().timestamp, (total - 1) * 60);
+        assert!(minutes.windows(2).all(|pair| pair[0].timestamp < pair[1].timestamp));
+
+        let quarters = get_candles(&data, 15 * 60).unwrap();
+        assert_eq!(quarters.len(), 5);
+        assert_eq!(quarters[4].volume, 10.0);
+    }
+
+    #[test]
+    fn test_rejects_foreign_accounts() {
+        let mut data = vec![0u8; PRICE_DATA_LEN];
+        assert!(record_trade(&mut data, 0, 1.0, 1.0).is_err());
+        assert!(init_price_data(&mut data[..PRICE_DATA_LEN - 1]).is_err());
+        assert!(get_candles(&new_store(), 30).is_err());
+    }
+}
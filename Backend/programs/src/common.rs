@@ -1,8 +1,8 @@
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
 
 // use crate::hybrid::CollectionData; // Disabled - NFT functionality removed, using Token-2022 only
-use crate::instruction::accounts::InitAccounts;
-use crate::instruction::{SetNameArgs, VoteArgs};
+use crate::instruction::accounts::{AcceptAdminAccounts, InitAccounts, ProposeAdminAccounts, SetPauseAccounts, UpdateFeeConfigAccounts};
+use crate::instruction::{ProposeAdminArgs, SetNameArgs, SetPauseArgs, UpdateFeeConfigArgs, VoteArgs};
 use crate::{accounts, launch};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
@@ -11,7 +11,9 @@ use solana_program::{
     program::invoke,
     program_error::ProgramError,
     pubkey::Pubkey,
+    rent::Rent,
     system_instruction,
+    sysvar::Sysvar,
 };
 
 use crate::state;
@@ -50,10 +52,189 @@ pub fn init<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> Program
         vec![&accounts::SOL_SEED.to_le_bytes()],
     )?;
 
-    let mut program_data = state::ProgramData::try_from_slice(&ctx.accounts.cook_data.data.borrow()[..])?;
+    let mut program_data = state::ProgramData::load(&ctx.accounts.cook_data.data.borrow())?;
 
     program_data.account_type = state::AccountType::Program;
-    program_data.serialize(&mut &mut ctx.accounts.cook_data.data.borrow_mut()[..])?;
+    program_data.store(&mut ctx.accounts.cook_data.data.borrow_mut())?;
+
+    Ok(())
+}
+
+/// UpdateFeeConfig: the admin sets the protocol's fee schedule, which every fee charging path checks its fees
+/// and recipient against. Program data accounts created before the fee schedule are grown to hold it,
+/// and the admin is recorded in it so it no longer falls back to daoplays_account.
+pub fn update_fee_config<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: UpdateFeeConfigArgs) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<UpdateFeeConfigAccounts> = UpdateFeeConfigAccounts::context(accounts)?;
+
+    let mut program_data = load_program_data_as_admin(program_id, ctx.accounts.admin, ctx.accounts.cook_data, ctx.accounts.system_program)?;
+
+    if args.ticket_fee_bps > state::FEE_BPS_CAP || args.swap_fee_bps > state::FEE_BPS_CAP || args.max_creator_fee_bps > state::FEE_BPS_CAP {
+        msg!("Fees are capped at {} basis points", state::FEE_BPS_CAP);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if args.recipient == Pubkey::default() {
+        msg!("Fee recipient must be set");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    program_data.fee_config = state::FeeConfig {
+        ticket_fee_bps: args.ticket_fee_bps,
+        swap_fee_bps: args.swap_fee_bps,
+        max_creator_fee_bps: args.max_creator_fee_bps,
+        recipient: args.recipient,
+    };
+
+    write_program_data(ctx.accounts.admin, ctx.accounts.cook_data, ctx.accounts.system_program, &program_data)?;
+
+    msg!(
+        "Fee schedule updated: {} bps on tickets, {} bps on swaps, creator fees up to {} bps, paid to {}",
+        args.ticket_fee_bps,
+        args.swap_fee_bps,
+        args.max_creator_fee_bps,
+        args.recipient
+    );
+    Ok(())
+}
+
+// Writes the program data back, growing accounts created before its newer fields with the payer covering the extra rent
+fn write_program_data<'a>(
+    payer: &AccountInfo<'a>,
+    cook_data: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    program_data: &state::ProgramData,
+) -> ProgramResult {
+    let account_len = cook_data.data_len();
+    let data_size = state::get_arena_data_size();
+    if account_len < data_size {
+        let rent = Rent::get()?;
+        let extra_rent = rent.minimum_balance(data_size) - rent.minimum_balance(account_len);
+        invoke(
+            &system_instruction::transfer(payer.key, cook_data.key, extra_rent),
+            &[payer.clone(), cook_data.clone(), system_program.clone()],
+        )?;
+
+        msg!("Resizing program data account from {} to {} bytes", account_len, data_size);
+        cook_data.realloc(data_size, true)?;
+    }
+
+    program_data.store(&mut cook_data.data.borrow_mut())
+}
+
+// Loads the program data for one of the admin instructions, checking the signer is its admin
+fn load_program_data_as_admin<'a>(
+    program_id: &Pubkey,
+    admin: &AccountInfo,
+    cook_data: &AccountInfo,
+    system_program: &'a AccountInfo<'a>,
+) -> Result<state::ProgramData, ProgramError> {
+    accounts::check_system_program_key(system_program)?;
+    accounts::check_admin(program_id, cook_data, admin)?;
+
+    let mut program_data = accounts::load_program_data(program_id, cook_data)?;
+    program_data.admin = program_data.get_admin();
+    Ok(program_data)
+}
+
+/// ProposeAdmin: the admin names who should take over from them. Nothing changes until the proposed admin
+/// signs AcceptAdmin, so a mistyped key can be replaced, or the proposal cancelled with the default key.
+pub fn propose_admin<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: ProposeAdminArgs) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<ProposeAdminAccounts> = ProposeAdminAccounts::context(accounts)?;
+
+    let mut program_data = load_program_data_as_admin(program_id, ctx.accounts.admin, ctx.accounts.cook_data, ctx.accounts.system_program)?;
+    program_data.pending_admin = args.new_admin;
+    write_program_data(ctx.accounts.admin, ctx.accounts.cook_data, ctx.accounts.system_program, &program_data)?;
+
+    if args.new_admin == Pubkey::default() {
+        msg!("Admin proposal cancelled");
+    } else {
+        msg!("Proposed {} as admin, waiting for them to accept", args.new_admin);
+    }
+    Ok(())
+}
+
+/// AcceptAdmin: the admin proposed with ProposeAdmin signs to take over
+pub fn accept_admin<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<AcceptAdminAccounts> = AcceptAdminAccounts::context(accounts)?;
+
+    if !ctx.accounts.new_admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+
+    let mut program_data = accounts::load_program_data(program_id, ctx.accounts.cook_data)?;
+    if program_data.pending_admin == Pubkey::default() || *ctx.accounts.new_admin.key != program_data.pending_admin {
+        msg!("{} has not been proposed as admin", ctx.accounts.new_admin.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let previous_admin = program_data.get_admin();
+    program_data.admin = program_data.pending_admin;
+    program_data.pending_admin = Pubkey::default();
+    write_program_data(ctx.accounts.new_admin, ctx.accounts.cook_data, ctx.accounts.system_program, &program_data)?;
+
+    msg!("Admin transferred from {} to {}", previous_admin, program_data.admin);
+    Ok(())
+}
+
+/// SetPause: the admin pauses or resumes ticket sales, swaps, liquidity and claims, for the launch passed
+/// after the system program or for the whole program when there is none.
+pub fn set_pause<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: SetPauseArgs) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<SetPauseAccounts> = SetPauseAccounts::context(accounts)?;
+
+    let mut program_data = load_program_data_as_admin(program_id, ctx.accounts.admin, ctx.accounts.cook_data, ctx.accounts.system_program)?;
+
+    let launch_account = match ctx.remaining_accounts.first() {
+        Some(launch_account) => launch_account,
+        None => {
+            program_data.paused = args.paused;
+            write_program_data(ctx.accounts.admin, ctx.accounts.cook_data, ctx.accounts.system_program, &program_data)?;
+
+            msg!("Program {}", if args.paused { "paused" } else { "resumed" });
+            return Ok(());
+        }
+    };
+
+    if launch_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut launch_data = launch::LaunchData::load(&launch_account.try_borrow_data()?)?;
+    if launch_data.flags.len() < launch::LaunchFlags::LENGTH as usize {
+        launch_data.flags.resize(launch::LaunchFlags::LENGTH as usize, 0);
+    }
+    launch_data.flags[launch::LaunchFlags::Paused as usize] = args.paused as u8;
+    launch::write_launch_data(ctx.accounts.admin, launch_account, ctx.accounts.system_program, &launch_data)?;
+
+    msg!("Launch {} {}", launch_data.page_name, if args.paused { "paused" } else { "resumed" });
+    Ok(())
+}
+
+/// Fails while SetPause has paused the whole program. Every instruction the pause applies to has to pass
+/// the program data account, `program_data` is None when it was left out.
+pub fn check_not_paused(program_id: &Pubkey, program_data: Option<&AccountInfo>) -> ProgramResult {
+    let program_data = match program_data {
+        Some(program_data) => program_data,
+        None => {
+            msg!("❌ Error: Missing the program data account");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    };
+
+    if accounts::load_program_data(program_id, program_data)?.paused {
+        msg!("❌ Error: The program is paused");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Fails while SetPause has paused the launch
+pub fn check_launch_not_paused(launch_data: &AccountInfo) -> ProgramResult {
+    let launch_data_bytes = launch_data.try_borrow_data()?;
+    if launch::LaunchAccessor::load(&launch_data_bytes)?.is_paused(&launch_data_bytes) {
+        msg!("❌ Error: Launch {} is paused", launch_data.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
 
     Ok(())
 }
@@ -215,50 +396,46 @@ pub fn set_name<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args:
     Ok(())
 }
 
+/// CloseAccount: the admin closes program accounts that are finished with and takes back their rent.
+/// Only JoinData whose tickets have all been claimed or refunded can be closed, anything else the
+/// program owns is still read or paid out of. The accounts to close follow the system program.
 pub fn close_account<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
-    msg!("in close account");
-    // This function expects to be passed three accounts, get them all first and then check their value is as expected
-    let user_account_info = next_account_info(account_info_iter)?;
-    let cook_pda = next_account_info(account_info_iter)?;
-    let system_program_account_info = next_account_info(account_info_iter)?;
-
-    if !user_account_info.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let admin = next_account_info(account_info_iter)?;
+    let cook_data = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
 
-    if *user_account_info.key != accounts::daoplays_account::ID {
-        return Err(ProgramError::InvalidAccountData);
-    }
-
-    let _pda_sol_bump_seed = accounts::check_program_data_account(cook_pda, program_id, vec![&accounts::SOL_SEED.to_le_bytes()]).unwrap();
-
-    accounts::check_system_program_key(system_program_account_info)?;
+    accounts::check_admin(program_id, cook_data, admin)?;
+    accounts::check_system_program_key(system_program)?;
 
-    loop {
-        let next_account = account_info_iter.next();
-        if next_account.is_none() {
-            break;
+    for account in account_info_iter {
+        if account.owner != program_id {
+            msg!("Account {} is not owned by the program", account.key);
+            return Err(ProgramError::IllegalOwner);
+        }
+        if !is_closable(&account.try_borrow_data()?) {
+            msg!("Account {} is not finished with", account.key);
+            return Err(ProgramError::InvalidAccountData);
         }
-        let account = next_account.unwrap();
-
-        /*
-        invoke_signed(
-            &system_instruction::transfer(cook_pda.key, account.key, 10000),
-            &[cook_pda.clone(), account.clone()],
-            &[&[&accounts::SOL_SEED.to_le_bytes(), &[pda_sol_bump_seed]]],
-        )?;
-
-        return Ok(());
-        */
 
         let account_lamports = **account.try_borrow_lamports()?;
-
-        msg!("transferring lamports");
         **account.try_borrow_mut_lamports()? -= account_lamports;
-        **user_account_info.try_borrow_mut_lamports()? += account_lamports;
+        **admin.try_borrow_mut_lamports()? += account_lamports;
+
+        account.realloc(0, false)?;
+        account.assign(system_program.key);
+
+        msg!("Closed {}, {} lamports returned", account.key, account_lamports);
     }
 
     Ok(())
 }
+
+// Whether a program account is in a terminal state CloseAccount can close it in
+fn is_closable(data: &[u8]) -> bool {
+    match state::AccountType::deserialize(&mut &data[..]) {
+        Ok(state::AccountType::Join) => launch::JoinData::load(data).is_ok_and(|join_data| join_data.is_settled()),
+        _ => false,
+    }
+}
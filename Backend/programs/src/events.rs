@@ -16,6 +16,7 @@ pub enum EventType {
     LaunchCreated,
     PoolCreated,
     LiquidityLocked,
+    LiquidityUnlocked,
     ThresholdMet,
     BondingCurveClosed,
     TradingStarted,
@@ -23,6 +24,8 @@ pub enum EventType {
     TokensSold,
     LiquidityAdded,
     LiquidityRemoved,
+    AuctionCleared,
+    CreatorFeesClaimed,
 }
 
 impl EventType {
@@ -31,6 +34,7 @@ impl EventType {
             EventType::LaunchCreated => "LAUNCH_CREATED",
             EventType::PoolCreated => "POOL_CREATED",
             EventType::LiquidityLocked => "LIQUIDITY_LOCKED",
+            EventType::LiquidityUnlocked => "LIQUIDITY_UNLOCKED",
             EventType::ThresholdMet => "THRESHOLD_MET",
             EventType::BondingCurveClosed => "BONDING_CURVE_CLOSED",
             EventType::TradingStarted => "TRADING_STARTED",
@@ -38,6 +42,8 @@ impl EventType {
             EventType::TokensSold => "TOKENS_SOLD",
             EventType::LiquidityAdded => "LIQUIDITY_ADDED",
             EventType::LiquidityRemoved => "LIQUIDITY_REMOVED",
+            EventType::AuctionCleared => "AUCTION_CLEARED",
+            EventType::CreatorFeesClaimed => "CREATOR_FEES_CLAIMED",
         }
     }
 }
@@ -103,6 +109,25 @@ pub fn emit_liquidity_locked_event(
     );
 }
 
+/// Emit a liquidity unlocked event
+pub fn emit_liquidity_unlocked_event(
+    lock_address: &Pubkey,
+    lp_token_mint: &Pubkey,
+    unlocked_amount: u64,
+    remaining_amount: u64,
+    beneficiary: &Pubkey,
+) {
+    msg!(
+        "EVENT:{}:lock_address:{}:lp_token_mint:{}:unlocked_amount:{}:remaining_amount:{}:beneficiary:{}",
+        EventType::LiquidityUnlocked.as_str(),
+        lock_address,
+        lp_token_mint,
+        unlocked_amount,
+        remaining_amount,
+        beneficiary
+    );
+}
+
 /// Emit a threshold met event
 pub fn emit_threshold_met_event(
     token_mint: &Pubkey,
@@ -224,3 +249,36 @@ pub fn emit_liquidity_removed_event(
     );
 }
 
+/// Emit a dutch auction cleared event
+pub fn emit_auction_cleared_event(
+    launch: &Pubkey,
+    clearing_price: u64,
+    tickets_sold: u32,
+    raised: u64,
+) {
+    msg!(
+        "EVENT:{}:launch:{}:clearing_price:{}:tickets_sold:{}:raised:{}",
+        EventType::AuctionCleared.as_str(),
+        launch,
+        clearing_price,
+        tickets_sold,
+        raised
+    );
+}
+
+/// Emit a creator fees claimed event
+pub fn emit_creator_fees_claimed_event(
+    launch: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+    creator: &Pubkey,
+) {
+    msg!(
+        "EVENT:{}:launch:{}:vault:{}:amount:{}:creator:{}",
+        EventType::CreatorFeesClaimed.as_str(),
+        launch,
+        vault,
+        amount,
+        creator
+    );
+}
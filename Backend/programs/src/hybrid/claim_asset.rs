@@ -1,5 +1,4 @@
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
-use sha2::{Digest, Sha256};
 
 use solana_program::{
     account_info::AccountInfo,
@@ -7,10 +6,9 @@ use solana_program::{
     entrypoint::ProgramResult,
     instruction::{AccountMeta, Instruction},
     msg,
-    program::{invoke, invoke_signed},
+    program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
-    system_instruction,
     sysvar::Sysvar,
 };
 
@@ -21,7 +19,7 @@ use crate::{
         NFTAssignment,
     },
     instruction::{accounts::ClaimNFTAccounts, ClaimNFTArgs},
-    state::FEE_AMOUNT,
+    orao, randomness,
 };
 
 use crate::state;
@@ -67,13 +65,6 @@ pub fn claim_nft<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args:
 
     let pda_sol_bump_seed = accounts::check_program_data_account(ctx.accounts.cook_pda, program_id, vec![&accounts::SOL_SEED.to_le_bytes()]).unwrap();
 
-    let random_bump = accounts::check_program_data_account(
-        ctx.accounts.orao_random,
-        ctx.accounts.orao_program.key,
-        vec![b"orao-vrf-randomness-request", &args.seed],
-    )
-    .unwrap();
-
     let expected_token_destination_owner = ctx.accounts.cook_pda;
 
     accounts::check_token_account(
@@ -99,9 +90,15 @@ pub fn claim_nft<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args:
     }
 
     // if we are on solana then use orao
-    if state::NETWORK != state::Network::Eclipse {
+    let random_address = if state::NETWORK != state::Network::Eclipse {
         accounts::check_orao_key(ctx.accounts.orao_program)?;
 
+        let _random_bump = accounts::check_program_data_account(
+            ctx.accounts.orao_random,
+            ctx.accounts.orao_program.key,
+            vec![orao::RANDOMNESS_REQUEST_SEED, &args.seed],
+        )?;
+
         let account_metas = vec![
             AccountMeta::new(*ctx.accounts.user.key, true),
             AccountMeta::new(*ctx.accounts.orao_network.key, false),
@@ -131,44 +128,13 @@ pub fn claim_nft<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args:
             ],
             &[&[&accounts::SOL_SEED.to_le_bytes(), &[pda_sol_bump_seed]]],
         )?;
-    }
-    // otherwise we just write some randomness to the account ourselves
-    else {
-        if ctx.accounts.orao_program.key != program_id {
-            msg!("incorrect orao program passed");
-            return Err(ProgramError::InvalidAccountData);
-        }
-        // create the account
-        utils::create_program_account(
-            ctx.accounts.user,
-            ctx.accounts.orao_random,
-            program_id,
-            random_bump,
-            104,
-            vec![b"orao-vrf-randomness-request", &args.seed],
-        )?;
 
-        let mut seed_values = state::SeedStruct { seed_prices: [0; 10] };
-        seed_values.seed_prices[0] = u64::from_le_bytes(args.seed[0..8].try_into().unwrap());
-        seed_values.seed_prices[1] = u64::from_le_bytes(args.seed[8..16].try_into().unwrap());
-        seed_values.seed_prices[2] = u64::from_le_bytes(args.seed[16..24].try_into().unwrap());
-        seed_values.seed_prices[3] = u64::from_le_bytes(args.seed[24..32].try_into().unwrap());
-        seed_values.seed_prices[4] = Clock::get()?.slot;
-        seed_values.seed_prices[5] = Clock::get()?.unix_timestamp as u64;
-
-        seed_values.seed_prices[6] = u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[..8].try_into().unwrap());
-        seed_values.seed_prices[7] = u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[8..16].try_into().unwrap());
-        seed_values.seed_prices[8] = u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[16..24].try_into().unwrap());
-        seed_values.seed_prices[9] = u64::from_le_bytes(ctx.accounts.user.key.to_bytes()[24..32].try_into().unwrap());
-
-        let vec_to_hash = unsafe { utils::any_as_u8_slice(&seed_values) };
-        let hash = &(Sha256::new().chain_update(vec_to_hash).finalize()[..32]);
-        let mut hash_array = [0u8; 104];
-        hash_array[40..72].copy_from_slice(&hash[..32]);
-        hash_array[72..104].copy_from_slice(&hash[..32]);
-
-        hash_array.serialize(&mut &mut ctx.accounts.orao_random.data.borrow_mut()[..])?;
+        *ctx.accounts.orao_random.key
     }
+    // otherwise the mint reads the collection's commit-reveal randomness, mixed with the user's assignment
+    else {
+        randomness::get_commit_reveal_address(ctx.accounts.collection_data.key, program_id).0
+    };
 
     msg!("get user data");
     // check if this person has a player account
@@ -239,11 +205,6 @@ pub fn claim_nft<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args:
         &transfer_hook_accounts,
     )?;
 
-    invoke(
-        &system_instruction::transfer(ctx.accounts.user.key, &ctx.accounts.cook_fees.key, FEE_AMOUNT),
-        &[ctx.accounts.user.clone(), ctx.accounts.cook_fees.clone()],
-    )?;
-
     // check if we are whitelisting
     let whitelist_option = plugin_map.get(&CollectionPluginType::WhiteListToken);
 
@@ -285,7 +246,7 @@ pub fn claim_nft<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args:
     }
 
     assignment_data.num_interactions = slot;
-    assignment_data.random_address = *ctx.accounts.orao_random.key;
+    assignment_data.random_address = random_address;
     assignment_data.nft_address = *ctx.accounts.system_program.key;
     assignment_data.status = 0;
     assignment_data.nft_index = 0;
@@ -78,11 +78,11 @@ pub fn edit_collection<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         return Err(ProgramError::InvalidAccountData);
     }
 
-    let mut program_data = state::ProgramData::try_from_slice(&ctx.accounts.cook_data.data.borrow()[..])?;
+    let mut program_data = state::ProgramData::load(&ctx.accounts.cook_data.data.borrow())?;
 
     program_data.num_launches += 1;
 
-    program_data.serialize(&mut &mut ctx.accounts.cook_data.data.borrow_mut()[..])?;
+    program_data.store(&mut ctx.accounts.cook_data.data.borrow_mut())?;
 
     let old_len = ctx.accounts.collection_data.data_len(); //Box::new(to_vec(&collection_data).unwrap()).len();
 
@@ -9,7 +9,7 @@ use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
 use crate::hybrid::{
     get_collection_plugin_map, CollectionData, CollectionKeys, CollectionMeta, CollectionPlugin, CollectionPluginType, NFTAssignment,
 };
-use crate::{accounts, instruction::accounts::MintRandomNFTAccounts, utils::mpl_compat::convert_mpl_error};
+use crate::{accounts, instruction::accounts::MintRandomNFTAccounts, randomness, utils::mpl_compat::convert_mpl_error};
 
 use crate::utils;
 
@@ -210,9 +210,16 @@ pub fn mint_random<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
         }
     }
 
-    let win_roll = utils::generate_random_f64(u64::try_from_slice(&ctx.accounts.orao_random.data.borrow()[40..48])?);
-    let which_roll = utils::generate_random_f64(u64::try_from_slice(&ctx.accounts.orao_random.data.borrow()[48..56])?);
-    let mut seed = u64::try_from_slice(&ctx.accounts.orao_random.data.borrow()[56..64])?;
+    let randomness_request = randomness::RandomnessRequest {
+        orao_request: assignment_data.random_address,
+        launch: *ctx.accounts.collection_data.key,
+        consumer: *ctx.accounts.assignment.key,
+    };
+    let random_bytes = randomness::load_provider(program_id, ctx.accounts.orao_random, &randomness_request)?.randomness()?;
+
+    let win_roll = utils::generate_random_f64(u64::from_le_bytes(random_bytes[0..8].try_into().unwrap()));
+    let which_roll = utils::generate_random_f64(u64::from_le_bytes(random_bytes[8..16].try_into().unwrap()));
+    let mut seed = u64::from_le_bytes(random_bytes[16..24].try_into().unwrap());
 
     msg!("rolls {} {}", win_roll, which_roll);
 
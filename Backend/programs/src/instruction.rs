@@ -10,14 +10,14 @@ pub enum LaunchInstruction {
     CreateLaunch { args: CreateArgs },
     BuyTickets { args: JoinArgs },
     CheckTickets,
-    InitCookAMM,
+    InitCookAMM { args: InitCookAMMArgs },
     HypeVote { args: HypeVoteArgs },
     ClaimRefund,
     EditLaunch { args: EditArgs },
     ClaimTokens,
     SetName { args: SetNameArgs },
     SwapCookAMM { args: PlaceOrderArgs },
-    GetMMRewardTokens { args: AddRewardsArgs },
+    GetMMRewardTokens { args: GetMMRewardArgs },
     CloseAccount,
     LaunchCollection { args: LaunchCollectionArgs },
     ClaimNFT { args: ClaimNFTArgs },
@@ -43,6 +43,22 @@ pub enum LaunchInstruction {
     BuyNFT { args: BuyNFTArgs },
     UpdateRaffleImages { args: UpdateRaffleImagesArgs },
     BestPriceSwap { args: BestPriceSwapArgs },
+    SwapCookAMMV2 { args: SwapArgs },
+    GetTWAP { args: GetTWAPArgs },
+    MigrateLaunch,
+    CommitRandomness { args: CommitRandomnessArgs },
+    RevealRandomness { args: RevealRandomnessArgs },
+    SettleRaffle,
+    FailLaunch,
+    SettleIDO,
+    GraduateLaunch,
+    LockLiquidity { args: LockLiquidityArgs },
+    UnlockLiquidity,
+    UpdateFeeConfig { args: UpdateFeeConfigArgs },
+    ClaimCreatorFees,
+    ProposeAdmin { args: ProposeAdminArgs },
+    AcceptAdmin,
+    SetPause { args: SetPauseArgs },
 }
 
 // Instruction argument structs
@@ -86,6 +102,12 @@ pub struct CreateArgs {
     pub launch_type: u8,
     pub whitelist_tokens: u64,
     pub whitelist_end: u64,
+    pub allowlist: Option<crate::launch::MerkleAllowlist>,
+    pub soft_cap: u64, // lamports, stored as minimum_liquidity
+    pub hard_cap: u64, // IDO only, lamports, 0 = uncapped
+    pub auction_floor_price: u64, // dutch auction only, ticket_price is the starting price
+    pub auction_half_life: u64,   // dutch auction only, seconds, 0 = linear decay until close_date
+    pub creator_fee_bps: u16,     // share of every SwapCookAMM trade paid to the creator, 0 = none
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
@@ -111,13 +133,23 @@ pub struct InstantLaunchArgs {
     pub twitter: String,
     pub telegram: String,
     pub discord: String,
+    pub liquidity_scaling: u8, // 1 to scale down fills while the pool's SOL reserve is below 10 SOL
+    pub allowlist: Option<crate::launch::MerkleAllowlist>,
+    pub creator_fee_bps: u16, // share of every SwapCookAMM trade paid to the creator, 0 = none
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct JoinArgs {
     pub amount: u64,
     pub num_tickets: u16,
-    pub seed: [u8; 32],
+    pub seed: [u8; 32], // ignored by BuyTickets, which derives its Orao request seed from the purchase
+    pub allowlist: Option<AllowlistProof>, // required while a merkle allowlist phase is running
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct AllowlistProof {
+    pub cap: u64,               // the wallet's cap from its allowlist entry, 0 = the launch's default cap
+    pub proof: Vec<[u8; 32]>,   // sibling hashes from the wallet's leaf up to the root
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
@@ -155,7 +187,8 @@ pub struct PlaceOrderArgs {
     pub order_type: u8,
     pub client_order_id: u64,
     pub limit: u16,
-    // Launch state fields (passed from frontend to avoid deserializing LaunchData)
+    // DEPRECATED: launch state fields are ignored and read from the launch account instead.
+    // They are kept so existing clients still serialize a valid SwapCookAMM, use SwapCookAMMV2 for new code.
     pub is_instant_launch: u8, // 0 = false, 1 = true
     pub is_graduated: u8,       // 0 = false, 1 = true
     pub tokens_sold: u64,       // Current tokens sold for bonding curve
@@ -163,6 +196,38 @@ pub struct PlaceOrderArgs {
     pub creator_key: Pubkey,    // Creator pubkey for limit check
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SwapArgs {
+    pub side: u8,
+    pub limit_price: u64,
+    pub max_base_quantity: u64,
+    pub max_quote_quantity: u64,
+    pub order_type: u8,
+    pub client_order_id: u64,
+    pub limit: u16,
+    pub allowlist: Option<AllowlistProof>, // required for bonding curve buys while a merkle allowlist phase is running
+}
+
+impl From<PlaceOrderArgs> for SwapArgs {
+    fn from(args: PlaceOrderArgs) -> Self {
+        SwapArgs {
+            side: args.side,
+            limit_price: args.limit_price,
+            max_base_quantity: args.max_base_quantity,
+            max_quote_quantity: args.max_quote_quantity,
+            order_type: args.order_type,
+            client_order_id: args.client_order_id,
+            limit: args.limit,
+            allowlist: None,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct GetTWAPArgs {
+    pub window: u32, // seconds, 0 = last trade price
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct CookSwapArgs {
     pub amount_0: u64,
@@ -177,13 +242,17 @@ pub struct RaydiumSwapArgs {
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct AddLiquidityArgs {
-    pub amount_0: u64,
-    pub amount_1: u64,
+    pub amount_0: u64, // desired SOL
+    pub amount_1: u64, // desired tokens
+    pub max_base_in: u64, // most tokens taken from the user, including transfer fees
+    pub max_quote_in: u64, // most SOL taken from the user
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct RemoveLiquidityArgs {
     pub amount: u64,
+    pub min_base_out: u64,
+    pub min_quote_out: u64,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
@@ -277,6 +346,52 @@ pub struct AddRewardsArgs {
     pub amount: u64,
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct GetMMRewardArgs {
+    pub date: u32, // reward day, counted from the day trade rewards were added
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct InitCookAMMArgs {
+    pub liquidity_scaling: u8, // 1 to opt the launch's Cook AMM pool in to liquidity scaling
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct CommitRandomnessArgs {
+    pub commitment: [u8; 32], // sha256 of the secret revealed after the launch ends
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RevealRandomnessArgs {
+    pub secret: [u8; 32],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct LockLiquidityArgs {
+    pub amount: u64,   // LP tokens
+    pub cliff: u64,    // seconds before any of them unlock
+    pub duration: u64, // seconds until all of them have unlocked, linearly from the lock date, at least the cliff
+    pub burn: u8,      // 1 = burn the LP tokens, locking the liquidity forever
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct UpdateFeeConfigArgs {
+    pub ticket_fee_bps: u16,      // share of ticket purchases, IDO contributions and auction bids
+    pub swap_fee_bps: u16,        // share of SwapCookAMM trades
+    pub max_creator_fee_bps: u16, // most a launch creator can take from their launch's swaps
+    pub recipient: Pubkey,        // wallet every protocol fee is paid to
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ProposeAdminArgs {
+    pub new_admin: Pubkey, // takes over once it signs AcceptAdmin, the default key cancels a proposal
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SetPauseArgs {
+    pub paused: bool, // applies to the launch passed after the system program, or the whole program without one
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct InitAMMExternalArgs {
     pub amount_0: u64,
@@ -309,6 +424,16 @@ pub struct BestPriceSwapArgs {
     pub input_mint: Pubkey,
     pub output_mint: Pubkey,
     pub slippage_bps: u16,
+    // Explicit route, empty = let the program pick the single best venue
+    pub legs: Vec<RouteLeg>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RouteLeg {
+    pub hop: u8,                 // 0 = input -> output (or -> SOL), 1 = SOL -> output for two-hop routes
+    pub venue: u8,               // 0 = Cook AMM, 1 = Raydium CPMM
+    pub amount_bps: u16,         // share of the hop's input traded on this leg, legs of a hop sum to 10000
+    pub minimum_amount_out: u64, // minimum delivered by this leg
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
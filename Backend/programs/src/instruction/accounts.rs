@@ -293,6 +293,7 @@ pub struct BuyTicketsAccounts<'a> {
     pub orao_network: &'a AccountInfo<'a>,
     pub orao_treasury: &'a AccountInfo<'a>,
     pub fees: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
 }
 
 impl<'a> BuyTicketsAccounts<'a> {
@@ -316,8 +317,9 @@ impl<'a> BuyTicketsAccounts<'a> {
                 orao_network: &accounts[14],
                 orao_treasury: &accounts[15],
                 fees: &accounts[16],
+                cook_data: &accounts[17],
             },
-            remaining_accounts: &accounts[17..],
+            remaining_accounts: &accounts[18..],
         })
     }
 }
@@ -880,3 +882,407 @@ impl<'a> InitAccounts<'a> {
     }
 }
 
+
+#[derive(Debug)]
+pub struct MigrateLaunchAccounts<'a> {
+    pub payer: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+}
+
+impl<'a> MigrateLaunchAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                payer: &accounts[0],
+                launch_data: &accounts[1],
+                system_program: &accounts[2],
+            },
+            remaining_accounts: &accounts[3..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SettleRaffleAccounts<'a> {
+    pub cranker: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub randomness: &'a AccountInfo<'a>,
+    pub raffle_draw: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+    pub orao_program: &'a AccountInfo<'a>,
+    pub orao_network: &'a AccountInfo<'a>,
+    pub orao_treasury: &'a AccountInfo<'a>,
+}
+
+impl<'a> SettleRaffleAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 8 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                cranker: &accounts[0],
+                launch_data: &accounts[1],
+                randomness: &accounts[2],
+                raffle_draw: &accounts[3],
+                system_program: &accounts[4],
+                orao_program: &accounts[5],
+                orao_network: &accounts[6],
+                orao_treasury: &accounts[7],
+            },
+            // the JoinData accounts to settle
+            remaining_accounts: &accounts[8..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct FailLaunchAccounts<'a> {
+    pub payer: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+}
+
+impl<'a> FailLaunchAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                payer: &accounts[0],
+                launch_data: &accounts[1],
+                system_program: &accounts[2],
+            },
+            // optionally the accounts to burn the unsold supply
+            remaining_accounts: &accounts[3..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SettleIDOAccounts<'a> {
+    pub payer: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub listing: &'a AccountInfo<'a>,
+    pub cook_pda: &'a AccountInfo<'a>,
+    pub base_token_mint: &'a AccountInfo<'a>,
+    pub cook_base_token: &'a AccountInfo<'a>,
+    pub amm: &'a AccountInfo<'a>,
+    pub amm_base: &'a AccountInfo<'a>,
+    pub amm_quote: &'a AccountInfo<'a>,
+    pub base_token_program: &'a AccountInfo<'a>,
+    pub quote_token_program: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+    pub liquidity_lock: &'a AccountInfo<'a>,
+}
+
+impl<'a> SettleIDOAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 13 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                payer: &accounts[0],
+                launch_data: &accounts[1],
+                listing: &accounts[2],
+                cook_pda: &accounts[3],
+                base_token_mint: &accounts[4],
+                cook_base_token: &accounts[5],
+                amm: &accounts[6],
+                amm_base: &accounts[7],
+                amm_quote: &accounts[8],
+                base_token_program: &accounts[9],
+                quote_token_program: &accounts[10],
+                system_program: &accounts[11],
+                liquidity_lock: &accounts[12],
+            },
+            // optionally the Raydium pool accounts
+            remaining_accounts: &accounts[13..],
+        })
+    }
+}
+
+// ClaimTokens on an IDO, the first seven accounts are the ones every ClaimTokens takes
+#[derive(Debug)]
+pub struct ClaimIDOTokensAccounts<'a> {
+    pub user: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub user_base: &'a AccountInfo<'a>,
+    pub base_token_mint: &'a AccountInfo<'a>,
+    pub base_token_program: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+    pub join_data: &'a AccountInfo<'a>,
+    pub listing: &'a AccountInfo<'a>,
+    pub cook_pda: &'a AccountInfo<'a>,
+    pub cook_base_token: &'a AccountInfo<'a>,
+    pub associated_token: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+}
+
+impl<'a> ClaimIDOTokensAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 12 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                user: &accounts[0],
+                launch_data: &accounts[1],
+                user_base: &accounts[2],
+                base_token_mint: &accounts[3],
+                base_token_program: &accounts[4],
+                system_program: &accounts[5],
+                join_data: &accounts[6],
+                listing: &accounts[7],
+                cook_pda: &accounts[8],
+                cook_base_token: &accounts[9],
+                associated_token: &accounts[10],
+                cook_data: &accounts[11],
+            },
+            remaining_accounts: &accounts[12..],
+        })
+    }
+}
+
+// GraduateLaunch, callable by anyone once the bonding curve has raised its graduation threshold
+#[derive(Debug)]
+pub struct GraduateLaunchAccounts<'a> {
+    pub caller: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub listing: &'a AccountInfo<'a>,
+    pub base_token_mint: &'a AccountInfo<'a>,
+    pub quote_token_mint: &'a AccountInfo<'a>,
+    pub amm: &'a AccountInfo<'a>,
+    pub amm_base: &'a AccountInfo<'a>,
+    pub amm_quote: &'a AccountInfo<'a>,
+    pub caller_quote: &'a AccountInfo<'a>,
+    pub base_token_program: &'a AccountInfo<'a>,
+    pub quote_token_program: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+    pub liquidity_lock: &'a AccountInfo<'a>,
+}
+
+impl<'a> GraduateLaunchAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 13 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                caller: &accounts[0],
+                launch_data: &accounts[1],
+                listing: &accounts[2],
+                base_token_mint: &accounts[3],
+                quote_token_mint: &accounts[4],
+                amm: &accounts[5],
+                amm_base: &accounts[6],
+                amm_quote: &accounts[7],
+                caller_quote: &accounts[8],
+                base_token_program: &accounts[9],
+                quote_token_program: &accounts[10],
+                system_program: &accounts[11],
+                liquidity_lock: &accounts[12],
+            },
+            remaining_accounts: &accounts[13..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct LockLiquidityAccounts<'a> {
+    pub creator: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub listing: &'a AccountInfo<'a>,
+    pub base_token_mint: &'a AccountInfo<'a>,
+    pub amm: &'a AccountInfo<'a>,
+    pub lp_token_mint: &'a AccountInfo<'a>,
+    pub creator_lp: &'a AccountInfo<'a>,
+    pub liquidity_lock: &'a AccountInfo<'a>,
+    pub lock_vault: &'a AccountInfo<'a>,
+    pub lp_token_program: &'a AccountInfo<'a>,
+    pub associated_token: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+}
+
+impl<'a> LockLiquidityAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 13 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                creator: &accounts[0],
+                launch_data: &accounts[1],
+                listing: &accounts[2],
+                base_token_mint: &accounts[3],
+                amm: &accounts[4],
+                lp_token_mint: &accounts[5],
+                creator_lp: &accounts[6],
+                liquidity_lock: &accounts[7],
+                lock_vault: &accounts[8],
+                lp_token_program: &accounts[9],
+                associated_token: &accounts[10],
+                system_program: &accounts[11],
+                cook_data: &accounts[12],
+            },
+            remaining_accounts: &accounts[13..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct UnlockLiquidityAccounts<'a> {
+    pub beneficiary: &'a AccountInfo<'a>,
+    pub liquidity_lock: &'a AccountInfo<'a>,
+    pub lp_token_mint: &'a AccountInfo<'a>,
+    pub lock_vault: &'a AccountInfo<'a>,
+    pub beneficiary_lp: &'a AccountInfo<'a>,
+    pub lp_token_program: &'a AccountInfo<'a>,
+    pub launch_data: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+}
+
+impl<'a> UnlockLiquidityAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 8 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                beneficiary: &accounts[0],
+                liquidity_lock: &accounts[1],
+                lp_token_mint: &accounts[2],
+                lock_vault: &accounts[3],
+                beneficiary_lp: &accounts[4],
+                lp_token_program: &accounts[5],
+                launch_data: &accounts[6],
+                cook_data: &accounts[7],
+            },
+            remaining_accounts: &accounts[8..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateFeeConfigAccounts<'a> {
+    pub admin: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+}
+
+impl<'a> UpdateFeeConfigAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                admin: &accounts[0],
+                cook_data: &accounts[1],
+                system_program: &accounts[2],
+            },
+            remaining_accounts: &accounts[3..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ClaimCreatorFeesAccounts<'a> {
+    pub launch_data: &'a AccountInfo<'a>,
+    pub creator: &'a AccountInfo<'a>,
+    pub creator_fees: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+}
+
+impl<'a> ClaimCreatorFeesAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 4 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                launch_data: &accounts[0],
+                creator: &accounts[1],
+                creator_fees: &accounts[2],
+                cook_data: &accounts[3],
+            },
+            remaining_accounts: &accounts[4..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ProposeAdminAccounts<'a> {
+    pub admin: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+}
+
+impl<'a> ProposeAdminAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                admin: &accounts[0],
+                cook_data: &accounts[1],
+                system_program: &accounts[2],
+            },
+            remaining_accounts: &accounts[3..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct AcceptAdminAccounts<'a> {
+    pub new_admin: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+}
+
+impl<'a> AcceptAdminAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                new_admin: &accounts[0],
+                cook_data: &accounts[1],
+                system_program: &accounts[2],
+            },
+            remaining_accounts: &accounts[3..],
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SetPauseAccounts<'a> {
+    pub admin: &'a AccountInfo<'a>,
+    pub cook_data: &'a AccountInfo<'a>,
+    pub system_program: &'a AccountInfo<'a>,
+}
+
+impl<'a> SetPauseAccounts<'a> {
+    pub fn context(accounts: &'a [AccountInfo<'a>]) -> Result<Context<'a, Self>, ProgramError> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(Context {
+            accounts: Self {
+                admin: &accounts[0],
+                cook_data: &accounts[1],
+                system_program: &accounts[2],
+            },
+            remaining_accounts: &accounts[3..],
+        })
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     accounts,
     instruction::accounts::CheckTicketsAccounts,
     launch::{JoinData, LaunchData, LaunchKeys, LaunchMetaType},
-    state, utils,
+    randomness, state, utils,
 };
 
 pub fn check_tickets<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
@@ -21,9 +21,9 @@ pub fn check_tickets<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -
     accounts::check_system_program_key(ctx.accounts.system_program)?;
 
     // check the game account is the same as is on chain
-    let mut launch_data = LaunchData::try_from_slice(&ctx.accounts.launch_data.data.borrow()[..])?;
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.data.borrow())?;
 
-    let mut join_data = JoinData::try_from_slice(&ctx.accounts.join_data.data.borrow()[..])?;
+    let mut join_data = JoinData::load(&ctx.accounts.join_data.data.borrow())?;
 
     let _player_data_bump =
         accounts::check_program_data_account(ctx.accounts.user_data, program_id, vec![&ctx.accounts.user.key.to_bytes(), b"User"]).unwrap();
@@ -89,17 +89,28 @@ pub fn check_tickets<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -
         launch_data.ticket_claimed += join_data.num_tickets as u32;
 
         launch_data.serialize(&mut &mut ctx.accounts.launch_data.data.borrow_mut()[..])?;
-        join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+        join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
         return Ok(());
     }
 
     msg!("Have Raffle - check randoms");
-    let ticket_block: u8 = (join_data.num_tickets_checked / state::N_RANDOMS as u16) as u8;
-    let r_start: usize = (40 + ticket_block * 8) as usize;
-    let r_end: usize = (r_start + 8) as usize;
+    let request = randomness::RandomnessRequest {
+        orao_request: join_data.random_address,
+        launch: *ctx.accounts.launch_data.key,
+        consumer: *ctx.accounts.join_data.key,
+    };
+    let randoms = randomness::load_provider(program_id, ctx.accounts.orao_random, &request)?.randomness()?;
+
+    let ticket_block: usize = (join_data.num_tickets_checked / state::N_RANDOMS as u16) as usize;
+    let r_start: usize = ticket_block * 8;
+    let r_end: usize = r_start + 8;
+    if r_end > randoms.len() {
+        msg!("ran out of randoms");
+        return Err(ProgramError::InvalidAccountData);
+    }
 
     // each ticket just has probability to win of tickets remaining / num winning tickets
-    let mut seed = u64::try_from_slice(&ctx.accounts.orao_random.data.borrow()[r_start..r_end])?;
+    let mut seed = u64::try_from_slice(&randoms[r_start..r_end])?;
 
     msg!("block {} seed {}", ticket_block, seed);
     if seed == 0 {
@@ -146,7 +157,7 @@ pub fn check_tickets<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -
     launch_data.ticket_claimed += tickets_to_check as u32;
 
     launch_data.serialize(&mut &mut ctx.accounts.launch_data.data.borrow_mut()[..])?;
-    join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+    join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
 
     Ok(())
 }
@@ -1,4 +1,4 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
 };
@@ -15,9 +15,9 @@ pub fn claim_refund<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let mut join_data = JoinData::try_from_slice(&ctx.accounts.join_data.data.borrow()[..])?;
+    let mut join_data = JoinData::load(&ctx.accounts.join_data.data.borrow())?;
 
-    let mut launch_data = LaunchData::try_from_slice(&ctx.accounts.launch_data.data.borrow()[..])?;
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.data.borrow())?;
 
     let _launch_bump_seed =
         accounts::check_program_data_account(ctx.accounts.launch_data, program_id, vec![launch_data.page_name.as_bytes(), b"Launch"]).unwrap();
@@ -111,7 +111,7 @@ pub fn claim_refund<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
 
     join_data.ticket_status = TicketStatus::FullyRefunded;
     join_data.num_tickets = 0;
-    join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+    join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
 
     let joiner_account_lamports = **ctx.accounts.join_data.try_borrow_lamports()?;
     msg!("close joiner account for {} lamports", joiner_account_lamports);
@@ -24,7 +24,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
     }
 
     // check the game account is the same as is on chain
-    let mut launch_data = LaunchData::try_from_slice(&ctx.accounts.launch_data.data.borrow()[..])?;
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.data.borrow())?;
     let listing = Listing::try_from_slice(&ctx.accounts.listing.data.borrow()[..])?;
 
     let _player_data_bump =
@@ -98,7 +98,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
 
     launch_data.num_interactions += 1;
 
-    let mut join_data = JoinData::try_from_slice(&ctx.accounts.join_data.data.borrow()[..])?;
+    let mut join_data = JoinData::load(&ctx.accounts.join_data.data.borrow())?;
 
     // user must be the joiner
     if ctx.accounts.user.key != &join_data.joiner_key {
@@ -152,7 +152,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
 
         // if there were no winning tickets, just close the account
         if winning_tickets == 0 {
-            join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+            join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
 
             let joiner_account_lamports = **ctx.accounts.join_data.try_borrow_lamports()?;
             msg!("close joiner account for {} lamports", joiner_account_lamports);
@@ -164,7 +164,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
         // if the LP hasn't been launched yet this is all we can do
         if launch_data.flags[LaunchFlags::LPState as usize] != 2 {
             msg!("LP not yet set up cannot claim tokens");
-            join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+            join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
             return Ok(());
         }
     }
@@ -175,7 +175,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
     // if the LP hasn't been launched yet this is all we can do
     if launch_data.flags[LaunchFlags::LPState as usize] != 2 {
         msg!("LP not yet set up cannot claim tokens");
-        join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+        join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
         return Ok(());
     }
 
@@ -199,6 +199,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
     let winning_ticket_amount = match launch_data.launch_meta {
         LaunchMeta::Raffle(_) => (winning_tickets as u64) * one_ticket_tokens,
         LaunchMeta::FCFS(_) => (winning_tickets as u64) * one_ticket_tokens,
+        LaunchMeta::DutchAuction(_) => (winning_tickets as u64) * one_ticket_tokens,
         LaunchMeta::IDO(props) => {
             let user_percent: f64 = join_data.num_tickets as f64 / launch_data.tickets_sold as f64;
             let mut total_percent: f64 = props.token_fraction_distributed + user_percent;
@@ -255,6 +256,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
             &mut launch_data,
             &listing,
             total_sol_collected,
+            None,
         )?;
         
         // Emit events after pool creation
@@ -278,7 +280,7 @@ pub fn claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
     }
 
     launch_data.serialize(&mut &mut ctx.accounts.launch_data.data.borrow_mut()[..])?;
-    join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+    join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
 
     let joiner_account_lamports = **ctx.accounts.join_data.try_borrow_lamports()?;
     msg!("close joiner account for {} lamports", joiner_account_lamports);
@@ -1,4 +1,4 @@
-use borsh::{to_vec, BorshDeserialize, BorshSerialize};
+use borsh::{to_vec, BorshSerialize};
 use solana_program::{
     account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program::invoke_signed, program_error::ProgramError, program_pack::Pack,
     pubkey::Pubkey, sysvar::Sysvar,
@@ -7,7 +7,7 @@ use solana_program::{
 use crate::{
     accounts,
     instruction::{accounts::CreateLaunchAccounts, CreateArgs},
-    launch::{Distribution, LaunchData, LaunchFlags, LaunchKeys, LaunchMeta, LaunchPlugin, Listing, Raffle, WhiteListToken, FCFS, IDO},
+    launch::{Distribution, LaunchData, LaunchFlags, LaunchKeys, LaunchMeta, LaunchPlugin, LAUNCH_DATA_VERSION, Listing, Raffle, WhiteListToken, FCFS, IDO},
     state,
     utils::{self, calculate_rent, create_2022_token},
     events::emit_launch_created_event,
@@ -223,11 +223,11 @@ pub fn create_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], a
         &[&[&accounts::SOL_SEED.to_le_bytes(), &[pda_sol_bump_seed]]],
     )?;
 
-    let mut program_data = state::ProgramData::try_from_slice(&ctx.accounts.cook_data.data.borrow()[..])?;
+    let mut program_data = state::ProgramData::load(&ctx.accounts.cook_data.data.borrow())?;
 
     program_data.num_launches += 1;
 
-    program_data.serialize(&mut &mut ctx.accounts.cook_data.data.borrow_mut()[..])?;
+    program_data.store(&mut ctx.accounts.cook_data.data.borrow_mut())?;
 
     msg!("create game account");
 
@@ -274,6 +274,7 @@ pub fn create_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], a
 
     let mut launch_data = LaunchData {
         account_type: state::AccountType::Launch,
+        version: LAUNCH_DATA_VERSION,
         launch_meta: meta,
         plugins: launch_plugins,
         last_interaction: last_interaction,
@@ -301,6 +302,9 @@ pub fn create_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], a
         flags: Vec::with_capacity(LaunchFlags::LENGTH as usize),
         strings: Vec::new(),
         keys: Vec::with_capacity(LaunchKeys::LENGTH as usize),
+        creator: *ctx.accounts.user.key,
+        upvotes: 0,
+        downvotes: 0,
         
         // Instant launch fields (pump.fun-style bonding curve)
         is_tradable: false, // Raffle launches start non-tradable until graduation
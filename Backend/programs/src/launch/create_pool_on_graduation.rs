@@ -15,24 +15,53 @@ use solana_program::{
     pubkey::Pubkey,
 };
 use std::str::FromStr;
-use crate::launch::{LaunchData, LaunchFlags, LaunchKeys};
+use crate::accounts;
+use crate::amm::CookVenue;
+use crate::launch::{get_ido_token_split, LaunchData, LaunchFlags, LaunchKeys};
+use crate::utils;
+
+/// Accounts that fund the Cook DEX pool on graduation: the SOL comes out of the launch account
+/// and the tokens out of the cook_pda's supply
+pub struct CookPoolSeed<'a, 'b> {
+    pub venue: CookVenue<'a, 'b>,
+    pub launch: &'b AccountInfo<'a>,
+    pub cook_pda: &'b AccountInfo<'a>,
+    pub cook_pda_bump: u8,
+    pub cook_base_token: &'b AccountInfo<'a>,
+    pub base_token_mint: &'b AccountInfo<'a>,
+    pub base_token_program: &'b AccountInfo<'a>,
+    pub quote_token_program: &'b AccountInfo<'a>,
+}
 
 /// Create liquidity pool when raffle graduates (threshold met, first claim)
 /// OR when instant launch graduates (market cap threshold met)
+/// OR when an IDO settles (soft cap met), which needs `cook_pool` to seed the Cook DEX pool
 pub fn create_pool_on_graduation<'a>(
     program_id: &Pubkey,
     accounts: &'a [AccountInfo<'a>],
     launch_data: &mut LaunchData,
     listing: &crate::launch::Listing,
     total_sol_collected: u64,
+    cook_pool: Option<&CookPoolSeed<'a, '_>>,
 ) -> ProgramResult {
     msg!("🚀 Creating liquidity pool on graduation...");
     
     // Check if this is a raffle launch or instant launch
     let is_raffle = matches!(launch_data.launch_meta, crate::launch::LaunchMeta::Raffle(_));
     let is_instant = matches!(launch_data.launch_meta, crate::launch::LaunchMeta::FCFS(_));
+    let is_ido = matches!(launch_data.launch_meta, crate::launch::LaunchMeta::IDO(_));
     
-    if is_raffle {
+    if is_ido {
+        // IDO SETTLEMENT: the soft cap decides between a pool and refunds, so it must be met here
+        if total_sol_collected == 0 || total_sol_collected < launch_data.minimum_liquidity {
+            msg!("❌ IDO soft cap not met: {} < {}", total_sol_collected, launch_data.minimum_liquidity);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if cook_pool.is_none() {
+            msg!("❌ IDO proceeds can only be seeded into a Cook DEX pool");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    } else if is_raffle {
         // RAFFLE GRADUATION: Check liquidity threshold
         let minimum_liquidity = launch_data.minimum_liquidity;
         if minimum_liquidity > 0 && total_sol_collected < minimum_liquidity {
@@ -77,7 +106,7 @@ pub fn create_pool_on_graduation<'a>(
 
     // Get DEX provider from launch data (buffer1 stores amm_provider: 0 = Cook, 1 = Raydium, 2 = Both)
     let dex_provider = launch_data.buffer1; 
-    let create_cook = dex_provider == 0 || dex_provider == 2;
+    let create_cook = dex_provider == 0 || dex_provider == 2 || is_ido;
     let create_raydium = dex_provider == 1 || dex_provider == 2;
     
     msg!("📊 DEX Provider config: {}", 
@@ -88,14 +117,17 @@ pub fn create_pool_on_graduation<'a>(
     // Calculate liquidity amounts
     // For raffles: 50% of SOL collected from ticket sales
     // For instant launches: Use SOL in AMM pool (accumulated from bonding curve trades)
-    let liquidity_sol_amount = if is_instant && accounts.len() > 2 {
+    // For IDOs: 100% of the proceeds, against the LP share of the supply
+    let total_token_amount = launch_data.total_supply * u64::pow(10, listing.decimals as u32);
+    let (liquidity_sol_amount, liquidity_token_amount) = if is_ido {
+        (total_sol_collected, get_ido_token_split(total_token_amount, &launch_data.distribution).1)
+    } else if is_instant && accounts.len() > 2 {
         // For instant launches, use AMM pool balance
         let amm_sol = **accounts[2].lamports.borrow();
-        amm_sol / 2 // Use 50% of AMM pool for liquidity
+        (amm_sol / 2, total_token_amount / 2) // Use 50% of AMM pool for liquidity
     } else {
-        total_sol_collected / 2 // For raffles, use 50% of collected SOL
+        (total_sol_collected / 2, total_token_amount / 2) // For raffles, use 50% of collected SOL
     };
-    let liquidity_token_amount = (launch_data.total_supply * u64::pow(10, listing.decimals as u32)) / 2;
 
     msg!("💰 Creating pool with {} SOL and {} tokens", liquidity_sol_amount, liquidity_token_amount);
 
@@ -114,6 +146,7 @@ pub fn create_pool_on_graduation<'a>(
             listing,
             liquidity_sol_amount,
             liquidity_token_amount,
+            cook_pool,
         )?;
         
         // Store Cook DEX pool address
@@ -138,6 +171,9 @@ pub fn create_pool_on_graduation<'a>(
     }
 
     // Update launch data
+    if launch_data.flags.len() < LaunchFlags::LENGTH as usize {
+        launch_data.flags.resize(LaunchFlags::LENGTH as usize, 0);
+    }
     launch_data.flags[LaunchFlags::LPState as usize] = 2; // Set LP state to "set up"
     launch_data.is_tradable = true;
     
@@ -145,20 +181,20 @@ pub fn create_pool_on_graduation<'a>(
     Ok(())
 }
 
-/// Create Cook DEX pool
+/// Create Cook DEX pool, seeding its vaults when `cook_pool` is given
 /// Returns the pool address (AMM PDA)
 fn create_cook_dex_pool<'a>(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
+    _accounts: &'a [AccountInfo<'a>],
     launch_data: &LaunchData,
     listing: &crate::launch::Listing,
-    _sol_amount: u64,
-    _token_amount: u64,
+    sol_amount: u64,
+    token_amount: u64,
+    cook_pool: Option<&CookPoolSeed<'a, '_>>,
 ) -> Result<Pubkey, ProgramError> {
     msg!("🍳 Creating Cook DEX pool...");
 
     // Get base and quote token mints
-    let base_token_mint = listing.mint; // Base token mint from listing
     let quote_token_mint = launch_data.keys[LaunchKeys::WSOLAddress as usize]; // WSOL
     
     // For Cook DEX, the pool is the AMM PDA
@@ -180,6 +216,11 @@ fn create_cook_dex_pool<'a>(
     
     let (amm_pda, _bump) = Pubkey::find_program_address(&amm_seeds, program_id);
     msg!("📍 Cook DEX AMM PDA: {}", amm_pda);
+
+    if let Some(seed) = cook_pool {
+        seed_cook_dex_pool(program_id, listing, seed, sol_amount, token_amount)?;
+        return Ok(*seed.venue.amm.key);
+    }
     
     // Note: Actual AMM initialization should be done via InitCookAMM instruction
     // This function derives and returns the pool address
@@ -189,6 +230,57 @@ fn create_cook_dex_pool<'a>(
     Ok(amm_pda)
 }
 
+/// Moves `sol_amount` from the launch account and `token_amount` from the cook_pda into the pool vaults.
+/// No LP tokens are minted for them, the AMM treats reserves seeded before any deposit as locked liquidity.
+fn seed_cook_dex_pool<'a>(
+    program_id: &Pubkey,
+    listing: &crate::launch::Listing,
+    seed: &CookPoolSeed<'a, '_>,
+    sol_amount: u64,
+    token_amount: u64,
+) -> ProgramResult {
+    if seed.base_token_mint.key != &listing.mint {
+        msg!("Token mint does not match the listing");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if seed.quote_token_program.key != &spl_token::id() {
+        msg!("Wrapped SOL vaults belong to the spl token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    seed.venue.checked_reserves(program_id, &listing.mint)?;
+    if seed.venue.total_liquidity()? != 0 {
+        msg!("Cook DEX pool already has liquidity providers, it cannot be seeded at a new price");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    utils::transfer_tokens(
+        seed.base_token_program.key == &spl_token_2022::id(),
+        token_amount,
+        seed.cook_base_token,
+        seed.base_token_mint,
+        seed.venue.amm_base,
+        seed.cook_pda,
+        seed.base_token_program,
+        seed.cook_pda_bump,
+        &vec![&accounts::SOL_SEED.to_le_bytes()],
+        listing.decimals,
+        &Vec::new(),
+    )?;
+
+    // the launch account is owned by this program, so its lamports can be moved straight into the wsol vault
+    **seed.launch.try_borrow_mut_lamports()? -= sol_amount;
+    **seed.venue.amm_quote.try_borrow_mut_lamports()? += sol_amount;
+    invoke_signed(
+        &spl_token::instruction::sync_native(seed.quote_token_program.key, seed.venue.amm_quote.key)?,
+        &[seed.venue.amm_quote.clone(), seed.quote_token_program.clone()],
+        &[],
+    )?;
+
+    msg!("✅ Seeded Cook DEX pool with {} lamports and {} tokens", sol_amount, token_amount);
+    Ok(())
+}
+
 /// Create Raydium pool via CPI
 /// Returns the pool address
 fn create_raydium_pool<'a>(
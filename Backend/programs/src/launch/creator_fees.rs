@@ -0,0 +1,182 @@
+/**
+ * Creator fees.
+ *
+ * A launch created with a creator fee carries a CreatorFee plugin, and every SwapCookAMM trade of it
+ * pays that share of the trade's SOL into the launch's CreatorFees vault at [launch, b"CreatorFees"],
+ * on top of the protocol's swap fee. The share is capped by the fee schedule's max_creator_fee_bps,
+ * both when the launch is created and on every trade, so lowering the cap applies to existing launches.
+ * The vault is created by the first trade that pays into it and holds the fees as SOL until anyone
+ * sweeps them to the launch creator with ClaimCreatorFees.
+ */
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+    system_instruction, sysvar::Sysvar,
+};
+
+use crate::{
+    common, events,
+    instruction::accounts::ClaimCreatorFeesAccounts,
+    launch::{get_creator_fee, CreatorFee, LaunchAccessor, LaunchPlugin},
+    state::{AccountType, FeeConfig},
+    utils,
+};
+
+pub const CREATOR_FEES_SEED: &[u8] = b"CreatorFees";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct CreatorFees {
+    pub account_type: AccountType,
+    pub launch: Pubkey,
+    pub accrued: u64, // lamports paid in by trades so far
+    pub claimed: u64, // lamports of `accrued` swept to the creator so far
+}
+
+impl CreatorFees {
+    pub const LEN: usize = 1 + 32 + 8 * 2;
+}
+
+pub fn get_creator_fees_address(launch: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&launch.to_bytes(), CREATOR_FEES_SEED], program_id)
+}
+
+/// The CreatorFee plugin of a new launch taking `creator_fee_bps` of its swaps, none for a launch without one
+pub fn get_creator_fee_plugin(creator_fee_bps: u16, fee_config: &FeeConfig) -> Result<Option<LaunchPlugin>, ProgramError> {
+    if creator_fee_bps == 0 {
+        return Ok(None);
+    }
+    if creator_fee_bps > fee_config.max_creator_fee_bps {
+        msg!("Creator fee of {} bps is above the {} bps the fee schedule allows", creator_fee_bps, fee_config.max_creator_fee_bps);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    msg!("💸 Creator fee of {} bps on every swap", creator_fee_bps);
+    Ok(Some(LaunchPlugin::CreatorFee(CreatorFee { bps: creator_fee_bps })))
+}
+
+/// Share of a launch's swaps its creator earns, in basis points, under the fee schedule in force
+pub fn get_creator_fee_bps(plugins: &[LaunchPlugin], fee_config: &FeeConfig) -> u16 {
+    get_creator_fee(plugins).map_or(0, |creator_fee| creator_fee.bps.min(fee_config.max_creator_fee_bps))
+}
+
+/// Pays `amount` lamports from the payer into the launch's CreatorFees vault, creating it if this is its first fee
+pub fn accrue_creator_fee<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    launch: &Pubkey,
+    creator_fees: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let (expected_address, bump) = get_creator_fees_address(launch, program_id);
+    if creator_fees.key != &expected_address {
+        msg!("expected creator fees vault {} {}", expected_address, creator_fees.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut vault = if **creator_fees.try_borrow_lamports()? > 0 {
+        if creator_fees.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        CreatorFees::try_from_slice(&creator_fees.try_borrow_data()?)?
+    } else {
+        utils::create_program_account(payer, creator_fees, program_id, bump, CreatorFees::LEN, vec![&launch.to_bytes(), CREATOR_FEES_SEED])?;
+        CreatorFees {
+            account_type: AccountType::CreatorFees,
+            launch: *launch,
+            accrued: 0,
+            claimed: 0,
+        }
+    };
+
+    invoke(
+        &system_instruction::transfer(payer.key, creator_fees.key, amount),
+        &[payer.clone(), creator_fees.clone(), system_program.clone()],
+    )?;
+
+    vault.accrued = vault.accrued.saturating_add(amount);
+    vault.serialize(&mut &mut creator_fees.data.borrow_mut()[..])?;
+
+    msg!("💸 {} lamports creator fee accrued, {} unclaimed", amount, vault.accrued - vault.claimed);
+    Ok(())
+}
+
+/// ClaimCreatorFees: sweeps everything a launch's CreatorFees vault holds above its rent to the launch creator.
+/// Anyone can crank it, the fees only ever go to the creator.
+pub fn claim_creator_fees<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<ClaimCreatorFeesAccounts> = ClaimCreatorFeesAccounts::context(accounts)?;
+
+    if ctx.accounts.launch_data.owner != program_id || ctx.accounts.creator_fees.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    common::check_not_paused(program_id, Some(ctx.accounts.cook_data))?;
+    common::check_launch_not_paused(ctx.accounts.launch_data)?;
+
+    let creator = {
+        let launch_data_bytes = ctx.accounts.launch_data.try_borrow_data()?;
+        LaunchAccessor::load(&launch_data_bytes)?.creator(&launch_data_bytes)
+    };
+    if creator != Some(*ctx.accounts.creator.key) {
+        msg!("Creator fees can only be claimed to the launch creator");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut vault = CreatorFees::try_from_slice(&ctx.accounts.creator_fees.try_borrow_data()?)?;
+    if vault.account_type != AccountType::CreatorFees || vault.launch != *ctx.accounts.launch_data.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let (expected_address, _) = get_creator_fees_address(&vault.launch, program_id);
+    if ctx.accounts.creator_fees.key != &expected_address {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let rent_floor = Rent::get()?.minimum_balance(ctx.accounts.creator_fees.data_len());
+    let claimable = ctx.accounts.creator_fees.lamports().saturating_sub(rent_floor);
+    if claimable == 0 {
+        msg!("No creator fees to claim");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    **ctx.accounts.creator_fees.try_borrow_mut_lamports()? -= claimable;
+    **ctx.accounts.creator.try_borrow_mut_lamports()? += claimable;
+
+    vault.claimed = vault.claimed.saturating_add(claimable);
+    vault.serialize(&mut &mut ctx.accounts.creator_fees.data.borrow_mut()[..])?;
+
+    events::emit_creator_fees_claimed_event(&vault.launch, ctx.accounts.creator_fees.key, claimable, ctx.accounts.creator.key);
+    msg!("✅ Claimed {} lamports of creator fees to {}", claimable, ctx.accounts.creator.key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_creator_fee_bps() {
+        let vault = CreatorFees {
+            account_type: AccountType::CreatorFees,
+            launch: Pubkey::new_unique(),
+            accrued: 0,
+            claimed: 0,
+        };
+        assert_eq!(borsh::to_vec(&vault).unwrap().len(), CreatorFees::LEN);
+
+        let fee_config = FeeConfig::default();
+
+        // launches without the plugin pay no creator fee
+        assert_eq!(get_creator_fee_plugin(0, &fee_config).unwrap(), None);
+        assert_eq!(get_creator_fee_bps(&[], &fee_config), 0);
+
+        let plugin = get_creator_fee_plugin(fee_config.max_creator_fee_bps, &fee_config).unwrap().unwrap();
+        assert_eq!(get_creator_fee_bps(&[plugin.clone()], &fee_config), fee_config.max_creator_fee_bps);
+        assert!(get_creator_fee_plugin(fee_config.max_creator_fee_bps + 1, &fee_config).is_err());
+
+        // lowering the cap applies to launches created under a higher one
+        let lowered = FeeConfig {
+            max_creator_fee_bps: 10,
+            ..fee_config
+        };
+        assert_eq!(get_creator_fee_bps(&[plugin], &lowered), 10);
+    }
+}
@@ -0,0 +1,304 @@
+/**
+ * Dutch auction launches.
+ *
+ * The ticket price starts at the auction's start_price on launch_date and falls towards its floor_price,
+ * linearly until end_date or halving its distance to the floor every half_life seconds. Bids are placed
+ * through BuyTickets at the current price, and every ticket wins, so ClaimTokens pays them out without a raffle.
+ *
+ * The auction clears when its last ticket is sold or end_date passes, at the price of the latest bid.
+ * Every bidder pays that clearing price, ClaimTokens refunds whatever they paid above it, and a launch
+ * that clears below its minimum liquidity is failed and fully refunded instead.
+ */
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey,
+    rent::Rent, system_instruction, sysvar::Sysvar,
+};
+
+use crate::{
+    accounts, events,
+    instruction::JoinArgs,
+    launch::{
+        burn_whitelist_tokens, check_allowlist, get_merkle_allowlist, get_whitelist, load_or_create_join_data, resize_join_data, write_launch_data, DutchAuction,
+        JoinData, LaunchAccessor, LaunchData, LaunchFlags, LaunchMeta, LaunchMetaType,
+    },
+    state,
+};
+
+/// Ticket price `elapsed` seconds into an auction that runs for `duration` seconds
+pub fn get_auction_price(auction: &DutchAuction, elapsed: u64, duration: u64) -> u64 {
+    let range = auction.start_price.saturating_sub(auction.floor_price);
+
+    let above_floor = match elapsed.checked_div(auction.half_life) {
+        // a half life of 0 decays linearly
+        None if elapsed >= duration => 0,
+        None => (range as u128 * (duration - elapsed) as u128 / duration as u128) as u64,
+        Some(halvings) if halvings >= u64::BITS as u64 => 0,
+        Some(halvings) => {
+            // linear between two halvings, so the price falls every second
+            let period_start = range >> halvings;
+            let into_period = elapsed % auction.half_life;
+            period_start - ((period_start / 2) as u128 * into_period as u128 / auction.half_life as u128) as u64
+        }
+    };
+
+    auction.floor_price + above_floor
+}
+
+/// Whether the auction has cleared, after which its clearing price is final
+pub fn is_auction_cleared(launch_data: &LaunchData, now: u64) -> bool {
+    launch_data.tickets_sold >= launch_data.num_mints || now > launch_data.end_date
+}
+
+/// (clearing price, cleared) of a launch account if it is a dutch auction
+pub fn get_auction_clearing(data: &[u8], now: u64) -> Result<Option<(u64, bool)>, ProgramError> {
+    if LaunchAccessor::load(data)?.launch_meta_type(data) != LaunchMetaType::DutchAuction {
+        return Ok(None);
+    }
+
+    let launch_data = LaunchData::load(data)?;
+    match launch_data.launch_meta {
+        LaunchMeta::DutchAuction(auction) => Ok(Some((auction.clearing_price, is_auction_cleared(&launch_data, now)))),
+        _ => Ok(None),
+    }
+}
+
+/// What a bidder is owed back once the auction has cleared
+pub fn get_auction_refund(paid: u64, num_tickets: u16, clearing_price: u64) -> u64 {
+    paid.saturating_sub(clearing_price * num_tickets as u64)
+}
+
+/// BuyTickets on a dutch auction: bids for `args.num_tickets` tickets at the current price, as long as they cost
+/// no more than `args.amount`. The platform fee is paid on top, so the launch holds exactly what was bid.
+/// Takes the BuyTickets accounts with join_data the bidder's Joiner PDA, which is created on their first bid.
+pub fn bid_dutch_auction<'a>(program_id: &Pubkey, accounts: &[AccountInfo<'a>], args: &JoinArgs) -> ProgramResult {
+    if accounts.len() < 7 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let user = &accounts[0];
+    let launch_account = &accounts[1];
+    let user_sol_account = &accounts[2];
+    let ledger_wallet = &accounts[3];
+    let system_program = &accounts[4];
+    let join_data = &accounts[5];
+    let cook_data = &accounts[6];
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if launch_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let fee_config = accounts::load_fee_config(program_id, cook_data)?;
+    accounts::check_fee_recipient(&fee_config, ledger_wallet)?;
+
+    let mut launch_data = LaunchData::load(&launch_account.try_borrow_data()?)?;
+    let mut auction = match launch_data.launch_meta {
+        LaunchMeta::DutchAuction(auction) => auction,
+        _ => {
+            msg!("Launch is not a dutch auction");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    };
+    if launch_data.flags.get(LaunchFlags::LaunchFailed as usize) == Some(&1) {
+        msg!("Auction has failed");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    if current_time < launch_data.launch_date || is_auction_cleared(&launch_data, current_time) {
+        msg!(
+            "Auction is not open (launch_date: {}, end_date: {}, current: {}, {} of {} tickets sold)",
+            launch_data.launch_date,
+            launch_data.end_date,
+            current_time,
+            launch_data.tickets_sold,
+            launch_data.num_mints
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let num_tickets = u32::min(args.num_tickets as u32, launch_data.num_mints - launch_data.tickets_sold);
+    if num_tickets == 0 {
+        msg!("No tickets to bid for");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let price = get_auction_price(
+        &auction,
+        current_time - launch_data.launch_date,
+        launch_data.end_date.saturating_sub(launch_data.launch_date),
+    );
+    let cost = price * num_tickets as u64;
+    if cost > args.amount {
+        msg!("{} tickets cost {} lamports at {} each, more than the {} bid", num_tickets, cost, price, args.amount);
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    if let Some(whitelist) = get_whitelist(&launch_data.plugins) {
+        burn_whitelist_tokens(&whitelist, current_time, num_tickets as u64, user, accounts.get(7), accounts.get(8), accounts.get(9))?;
+    }
+
+    let mut join_data_struct = load_or_create_join_data(program_id, user, join_data, &launch_data.page_name)?;
+    let total_tickets = join_data_struct.num_tickets.checked_add(num_tickets as u16).ok_or_else(|| {
+        msg!("Too many tickets for one wallet");
+        ProgramError::InvalidInstructionData
+    })?;
+
+    if let Some(allowlist) = get_merkle_allowlist(&launch_data.plugins) {
+        join_data_struct.allowlist_spent = check_allowlist(
+            &allowlist,
+            current_time,
+            user.key,
+            args.allowlist.as_ref(),
+            join_data_struct.allowlist_spent,
+            cost,
+        )?;
+    }
+
+    let fee_amount = state::get_fee(cost, fee_config.ticket_fee_bps);
+    if fee_amount > 0 {
+        invoke(
+            &system_instruction::transfer(user_sol_account.key, ledger_wallet.key, fee_amount),
+            &[user_sol_account.clone(), ledger_wallet.clone(), system_program.clone()],
+        )?;
+    }
+    invoke(
+        &system_instruction::transfer(user_sol_account.key, launch_account.key, cost),
+        &[user_sol_account.clone(), launch_account.clone(), system_program.clone()],
+    )?;
+
+    // the price only falls, so the latest bid always sets the clearing price
+    auction.clearing_price = price;
+    launch_data.launch_meta = LaunchMeta::DutchAuction(auction);
+    launch_data.tickets_sold += num_tickets;
+    launch_data.num_interactions = launch_data.num_interactions.saturating_add(1);
+    launch_data.last_interaction = current_time as i64;
+    write_launch_data(user, launch_account, system_program, &launch_data)?;
+
+    // every bid wins, so ClaimTokens takes these tickets without a CheckTickets
+    join_data_struct.num_tickets = total_tickets;
+    join_data_struct.num_tickets_checked = total_tickets;
+    join_data_struct.num_winning_tickets = total_tickets;
+    join_data_struct.auction_paid += cost;
+    join_data_struct.last_slot = clock.slot;
+    resize_join_data(user, join_data, system_program, join_data_struct.size()?)?;
+    join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
+
+    msg!("✅ Bid for {} tickets at {} lamports each", num_tickets, price);
+
+    if launch_data.tickets_sold >= launch_data.num_mints {
+        events::emit_auction_cleared_event(launch_account.key, price, launch_data.tickets_sold, price * launch_data.tickets_sold as u64);
+    }
+    Ok(())
+}
+
+/// Part of ClaimTokens on a dutch auction: refunds the bidder whatever they paid above the clearing price.
+/// The first claim of an auction that cleared at end_date rather than selling out announces the clearing price.
+pub fn refund_auction_overpayment(
+    program_id: &Pubkey,
+    user: &AccountInfo,
+    launch_account: &AccountInfo,
+    join_data: &AccountInfo,
+    first_claim: bool,
+) -> ProgramResult {
+    if launch_account.owner != program_id || join_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let launch_data = LaunchData::load(&launch_account.try_borrow_data()?)?;
+    let clearing_price = match launch_data.launch_meta {
+        LaunchMeta::DutchAuction(auction) => auction.clearing_price,
+        _ => {
+            msg!("Launch is not a dutch auction");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    };
+
+    let mut join_data_struct = JoinData::load(&join_data.try_borrow_data()?)?;
+    if join_data_struct.joiner_key != *user.key || join_data_struct.page_name != launch_data.page_name {
+        msg!("JoinData does not belong to user for this launch");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if first_claim && launch_data.tickets_sold < launch_data.num_mints {
+        events::emit_auction_cleared_event(
+            launch_account.key,
+            clearing_price,
+            launch_data.tickets_sold,
+            clearing_price * launch_data.tickets_sold as u64,
+        );
+    }
+
+    let refund_amount = get_auction_refund(join_data_struct.auction_paid, join_data_struct.num_winning_tickets, clearing_price);
+    if refund_amount == 0 {
+        return Ok(());
+    }
+
+    let rent_floor = Rent::get()?.minimum_balance(launch_account.data_len());
+    if launch_account.lamports().saturating_sub(rent_floor) < refund_amount {
+        msg!("Launch cannot cover a refund of {} lamports", refund_amount);
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    **launch_account.try_borrow_mut_lamports()? -= refund_amount;
+    **user.try_borrow_mut_lamports()? += refund_amount;
+
+    join_data_struct.auction_paid -= refund_amount;
+    join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
+
+    msg!("✅ Refunded {} lamports paid above the {} lamport clearing price", refund_amount, clearing_price);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auction(half_life: u64) -> DutchAuction {
+        DutchAuction {
+            start_price: 1_000_000,
+            floor_price: 200_000,
+            half_life,
+            clearing_price: 0,
+        }
+    }
+
+    #[test]
+    fn test_linear_decay() {
+        let linear = auction(0);
+        assert_eq!(get_auction_price(&linear, 0, 1_000), 1_000_000);
+        assert_eq!(get_auction_price(&linear, 500, 1_000), 600_000);
+        assert_eq!(get_auction_price(&linear, 1_000, 1_000), 200_000);
+        assert_eq!(get_auction_price(&linear, 5_000, 1_000), 200_000);
+    }
+
+    #[test]
+    fn test_exponential_decay() {
+        let exponential = auction(100);
+        assert_eq!(get_auction_price(&exponential, 0, 1_000), 1_000_000);
+        assert_eq!(get_auction_price(&exponential, 100, 1_000), 600_000);
+        assert_eq!(get_auction_price(&exponential, 200, 1_000), 400_000);
+        assert_eq!(get_auction_price(&exponential, 50, 1_000), 800_000);
+        assert_eq!(get_auction_price(&exponential, 100 * 80, 1_000), 200_000);
+
+        // never rises and never passes the floor
+        let mut last = u64::MAX;
+        for elapsed in (0..3_000).step_by(7) {
+            let price = get_auction_price(&exponential, elapsed, 1_000);
+            assert!(price <= last && price >= 200_000);
+            last = price;
+        }
+    }
+
+    #[test]
+    fn test_auction_refund() {
+        // bids of 2 tickets at 900 and 3 at 600, clearing at 500
+        let paid = 2 * 900 + 3 * 600;
+        assert_eq!(get_auction_refund(paid, 5, 500), paid - 5 * 500);
+        assert_eq!(get_auction_refund(5 * 500, 5, 500), 0);
+    }
+}
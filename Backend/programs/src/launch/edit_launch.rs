@@ -32,7 +32,7 @@ pub fn edit_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
     msg!("get listing data");
     let mut listing = Listing::try_from_slice(&ctx.accounts.listing.data.borrow()[..])?;
     msg!("get launch data");
-    let mut launch_data = LaunchData::try_from_slice(&ctx.accounts.launch_data.data.borrow()[..])?;
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.data.borrow())?;
 
     let _launch_bump_seed =
         accounts::check_program_data_account(ctx.accounts.launch_data, program_id, vec![launch_data.page_name.as_bytes(), b"Launch"]).unwrap();
@@ -0,0 +1,259 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+};
+
+use crate::{
+    accounts,
+    instruction::accounts::FailLaunchAccounts,
+    launch::{write_launch_data, JoinData, LaunchAccessor, LaunchData, LaunchFlags, LaunchMeta, LaunchMetaType, Listing, TicketStatus},
+    state, utils,
+};
+
+/// What a ticket holder gets back from a failed launch, or for losing raffle tickets: everything the launch holds for their tickets.
+/// The platform fee, at the `fee_bps` the tickets were bought at, was paid out to the ledger wallet at
+/// purchase and is not refunded, the crank fee is returned to indexed tickets since the raffle will never be settled.
+pub fn get_failed_refund(ticket_price: u64, num_tickets: u16, fee_bps: u16, paid_crank_fee: bool) -> Result<u64, ProgramError> {
+    let ticket_cost = ticket_price.checked_mul(num_tickets as u64).ok_or(ProgramError::ArithmeticOverflow)?;
+    let platform_fee = state::get_fee(ticket_cost, fee_bps);
+    let crank_fee = if paid_crank_fee {
+        state::RAFFLE_CRANK_FEE.checked_mul(num_tickets as u64).ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        0
+    };
+
+    (ticket_cost - platform_fee).checked_add(crank_fee).ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// FailLaunch: anyone can fail a launch once it has ended without selling enough tickets or raising its minimum liquidity,
+/// which for an IDO is its soft cap.
+/// The launch is marked failed for good, which opens full refunds through ClaimRefund and closes every other path.
+/// If the launch holds its supply, passing cook_pda, listing, base mint, cook base token and base token program after
+/// the fixed accounts burns the unsold supply.
+pub fn fail_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<FailLaunchAccounts> = FailLaunchAccounts::context(accounts)?;
+
+    if !ctx.accounts.payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+
+    if ctx.accounts.launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    // legacy launches may not have room for the flag, MigrateLaunch them first
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.try_borrow_data()?)?;
+
+    if launch_data.flags.get(LaunchFlags::LaunchFailed as usize) == Some(&1) {
+        msg!("Launch has already failed");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time <= launch_data.end_date {
+        msg!("Launch is still active (end_date: {}, current: {})", launch_data.end_date, current_time);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // the first token claim opens trading, after which the launch has succeeded
+    if launch_data.is_tradable {
+        msg!("Tokens have already been claimed, cannot fail launch");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // an IDO has no tickets to sell out, only its soft cap to raise, and a dutch auction clears whether it sells out or not
+    let (collected, sold_out) = match launch_data.launch_meta {
+        LaunchMeta::IDO(_) => (launch_data.buffer3, true),
+        LaunchMeta::DutchAuction(auction) => (launch_data.tickets_sold as u64 * auction.clearing_price, true),
+        _ => (
+            launch_data.tickets_sold as u64 * launch_data.ticket_price,
+            launch_data.tickets_sold >= launch_data.num_mints,
+        ),
+    };
+    if sold_out && collected > 0 && collected >= launch_data.minimum_liquidity {
+        msg!(
+            "Launch sold {} of {} tickets and raised {} of {} lamports, it has not failed",
+            launch_data.tickets_sold,
+            launch_data.num_mints,
+            collected,
+            launch_data.minimum_liquidity
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if launch_data.flags.len() < LaunchFlags::LENGTH as usize {
+        launch_data.flags.resize(LaunchFlags::LENGTH as usize, 0);
+    }
+    launch_data.flags[LaunchFlags::LaunchFailed as usize] = 1;
+    launch_data.num_interactions = launch_data.num_interactions.saturating_add(1);
+    launch_data.last_interaction = current_time as i64;
+
+    write_launch_data(ctx.accounts.payer, ctx.accounts.launch_data, ctx.accounts.system_program, &launch_data)?;
+
+    if let [cook_pda, listing, base_token_mint, cook_base_token, base_token_program, ..] = ctx.remaining_accounts {
+        burn_unsold_supply(
+            program_id,
+            &launch_data,
+            cook_pda,
+            listing,
+            base_token_mint,
+            cook_base_token,
+            base_token_program,
+        )?;
+    }
+
+    msg!(
+        "❌ Launch {} failed with {} of {} tickets sold, {} of {} lamports raised",
+        launch_data.page_name,
+        launch_data.tickets_sold,
+        launch_data.num_mints,
+        collected,
+        launch_data.minimum_liquidity
+    );
+    Ok(())
+}
+
+fn burn_unsold_supply<'a>(
+    program_id: &Pubkey,
+    launch_data: &LaunchData,
+    cook_pda: &'a AccountInfo<'a>,
+    listing: &'a AccountInfo<'a>,
+    base_token_mint: &'a AccountInfo<'a>,
+    cook_base_token: &'a AccountInfo<'a>,
+    base_token_program: &'a AccountInfo<'a>,
+) -> ProgramResult {
+    let pda_sol_bump_seed = accounts::check_program_data_account(cook_pda, program_id, vec![&accounts::SOL_SEED.to_le_bytes()])?;
+
+    if listing.key != &launch_data.listing || listing.owner != program_id {
+        msg!("Listing does not match the launch");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let listing_data = Listing::deserialize(&mut &listing.try_borrow_data()?[..])?;
+    if base_token_mint.key != &listing_data.mint {
+        msg!("Token mint does not match the listing");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    accounts::check_token_program_key(base_token_program)?;
+    accounts::check_token_account(cook_pda, base_token_mint, cook_base_token, base_token_program)?;
+
+    // nothing has been claimed from a failed launch, so everything the pda holds of the mint is unsold
+    let unsold_supply = utils::get_token_balance(cook_base_token);
+    if unsold_supply == 0 {
+        return Ok(());
+    }
+
+    utils::burn(
+        unsold_supply,
+        base_token_program,
+        base_token_mint,
+        cook_base_token,
+        cook_pda,
+        pda_sol_bump_seed,
+        &vec![&accounts::SOL_SEED.to_le_bytes()],
+    )?;
+
+    msg!("🔥 Burned {} unsold tokens", unsold_supply);
+    Ok(())
+}
+
+/// ClaimRefund on a failed launch: every ticket holder, winner or not, gets a full refund of whatever was not refunded already.
+/// IDO contributors get back everything they contributed after the platform fee, dutch auction bidders everything they bid.
+pub fn refund_failed_launch(program_id: &Pubkey, user: &AccountInfo, launch_data: &AccountInfo, join_data: &AccountInfo) -> ProgramResult {
+    if launch_data.owner != program_id || join_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (page_name, ticket_price, launch_type) = {
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = LaunchAccessor::load(&launch_data_bytes)?;
+        (
+            launch_layout.page_name(&launch_data_bytes).to_string(),
+            launch_layout.ticket_price(&launch_data_bytes),
+            launch_layout.launch_meta_type(&launch_data_bytes),
+        )
+    };
+
+    let mut join_data_struct = JoinData::load(&join_data.try_borrow_data()?)?;
+    if join_data_struct.joiner_key != *user.key || join_data_struct.page_name != page_name {
+        msg!("❌ Error: JoinData does not belong to user for this launch");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if join_data_struct.ticket_status == TicketStatus::FullyRefunded {
+        msg!("❌ Error: Tickets have already been refunded");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let refund_amount = if launch_type == LaunchMetaType::IDO {
+        join_data_struct.ido_contribution
+    } else if launch_type == LaunchMetaType::DutchAuction {
+        join_data_struct.auction_paid
+    } else {
+        // losing tickets refunded before the launch failed only leave the winning ones to refund
+        let tickets_to_refund = if join_data_struct.ticket_status == TicketStatus::LosingRefunded {
+            join_data_struct.num_winning_tickets
+        } else {
+            join_data_struct.num_tickets
+        };
+
+        // the crank fee is only still held for tickets that were never settled
+        let paid_crank_fee = join_data_struct.first_ticket.is_some() && join_data_struct.num_tickets_checked == 0;
+        get_failed_refund(ticket_price, tickets_to_refund, join_data_struct.ticket_fee_bps, paid_crank_fee)?
+    };
+    if refund_amount == 0 {
+        msg!("❌ Error: Nothing to refund");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let rent_floor = Rent::get()?.minimum_balance(launch_data.data_len());
+    if launch_data.lamports().saturating_sub(rent_floor) < refund_amount {
+        msg!("❌ Error: Launch cannot cover a refund of {} lamports", refund_amount);
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    **launch_data.try_borrow_mut_lamports()? -= refund_amount;
+    **user.try_borrow_mut_lamports()? += refund_amount;
+
+    join_data_struct.ticket_status = TicketStatus::FullyRefunded;
+    join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
+
+    msg!("✅ Refunded {} lamports to {} from failed launch {}", refund_amount, user.key, page_name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_refund() {
+        // 0.5% of 3 SOL stays with the platform
+        assert_eq!(get_failed_refund(1_000_000_000, 3, state::PLATFORM_FEE_BPS, false).unwrap(), 2_985_000_000);
+        assert_eq!(get_failed_refund(1_000_000_000, 3, state::PLATFORM_FEE_BPS, true).unwrap(), 2_985_000_000 + 3 * state::RAFFLE_CRANK_FEE);
+
+        // never more than BuyTickets left with the launch
+        let ticket_price = 333_333;
+        let paid = ticket_price * 7;
+        let held = paid - paid * state::PLATFORM_FEE_BPS as u64 / 10000;
+        assert!(get_failed_refund(ticket_price, 7, state::PLATFORM_FEE_BPS, false).unwrap() <= held);
+
+        // tickets keep the fee they were bought at
+        assert_eq!(get_failed_refund(1_000_000_000, 3, 0, false).unwrap(), 3_000_000_000);
+        assert_eq!(get_failed_refund(u64::MAX, 2, 0, false), Err(ProgramError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_losing_ticket_refund() {
+        // 5 tickets bought at 1% with 2 winning, ClaimRefund returns the 3 losing ones less their fee
+        let ticket_price = 123_456_789;
+        let fee_bps = 100;
+        let losing_cost = ticket_price * 3;
+        let refund = get_failed_refund(ticket_price, 3, fee_bps, false).unwrap();
+        assert!(refund < losing_cost);
+        assert_eq!(refund + state::get_fee(losing_cost, fee_bps), losing_cost);
+
+    }
+}
@@ -0,0 +1,209 @@
+/**
+ * Permissionless graduation of an instant launch off its bonding curve.
+ *
+ * Bonding curve trades already settle against the Cook AMM vaults, so once the curve's SOL reaches the
+ * launch's graduation_threshold its SwapCookAMM trades are frozen and anyone can call GraduateLaunch.
+ * The vaults then become the Cook DEX pool: the SOL stays where it is and the reserve tokens are cut
+ * down to what the SOL buys at the curve's final price, with the rest of the unsold supply burned, so
+ * the pool opens at the price the curve closed at. The caller is paid the GRADUATION_BOUNTY out of
+ * the curve's SOL for doing so, and the pool's seeded liquidity is recorded as locked forever in the
+ * launch's LiquidityLock.
+ *
+ * Only the Cook DEX pool is opened: launches that chose Raydium cannot graduate yet, and launches that
+ * chose both are left with LPState 1 until their Raydium pool exists.
+ */
+use solana_program::{account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar};
+
+use crate::{
+    accounts,
+    amm::{self, CookVenue},
+    bonding_curve, events,
+    instruction::accounts::GraduateLaunchAccounts,
+    launch::{load_listing, record_graduation_liquidity, write_launch_data, LaunchData, LaunchFlags, LaunchKeys, LaunchMeta},
+    state,
+};
+
+/// SOL a bonding curve has to hold before it can graduate, launches without a threshold use 30 SOL
+pub fn get_graduation_threshold(graduation_threshold: u64) -> u64 {
+    match graduation_threshold {
+        0 => 30_000_000_000,
+        threshold => threshold,
+    }
+}
+
+/// Tokens that `sol_amount` lamports buy at `final_price` SOL per whole token, which is what the
+/// pool is seeded with. A pool short of reserve tokens takes all of them and opens slightly higher.
+pub fn get_graduation_pool_tokens(sol_amount: u64, base_reserve: u64, final_price: f64, decimals: u8) -> u64 {
+    if final_price <= 0.0 {
+        return base_reserve;
+    }
+    let tokens = (sol_amount as f64 / 1_000_000_000.0) / final_price * 10_f64.powi(decimals as i32);
+    if tokens >= base_reserve as f64 {
+        base_reserve
+    } else {
+        tokens as u64
+    }
+}
+
+/// LPState a graduation leaves the launch in for its DEX provider (0 = Cook, 1 = Raydium, 2 = Both).
+/// GraduateLaunch only opens the Cook DEX pool, so a launch that also wants Raydium is left at 1 rather than
+/// reported as fully set up, and a Raydium only launch cannot graduate until migrating to Raydium is supported.
+pub fn get_graduation_lp_state(dex_provider: u64) -> Result<u8, ProgramError> {
+    match dex_provider {
+        0 => Ok(2),
+        1 => {
+            msg!("Raydium launches cannot graduate until their liquidity can be migrated to Raydium");
+            Err(ProgramError::InvalidAccountData)
+        }
+        2 => {
+            msg!("⚠️ Only the Cook DEX side of this launch's liquidity is created, its Raydium pool is still to be set up");
+            Ok(1)
+        }
+        _ => {
+            msg!("Unknown DEX provider {}", dex_provider);
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+pub fn graduate_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<GraduateLaunchAccounts> = GraduateLaunchAccounts::context(accounts)?;
+
+    if !ctx.accounts.caller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+    accounts::check_wrapped_sol_key(ctx.accounts.quote_token_mint)?;
+    accounts::check_token_program_key(ctx.accounts.base_token_program)?;
+    accounts::check_token_program_key(ctx.accounts.quote_token_program)?;
+
+    if ctx.accounts.launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.try_borrow_data()?)?;
+    if !matches!(launch_data.launch_meta, LaunchMeta::FCFS(_)) {
+        msg!("Only instant launches trade on a bonding curve");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if launch_data.is_graduated {
+        msg!("Launch has already graduated");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let listing = load_listing(program_id, &launch_data, ctx.accounts.listing, ctx.accounts.base_token_mint)?;
+
+    let venue = CookVenue {
+        amm: ctx.accounts.amm,
+        amm_base: ctx.accounts.amm_base,
+        amm_quote: ctx.accounts.amm_quote,
+    };
+    let (base_reserve, quote_reserve) = venue.checked_reserves(program_id, &listing.mint)?;
+
+    let graduation_threshold = get_graduation_threshold(launch_data.graduation_threshold);
+    if quote_reserve < graduation_threshold {
+        msg!("Bonding curve holds {} of the {} lamports it needs to graduate", quote_reserve, graduation_threshold);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if venue.total_liquidity()? != 0 {
+        msg!("Cook DEX pool already has liquidity providers, it cannot be repriced at the curve's final price");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // instant launches keep their DEX provider in the AMMProvider flag rather than buffer1
+    let dex_provider = match launch_data.buffer1 {
+        0 => launch_data.flags.get(LaunchFlags::AMMProvider as usize).copied().unwrap_or(0) as u64,
+        dex_provider => dex_provider,
+    };
+    let lp_state = get_graduation_lp_state(dex_provider)?;
+
+    // the caller's bounty comes out of the curve before the pool is priced
+    let bounty = state::GRADUATION_BOUNTY.min(quote_reserve);
+    venue.withdraw(
+        program_id,
+        ctx.accounts.base_token_mint,
+        ctx.accounts.quote_token_mint,
+        ctx.accounts.amm_base,
+        ctx.accounts.caller_quote,
+        ctx.accounts.base_token_program,
+        ctx.accounts.quote_token_program,
+        0,
+        bounty,
+    )?;
+    let sol_amount = quote_reserve - bounty;
+
+    let final_price = bonding_curve::calculate_price(launch_data.tokens_sold, launch_data.total_supply, listing.decimals)?;
+    let pool_tokens = get_graduation_pool_tokens(sol_amount, base_reserve, final_price, listing.decimals);
+    let burned = base_reserve - pool_tokens;
+    if burned > 0 {
+        venue.burn_base(program_id, ctx.accounts.base_token_mint, ctx.accounts.base_token_program, burned)?;
+    }
+
+    if launch_data.keys.len() < LaunchKeys::LENGTH as usize {
+        launch_data.keys.resize(LaunchKeys::LENGTH as usize, Pubkey::default());
+    }
+    if launch_data.flags.len() < LaunchFlags::LENGTH as usize {
+        launch_data.flags.resize(LaunchFlags::LENGTH as usize, 0);
+    }
+    launch_data.keys[LaunchKeys::CookDEXPool as usize] = *ctx.accounts.amm.key;
+    launch_data.flags[LaunchFlags::LPState as usize] = lp_state;
+    launch_data.is_graduated = true;
+    launch_data.is_tradable = true;
+    launch_data.num_interactions = launch_data.num_interactions.saturating_add(1);
+    launch_data.last_interaction = Clock::get()?.unix_timestamp;
+    write_launch_data(ctx.accounts.caller, ctx.accounts.launch_data, ctx.accounts.system_program, &launch_data)?;
+
+    record_graduation_liquidity(
+        program_id,
+        ctx.accounts.caller,
+        ctx.accounts.launch_data,
+        ctx.accounts.liquidity_lock,
+        ctx.accounts.amm.key,
+        &listing.mint,
+        &launch_data.creator,
+        amm::get_seeded_liquidity(pool_tokens, sol_amount),
+    )?;
+
+    events::emit_bonding_curve_closed_event(&listing.mint);
+    events::emit_pool_created_event(&listing.mint, ctx.accounts.amm.key, 0, sol_amount, pool_tokens);
+    events::emit_trading_started_event(&listing.mint, ctx.accounts.amm.key, 0);
+
+    msg!(
+        "✅ Launch {} graduated with {} lamports and {} tokens at {} SOL per token, {} tokens burned, {} lamports bounty",
+        launch_data.page_name,
+        sol_amount,
+        pool_tokens,
+        final_price,
+        burned,
+        bounty
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graduation_pool_tokens() {
+        // 30 SOL at 0.000001 SOL per token buys 30M tokens of 6 decimals
+        assert_eq!(get_graduation_pool_tokens(30_000_000_000, 500_000_000_000_000, 0.000001, 6), 30_000_000_000_000);
+
+        // a pool without enough reserve tokens takes all of them
+        assert_eq!(get_graduation_pool_tokens(30_000_000_000, 1_000_000, 0.000001, 6), 1_000_000);
+        assert_eq!(get_graduation_pool_tokens(30_000_000_000, 1_000_000, 0.0, 6), 1_000_000);
+
+        assert_eq!(get_graduation_threshold(0), 30_000_000_000);
+        assert_eq!(get_graduation_threshold(85_000_000_000), 85_000_000_000);
+    }
+
+    #[test]
+    fn test_graduation_lp_state() {
+        // a Cook launch is fully set up, one that also wants Raydium only has its Cook side
+        assert_eq!(get_graduation_lp_state(0), Ok(2));
+        assert_eq!(get_graduation_lp_state(2), Ok(1));
+        assert!(get_graduation_lp_state(1).is_err());
+        assert!(get_graduation_lp_state(3).is_err());
+    }
+}
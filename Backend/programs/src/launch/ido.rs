@@ -0,0 +1,438 @@
+/**
+ * IDO launches.
+ *
+ * Contributions of any size are taken until the launch ends, as often as a wallet likes, and the
+ * launch's minimum_liquidity is its soft cap. buffer2 holds an optional hard cap and buffer3 the
+ * lamports raised so far, which are what the launch holds for its contributors after the platform fee.
+ *
+ * Once the IDO has ended, SettleIDO seeds every lamport raised into the Cook DEX pool alongside the LP
+ * share of the supply, which the launch's LiquidityLock records as locked forever, and each contributor
+ * claims their pro-rata share of the tokens for sale.
+ * An IDO that missed its soft cap is failed with FailLaunch and refunded through ClaimRefund instead.
+ */
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey,
+    rent::Rent, system_instruction, sysvar::Sysvar,
+};
+
+use crate::{
+    accounts, common,
+    amm::{self, CookVenue},
+    events,
+    instruction::{
+        accounts::{ClaimIDOTokensAccounts, SettleIDOAccounts},
+        JoinArgs,
+    },
+    launch::{
+        burn_whitelist_tokens, check_allowlist, create_pool_on_graduation, get_merkle_allowlist, get_whitelist, load_or_create_join_data,
+        record_graduation_liquidity, resize_join_data, write_launch_data, CookPoolSeed, Distribution, JoinData, LaunchData, LaunchFlags, LaunchKeys, LaunchMeta, Listing, TicketStatus, IDO,
+    },
+    state, utils,
+};
+
+/// Splits the supply into (tokens for sale, tokens for the pool). Launches created without a
+/// distribution sell half of the supply and pool the other half.
+pub fn get_ido_token_split(total_token_amount: u64, distribution: &[u8]) -> (u64, u64) {
+    if distribution.len() <= Distribution::LP as usize {
+        let for_sale = total_token_amount / 2;
+        return (for_sale, total_token_amount - for_sale);
+    }
+
+    let share = |percent: u8| (total_token_amount as u128 * percent as u128 / 100) as u64;
+    (
+        share(distribution[Distribution::Raffle as usize]),
+        share(distribution[Distribution::LP as usize]),
+    )
+}
+
+/// Splits a contribution of `amount` lamports into (platform fee, lamports raised), keeping what has been
+/// raised within `hard_cap` (0 = uncapped). A contribution cut down by the hard cap only pays the fee on what it raises.
+pub fn get_ido_contribution(amount: u64, raised: u64, hard_cap: u64, fee_bps: u16) -> (u64, u64) {
+    let fee = state::get_fee(amount, fee_bps);
+    let contribution = amount - fee;
+    if hard_cap == 0 || raised.saturating_add(contribution) <= hard_cap {
+        return (fee, contribution);
+    }
+
+    let contribution = hard_cap.saturating_sub(raised);
+    (state::get_fee(contribution, fee_bps), contribution)
+}
+
+/// A contributor's pro-rata share of the tokens for sale
+pub fn get_ido_allocation(tokens_for_sale: u64, contribution: u64, raised: u64) -> u64 {
+    if raised == 0 {
+        return 0;
+    }
+    (tokens_for_sale as u128 * contribution as u128 / raised as u128) as u64
+}
+
+fn is_ido(launch_data: &LaunchData) -> bool {
+    matches!(launch_data.launch_meta, LaunchMeta::IDO(_))
+}
+
+fn is_failed(launch_data: &LaunchData) -> bool {
+    launch_data.flags.get(LaunchFlags::LaunchFailed as usize) == Some(&1)
+}
+
+/// BuyTickets on an IDO: contributes `args.amount` lamports, cut down to whatever is left under the hard cap.
+/// Takes the BuyTickets accounts with join_data the contributor's Joiner PDA, which is created on their first contribution.
+pub fn contribute_ido<'a>(program_id: &Pubkey, accounts: &[AccountInfo<'a>], args: &JoinArgs) -> ProgramResult {
+    if accounts.len() < 7 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let user = &accounts[0];
+    let launch_account = &accounts[1];
+    let user_sol_account = &accounts[2];
+    let ledger_wallet = &accounts[3];
+    let system_program = &accounts[4];
+    let join_data = &accounts[5];
+    let cook_data = &accounts[6];
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if launch_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let fee_config = accounts::load_fee_config(program_id, cook_data)?;
+    accounts::check_fee_recipient(&fee_config, ledger_wallet)?;
+
+    // legacy launches have to be migrated before they can be written as a whole
+    let mut launch_data = LaunchData::load(&launch_account.try_borrow_data()?)?;
+    if !is_ido(&launch_data) {
+        msg!("Launch is not an IDO");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if is_failed(&launch_data) {
+        msg!("IDO has failed");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    if current_time < launch_data.launch_date || current_time > launch_data.end_date {
+        msg!(
+            "IDO is not open (launch_date: {}, end_date: {}, current: {})",
+            launch_data.launch_date,
+            launch_data.end_date,
+            current_time
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (fee_amount, contribution) = get_ido_contribution(args.amount, launch_data.buffer3, launch_data.buffer2, fee_config.ticket_fee_bps);
+    if contribution == 0 {
+        msg!("IDO has raised {} of its {} lamport hard cap, nothing to contribute", launch_data.buffer3, launch_data.buffer2);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // a contribution uses one ticket's worth of whitelist tokens, accounts 7-9 as for BuyTickets
+    if let Some(whitelist) = get_whitelist(&launch_data.plugins) {
+        burn_whitelist_tokens(&whitelist, current_time, 1, user, accounts.get(7), accounts.get(8), accounts.get(9))?;
+    }
+
+    let mut join_data_struct = load_or_create_join_data(program_id, user, join_data, &launch_data.page_name)?;
+
+    if let Some(allowlist) = get_merkle_allowlist(&launch_data.plugins) {
+        join_data_struct.allowlist_spent = check_allowlist(
+            &allowlist,
+            current_time,
+            user.key,
+            args.allowlist.as_ref(),
+            join_data_struct.allowlist_spent,
+            contribution,
+        )?;
+    }
+
+    if fee_amount > 0 {
+        invoke(
+            &system_instruction::transfer(user_sol_account.key, ledger_wallet.key, fee_amount),
+            &[user_sol_account.clone(), ledger_wallet.clone(), system_program.clone()],
+        )?;
+    }
+    invoke(
+        &system_instruction::transfer(user_sol_account.key, launch_account.key, contribution),
+        &[user_sol_account.clone(), launch_account.clone(), system_program.clone()],
+    )?;
+
+    launch_data.buffer3 += contribution;
+    launch_data.num_interactions = launch_data.num_interactions.saturating_add(1);
+    launch_data.last_interaction = current_time as i64;
+    write_launch_data(user, launch_account, system_program, &launch_data)?;
+
+    join_data_struct.ido_contribution += contribution;
+    join_data_struct.last_slot = clock.slot;
+    resize_join_data(user, join_data, system_program, join_data_struct.size()?)?;
+    join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
+
+    msg!(
+        "✅ Contributed {} lamports to IDO {} ({} in total), {} raised",
+        contribution,
+        launch_data.page_name,
+        join_data_struct.ido_contribution,
+        launch_data.buffer3
+    );
+    Ok(())
+}
+
+/// SettleIDO: anyone can settle an IDO that has ended having met its soft cap. Every lamport raised and the
+/// LP share of the supply, which the cook_pda must hold along with the tokens for sale, seed the Cook DEX pool,
+/// and the launch becomes tradable with its contributors able to claim their tokens.
+pub fn settle_ido<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<SettleIDOAccounts> = SettleIDOAccounts::context(accounts)?;
+
+    if !ctx.accounts.payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+
+    if ctx.accounts.launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.try_borrow_data()?)?;
+    if !is_ido(&launch_data) {
+        msg!("Launch is not an IDO");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if is_failed(&launch_data) {
+        msg!("IDO has failed, contributors claim refunds instead");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if launch_data.is_tradable {
+        msg!("IDO has already been settled");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time <= launch_data.end_date {
+        msg!("IDO is still active (end_date: {}, current: {})", launch_data.end_date, current_time);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let raised = launch_data.buffer3;
+    if raised == 0 || raised < launch_data.minimum_liquidity {
+        msg!("IDO raised {} of its {} lamport soft cap, FailLaunch it instead", raised, launch_data.minimum_liquidity);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let rent_floor = Rent::get()?.minimum_balance(ctx.accounts.launch_data.data_len());
+    if ctx.accounts.launch_data.lamports().saturating_sub(rent_floor) < raised {
+        msg!("Launch does not hold the {} lamports it raised", raised);
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let listing = load_listing(program_id, &launch_data, ctx.accounts.listing, ctx.accounts.base_token_mint)?;
+    let cook_pda_bump = accounts::check_program_data_account(ctx.accounts.cook_pda, program_id, vec![&accounts::SOL_SEED.to_le_bytes()])?;
+    accounts::check_token_program_key(ctx.accounts.base_token_program)?;
+    accounts::check_token_account(ctx.accounts.cook_pda, ctx.accounts.base_token_mint, ctx.accounts.cook_base_token, ctx.accounts.base_token_program)?;
+
+    let total_token_amount = launch_data
+        .total_supply
+        .checked_mul(u64::pow(10, listing.decimals as u32))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let (tokens_for_sale, pool_tokens) = get_ido_token_split(total_token_amount, &launch_data.distribution);
+    let supply_held = utils::get_token_balance(ctx.accounts.cook_base_token);
+    if supply_held < tokens_for_sale + pool_tokens {
+        msg!("cook_pda holds {} tokens, the IDO needs {} for sale and {} for the pool", supply_held, tokens_for_sale, pool_tokens);
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let cook_pool = CookPoolSeed {
+        venue: CookVenue {
+            amm: ctx.accounts.amm,
+            amm_base: ctx.accounts.amm_base,
+            amm_quote: ctx.accounts.amm_quote,
+        },
+        launch: ctx.accounts.launch_data,
+        cook_pda: ctx.accounts.cook_pda,
+        cook_pda_bump,
+        cook_base_token: ctx.accounts.cook_base_token,
+        base_token_mint: ctx.accounts.base_token_mint,
+        base_token_program: ctx.accounts.base_token_program,
+        quote_token_program: ctx.accounts.quote_token_program,
+    };
+    create_pool_on_graduation(program_id, ctx.remaining_accounts, &mut launch_data, &listing, raised, Some(&cook_pool))?;
+
+    launch_data.num_interactions = launch_data.num_interactions.saturating_add(1);
+    launch_data.last_interaction = current_time as i64;
+    write_launch_data(ctx.accounts.payer, ctx.accounts.launch_data, ctx.accounts.system_program, &launch_data)?;
+
+    let (base_reserve, quote_reserve) = cook_pool.venue.reserves(&listing.mint)?;
+    record_graduation_liquidity(
+        program_id,
+        ctx.accounts.payer,
+        ctx.accounts.launch_data,
+        ctx.accounts.liquidity_lock,
+        ctx.accounts.amm.key,
+        &listing.mint,
+        &launch_data.creator,
+        amm::get_seeded_liquidity(base_reserve, quote_reserve),
+    )?;
+
+    let pool = launch_data.keys[LaunchKeys::CookDEXPool as usize];
+    events::emit_threshold_met_event(&listing.mint, launch_data.minimum_liquidity, raised, &pool, 0);
+    events::emit_pool_created_event(&listing.mint, &pool, 0, raised, pool_tokens);
+    events::emit_trading_started_event(&listing.mint, &pool, 0);
+
+    msg!("✅ IDO {} settled with {} lamports raised", launch_data.page_name, raised);
+    Ok(())
+}
+
+/// ClaimTokens on a settled IDO: pays the contributor their pro-rata share of the tokens for sale from the cook_pda
+pub fn claim_ido_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<ClaimIDOTokensAccounts> = ClaimIDOTokensAccounts::context(accounts)?;
+
+    if !ctx.accounts.user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+    accounts::check_associated_token_program_key(ctx.accounts.associated_token)?;
+
+    if ctx.accounts.launch_data.owner != program_id || ctx.accounts.join_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    common::check_not_paused(program_id, Some(ctx.accounts.cook_data))?;
+    common::check_launch_not_paused(ctx.accounts.launch_data)?;
+
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.try_borrow_data()?)?;
+    let tokens_distributed = match launch_data.launch_meta {
+        LaunchMeta::IDO(props) => props.tokens_distributed,
+        _ => {
+            msg!("Launch is not an IDO");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    };
+    if is_failed(&launch_data) {
+        msg!("IDO has failed, claim a refund instead");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !launch_data.is_tradable {
+        msg!("IDO has not been settled yet, run SettleIDO first");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut join_data = JoinData::load(&ctx.accounts.join_data.try_borrow_data()?)?;
+    if join_data.joiner_key != *ctx.accounts.user.key || join_data.page_name != launch_data.page_name {
+        msg!("JoinData does not belong to user for this launch");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if join_data.ido_contribution == 0 {
+        msg!("User did not contribute to this IDO");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if join_data.ticket_status == TicketStatus::WinningClaimed {
+        msg!("Tokens have already been claimed");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let listing = load_listing(program_id, &launch_data, ctx.accounts.listing, ctx.accounts.base_token_mint)?;
+    let cook_pda_bump = accounts::check_program_data_account(ctx.accounts.cook_pda, program_id, vec![&accounts::SOL_SEED.to_le_bytes()])?;
+    let base_2022 = accounts::check_token_program_key(ctx.accounts.base_token_program)?;
+    accounts::check_token_account(ctx.accounts.cook_pda, ctx.accounts.base_token_mint, ctx.accounts.cook_base_token, ctx.accounts.base_token_program)?;
+
+    let total_token_amount = launch_data
+        .total_supply
+        .checked_mul(u64::pow(10, listing.decimals as u32))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let (tokens_for_sale, _) = get_ido_token_split(total_token_amount, &launch_data.distribution);
+    let allocation = get_ido_allocation(tokens_for_sale, join_data.ido_contribution, launch_data.buffer3);
+
+    utils::check_and_create_ata(
+        ctx.accounts.user,
+        ctx.accounts.user,
+        ctx.accounts.base_token_mint,
+        ctx.accounts.user_base,
+        ctx.accounts.base_token_program,
+        ctx.accounts.system_program,
+        ctx.accounts.associated_token,
+    )?;
+
+    let transfer_hook_accounts: Vec<&AccountInfo<'a>> = ctx.remaining_accounts.iter().collect();
+    utils::transfer_tokens(
+        base_2022,
+        allocation,
+        ctx.accounts.cook_base_token,
+        ctx.accounts.base_token_mint,
+        ctx.accounts.user_base,
+        ctx.accounts.cook_pda,
+        ctx.accounts.base_token_program,
+        cook_pda_bump,
+        &vec![&accounts::SOL_SEED.to_le_bytes()],
+        listing.decimals,
+        &transfer_hook_accounts,
+    )?;
+
+    let tokens_distributed = tokens_distributed + allocation;
+    launch_data.launch_meta = LaunchMeta::IDO(IDO {
+        token_fraction_distributed: tokens_distributed as f64 / tokens_for_sale as f64,
+        tokens_distributed,
+    });
+    launch_data.num_interactions = launch_data.num_interactions.saturating_add(1);
+    launch_data.last_interaction = Clock::get()?.unix_timestamp;
+    write_launch_data(ctx.accounts.user, ctx.accounts.launch_data, ctx.accounts.system_program, &launch_data)?;
+
+    join_data.ticket_status = TicketStatus::WinningClaimed;
+    join_data.store(&mut ctx.accounts.join_data.try_borrow_mut_data()?)?;
+
+    msg!(
+        "✅ Claimed {} tokens for a contribution of {} of {} lamports",
+        allocation,
+        join_data.ido_contribution,
+        launch_data.buffer3
+    );
+    Ok(())
+}
+
+/// Reads the launch's listing, checking it belongs to the launch and lists `base_token_mint`
+pub fn load_listing(program_id: &Pubkey, launch_data: &LaunchData, listing: &AccountInfo, base_token_mint: &AccountInfo) -> Result<Listing, ProgramError> {
+    if listing.key != &launch_data.listing || listing.owner != program_id {
+        msg!("Listing does not match the launch");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let listing_data = Listing::deserialize(&mut &listing.try_borrow_data()?[..])?;
+    if base_token_mint.key != &listing_data.mint {
+        msg!("Token mint does not match the listing");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(listing_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ido_contribution() {
+        // uncapped, the platform fee comes out of the contribution
+        assert_eq!(get_ido_contribution(1_000_000_000, 0, 0, state::PLATFORM_FEE_BPS), (5_000_000, 995_000_000));
+
+        // cut down to what is left under the hard cap
+        assert_eq!(get_ido_contribution(1_000_000_000, 9_800_000_000, 10_000_000_000, state::PLATFORM_FEE_BPS), (1_000_000, 200_000_000));
+        assert_eq!(get_ido_contribution(1_000_000_000, 10_000_000_000, 10_000_000_000, state::PLATFORM_FEE_BPS), (0, 0));
+    }
+
+    #[test]
+    fn test_ido_allocation() {
+        let supply = 1_000_000_000_000_000;
+        let (for_sale, pool) = get_ido_token_split(supply, &[]);
+        assert_eq!(for_sale + pool, supply);
+        assert_eq!(get_ido_token_split(supply, &[40, 30, 0, 0, 0, 30, 0]), (supply * 40 / 100, supply * 30 / 100));
+
+        // pro-rata shares never hand out more than is for sale
+        let contributions = [995_000_000u64, 333_333_333, 7, 12_345_678_901];
+        let raised: u64 = contributions.iter().sum();
+        let allocated: u64 = contributions.iter().map(|contribution| get_ido_allocation(for_sale, *contribution, raised)).sum();
+        assert!(allocated <= for_sale);
+        assert!(for_sale - allocated < contributions.len() as u64);
+
+        assert_eq!(get_ido_allocation(for_sale, raised, raised), for_sale);
+        assert_eq!(get_ido_allocation(for_sale, 0, 0), 0);
+    }
+}
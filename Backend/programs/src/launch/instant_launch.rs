@@ -1,21 +1,38 @@
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, msg, native_token::LAMPORTS_PER_SOL, 
-    program::{invoke, invoke_signed}, program_error::ProgramError, program_pack::Pack, 
+    program::{invoke, invoke_signed}, program_error::ProgramError, 
     pubkey::Pubkey, rent,
 };
 use spl_token_2022::extension::StateWithExtensions;
 
 use crate::{
     accounts, amm,
+    amm_plugins::{AMMPlugin, LiquidityScaling},
     instruction::{accounts::CreateInstantLaunchAccounts, InstantLaunchArgs},
-    launch::{Listing, LaunchData, LaunchFlags, LaunchKeys, LaunchMeta, LaunchPlugin, FCFS, Distribution},
+    launch::{get_creator_fee_plugin, get_whitelist_plugin, Listing, LaunchData, LaunchFlags, LaunchKeys, LaunchMeta, LaunchPlugin, FCFS, Distribution, LAUNCH_DATA_VERSION},
     state::{self, Socials},
-    utils::{self, calculate_rent, create_2022_token},
+    utils::{self, create_2022_token},
 };
 use solana_program::sysvar::Sysvar;
 use solana_program::clock::Clock;
 
+/// Builds the plugins of an instant launch from its creator fee, allowlist and whitelist args,
+/// the whitelist mint follows the fixed accounts when whitelist_tokens > 0
+pub fn get_instant_launch_plugins(
+    args: &InstantLaunchArgs,
+    fee_config: &state::FeeConfig,
+    whitelist_mint: Option<&AccountInfo>,
+) -> Result<Vec<LaunchPlugin>, ProgramError> {
+    let mut plugins: Vec<LaunchPlugin> = get_creator_fee_plugin(args.creator_fee_bps, fee_config)?.into_iter().collect();
+    if let Some(allowlist) = args.allowlist {
+        msg!("📜 Allowlist phase until {}, default cap {} lamports", allowlist.phase_end, allowlist.default_cap);
+        plugins.push(LaunchPlugin::MerkleAllowlist(allowlist));
+    }
+    plugins.extend(get_whitelist_plugin(whitelist_mint, args.whitelist_tokens, args.whitelist_end)?);
+    Ok(plugins)
+}
+
 pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: InstantLaunchArgs) -> ProgramResult {
     msg!("🚀 Starting instant_launch instruction");
     msg!("📊 Args: name length={}, symbol length={}, page_name length={}", args.name.len(), args.symbol.len(), args.page_name.len());
@@ -138,6 +155,13 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         }
     };
     
+    // Creators can opt in to scaled down fills while the pool is still thin
+    let mut amm_plugins: Vec<AMMPlugin> = Vec::new();
+    if args.liquidity_scaling != 0 {
+        msg!("📉 Liquidity scaling enabled for this pool");
+        amm_plugins.push(AMMPlugin::LiquidityScaling(LiquidityScaling::default()));
+    }
+
     // CRITICAL: Create AMM account EARLY, before validating lp_token_mint which depends on it
     msg!("🔨 Creating AMM account early (Alternative 4 - simplest approach)...");
     amm::init_cook_amm_data(
@@ -148,6 +172,7 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         &amm_seed_keys,
         amm_provider_bytes,
         ctx.accounts.system_program,
+        amm_plugins,
     )?;
     msg!("✅ AMM account created/verified early");
 
@@ -218,7 +243,7 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         msg!("❌ Error: cook_data is not owned by this program");
         return Err(ProgramError::IllegalOwner);
     }
-    let mut program_data = match state::ProgramData::try_from_slice(&ctx.accounts.cook_data.data.borrow()[..]) {
+    let mut program_data = match state::ProgramData::load(&ctx.accounts.cook_data.data.borrow()) {
         Ok(data) => {
             msg!("✅ Successfully deserialized cook_data");
             data
@@ -229,8 +254,9 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         }
     };
 
+    let launch_plugins = get_instant_launch_plugins(&args, &program_data.get_fee_config(), ctx.remaining_accounts.first())?;
     program_data.num_launches += 1;
-    program_data.serialize(&mut &mut ctx.accounts.cook_data.data.borrow_mut()[..])?;
+    program_data.store(&mut ctx.accounts.cook_data.data.borrow_mut())?;
 
     let mut listing = Listing {
         account_type: state::AccountType::Listing,
@@ -300,10 +326,10 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
     let last_interaction = clock.unix_timestamp;
 
     let meta = LaunchMeta::FCFS(FCFS {});
-    let launch_plugins: Vec<LaunchPlugin> = Vec::new();
 
     let mut launch_data = LaunchData {
         account_type: state::AccountType::Launch,
+        version: LAUNCH_DATA_VERSION,
         launch_meta: meta,
         plugins: launch_plugins,
         last_interaction: last_interaction,
@@ -326,6 +352,9 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         flags: Vec::with_capacity(LaunchFlags::LENGTH as usize),
         strings: Vec::new(),
         keys: Vec::with_capacity(LaunchKeys::LENGTH as usize),
+        creator: *ctx.accounts.user.key,
+        upvotes: 0,
+        downvotes: 0,
         is_tradable: true,
         tokens_sold: 0,
         is_graduated: false,
@@ -366,7 +395,7 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         }
         if !ctx.accounts.launch_data.data_is_empty() {
             msg!("⚠️ launch_data account already exists");
-            match LaunchData::try_from_slice(&ctx.accounts.launch_data.data.borrow()[..]) {
+            match LaunchData::load_any_layout(&ctx.accounts.launch_data.data.borrow()) {
                 Ok(existing_data) => {
                     msg!("✅ launch_data already exists with valid data");
                     if existing_data.page_name != args.page_name {
@@ -816,6 +845,20 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
     // AMM account was already created earlier (before lp_token_mint validation)
     msg!("✅ AMM account already created and ready");
 
+    // Fixed size candle store for the pool, filled in by SwapCookAMM
+    if ctx.accounts.price_data.data_is_empty() {
+        utils::create_program_account(
+            ctx.accounts.user,
+            ctx.accounts.price_data,
+            program_id,
+            price_data_bump_seed,
+            crate::candles::PRICE_DATA_LEN,
+            vec![&amm_key_bytes, &num_price_accounts.to_le_bytes(), b"TimeSeries"],
+        )?;
+        crate::candles::init_price_data(&mut ctx.accounts.price_data.try_borrow_mut_data()?)?;
+        msg!("✅ price_data account created");
+    }
+
     let quote_mint_data = ctx.accounts.quote_token_mint.data.borrow();
     let _quote_mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&quote_mint_data)?;
 
@@ -831,4 +874,76 @@ pub fn instant_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
 
     msg!("✅ Instant launch completed successfully");
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::AllowlistProof;
+    use crate::launch::{allowlist_leaf, check_allowlist, get_merkle_allowlist, MerkleAllowlist};
+
+    fn instant_launch_args(allowlist: Option<MerkleAllowlist>) -> InstantLaunchArgs {
+        InstantLaunchArgs {
+            name: "Cook".to_string(),
+            symbol: "COOK".to_string(),
+            uri: String::new(),
+            icon: String::new(),
+            banner: String::new(),
+            total_supply: 1_000_000_000,
+            decimals: 6,
+            ticket_price: 0,
+            page_name: "cook".to_string(),
+            transfer_fee: 0,
+            max_transfer_fee: 0,
+            extensions: 0,
+            amm_provider: 0,
+            launch_type: 0,
+            whitelist_tokens: 0,
+            whitelist_end: 0,
+            description: String::new(),
+            website: String::new(),
+            twitter: String::new(),
+            telegram: String::new(),
+            discord: String::new(),
+            liquidity_scaling: 0,
+            allowlist,
+            creator_fee_bps: 0,
+        }
+    }
+
+    #[test]
+    fn test_instant_launch_allowlist() {
+        let fee_config = state::FeeConfig::default();
+        assert!(get_instant_launch_plugins(&instant_launch_args(None), &fee_config, None).unwrap().is_empty());
+
+        let wallet = Pubkey::new_unique();
+        // a single leaf tree, the root is the wallet's leaf
+        let allowlist = MerkleAllowlist {
+            root: allowlist_leaf(&wallet, 0),
+            phase_end: 100,
+            default_cap: 1_000,
+        };
+        let plugins = get_instant_launch_plugins(&instant_launch_args(Some(allowlist)), &fee_config, None).unwrap();
+        let stored = get_merkle_allowlist(&plugins).unwrap();
+        assert_eq!(stored, allowlist);
+
+        // buys during the phase need a proof
+        let proof = AllowlistProof { cap: 0, proof: vec![] };
+        assert!(check_allowlist(&stored, 50, &wallet, None, 0, 500).is_err());
+        assert!(check_allowlist(&stored, 50, &Pubkey::new_unique(), Some(&proof), 0, 500).is_err());
+        assert_eq!(check_allowlist(&stored, 50, &wallet, Some(&proof), 0, 500).unwrap(), 500);
+        assert_eq!(check_allowlist(&stored, 100, &Pubkey::new_unique(), None, 0, 500).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_instant_launch_whitelist_needs_mint() {
+        let args = InstantLaunchArgs {
+            whitelist_tokens: 1,
+            whitelist_end: 100,
+            ..instant_launch_args(None)
+        };
+        assert_eq!(
+            get_instant_launch_plugins(&args, &state::FeeConfig::default(), None),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+    }
+}
@@ -17,7 +17,7 @@ use crate::{
     accounts,
     instruction::{accounts::BuyTicketsAccounts, JoinArgs},
     launch::{get_launch_plugin_map, JoinData, LaunchData, LaunchKeys, LaunchMeta, LaunchPlugin, LaunchPluginType, Listing, TicketStatus},
-    state::{self, FEE_AMOUNT},
+    state,
     utils,
 };
 
@@ -30,7 +30,7 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
 
     // check the game account is the same as is on chain
     let listing = Listing::try_from_slice(&ctx.accounts.listing.data.borrow()[..])?;
-    let mut launch_data = LaunchData::try_from_slice(&ctx.accounts.launch_data.data.borrow()[..])?;
+    let mut launch_data = LaunchData::load(&ctx.accounts.launch_data.data.borrow())?;
 
     msg!("check player join account");
     let user_join_bump = accounts::check_program_data_account(
@@ -115,6 +115,11 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
             num_winning_tickets: 0,
             random_address: *ctx.accounts.orao_random.key,
             order_id: String::new(), // Will be set after purchase
+            first_ticket: None,      // repeat purchases are not contiguous, so these are checked per join
+            allowlist_spent: 0,
+            ido_contribution: 0,
+            auction_paid: 0,
+            ticket_fee_bps: 0,
         };
 
         let join_data_len = to_vec(&temp)
@@ -134,11 +139,11 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
         )?;
 
         msg!("init join data");
-        temp.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+        temp.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
     }
 
     msg!("get old join data");
-    let mut join_data = JoinData::try_from_slice(&ctx.accounts.join_data.data.borrow()[..])?;
+    let mut join_data = JoinData::load(&ctx.accounts.join_data.data.borrow())?;
 
     if join_data.account_type != state::AccountType::Join {
         join_data.account_type = state::AccountType::Join;
@@ -164,7 +169,9 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
             let max_raffle_tickets_per_wallet: u16 = 1000;
             u16::min(args.num_tickets, max_raffle_tickets_per_wallet.saturating_sub(join_data.num_tickets))
         }
-        LaunchMeta::FCFS(_) => u16::min(args.num_tickets, launch_data.num_mints.saturating_sub(launch_data.tickets_sold) as u16),
+        LaunchMeta::FCFS(_) | LaunchMeta::DutchAuction(_) => {
+            u16::min(args.num_tickets, launch_data.num_mints.saturating_sub(launch_data.tickets_sold) as u16)
+        }
         LaunchMeta::IDO(_) => {
             let max_ido_tickets_per_wallet: u16 = u64::min(50000, launch_data.total_supply) as u16;
             u16::min(args.num_tickets, max_ido_tickets_per_wallet.saturating_sub(join_data.num_tickets) as u16)
@@ -178,7 +185,7 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
     } else {
         join_data.random_address = *ctx.accounts.orao_random.key;
     }
-    join_data.serialize(&mut &mut ctx.accounts.join_data.data.borrow_mut()[..])?;
+    join_data.store(&mut ctx.accounts.join_data.data.borrow_mut())?;
 
     // add points to the user
     user_data.total_points += 25 * tickets_bought as u32;
@@ -206,6 +213,7 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
                     &Vec::new(),
                 )?;
             }
+            _ => {}
         }
     }
 
@@ -214,6 +222,7 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
         LaunchMeta::Raffle(_) => true,
         LaunchMeta::FCFS(_) => false,
         LaunchMeta::IDO(_) => false,
+        LaunchMeta::DutchAuction(_) => false,
     };
     if requires_randomness {
         // if we are on solana then use orao
@@ -302,10 +311,15 @@ pub fn join_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], arg
 
     msg!("transfer fees");
 
-    invoke(
-        &system_instruction::transfer(ctx.accounts.user.key, &ctx.accounts.fees.key, FEE_AMOUNT),
-        &[ctx.accounts.user.clone(), ctx.accounts.fees.clone()],
-    )?;
+    let fee_config = accounts::load_fee_config(program_id, ctx.accounts.cook_data)?;
+    accounts::check_fee_recipient(&fee_config, ctx.accounts.fees)?;
+    let fee_amount = state::get_fee(ticket_cost, fee_config.ticket_fee_bps);
+    if fee_amount > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.accounts.user.key, ctx.accounts.fees.key, fee_amount),
+            &[ctx.accounts.user.clone(), ctx.accounts.fees.clone()],
+        )?;
+    }
 
     launch_data.num_interactions += 1;
     launch_data.tickets_sold += tickets_bought as u32;
@@ -0,0 +1,794 @@
+/**
+ * Zero-copy LaunchData accessor
+ *
+ * Launch accounts on chain have been written by three different structs:
+ *  - `state::NativeLaunchData` (the native CreateLaunch handler, before versioning)
+ *  - `launch::state::LegacyLaunchData` (launch module handlers, before versioning)
+ *  - `launch::state::LaunchData` (everything since, with a version byte after the account type)
+ *
+ * All are borsh encoded and share the same field order, but differ in the
+ * encoding of `listing`, `buffer3`, `distribution`, `keys` and the trailing
+ * creator/vote fields. Deserializing the full struct allocates every
+ * `Vec<String>` member, which is both expensive and fails when the wrong
+ * struct is used, so handlers read and write individual fields through the
+ * offsets computed here instead. Legacy accounts keep working until they
+ * are rewritten by MigrateLaunch.
+ */
+use borsh::BorshDeserialize;
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+use std::str::FromStr;
+
+use crate::launch::{LaunchFlags, LaunchKeys, LaunchMetaType, LaunchPlugin, LAUNCH_DATA_VERSION};
+use crate::state::AccountType;
+
+/// The struct that wrote a launch account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaunchLayout {
+    /// `state::NativeLaunchData`
+    Native,
+    /// `launch::state::LegacyLaunchData`
+    Launch,
+    /// `launch::state::LaunchData`
+    Versioned,
+}
+
+// offsets of the fixed size block that follows `listing`, identical in both layouts
+const TOTAL_SUPPLY: usize = 0;
+const NUM_MINTS: usize = 8;
+const TICKET_PRICE: usize = 12;
+const MINIMUM_LIQUIDITY: usize = 20;
+const LAUNCH_DATE: usize = 28;
+const END_DATE: usize = 36;
+const TICKETS_SOLD: usize = 44;
+const TICKET_CLAIMED: usize = 48;
+const MINTS_WON: usize = 52;
+const BUFFER1: usize = 56;
+const BUFFER2: usize = 64;
+const BUFFER3: usize = 72;
+
+// offsets of the trailing block that follows `keys`, native and versioned launches share theirs
+const NATIVE_CREATOR: usize = 0;
+const NATIVE_UPVOTES: usize = 32;
+const NATIVE_DOWNVOTES: usize = 36;
+const NATIVE_TRADING: usize = 40;
+const LAUNCH_TRADING: usize = 0;
+
+// offsets within the trading block: is_tradable, tokens_sold, is_graduated, graduation_threshold
+const IS_TRADABLE: usize = 0;
+const TOKENS_SOLD: usize = 1;
+const IS_GRADUATED: usize = 9;
+const GRADUATION_THRESHOLD: usize = 10;
+const TRADING_LEN: usize = 18;
+
+/// Field offsets of a single launch account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaunchAccessor {
+    pub layout: LaunchLayout,
+    launch_meta: usize,
+    plugins: usize,
+    last_interaction: usize,
+    num_interactions: usize,
+    page_name: usize,
+    fixed: usize,
+    distribution: usize,
+    flags: usize,
+    keys: usize,
+    tail: usize,
+    /// number of bytes covered by the encoded struct
+    pub len: usize,
+}
+
+/// Bounds checked cursor used to walk the borsh encoding without deserializing it
+struct Cursor<'d> {
+    data: &'d [u8],
+    pos: usize,
+}
+
+impl<'d> Cursor<'d> {
+    fn skip(&mut self, n: usize) -> Result<usize, ProgramError> {
+        let start = self.pos;
+        let end = start.checked_add(n).ok_or(ProgramError::InvalidAccountData)?;
+        if end > self.data.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        self.pos = end;
+        Ok(start)
+    }
+
+    fn read_len(&mut self) -> Result<usize, ProgramError> {
+        let start = self.skip(4)?;
+        Ok(read_u32(self.data, start) as usize)
+    }
+
+    // skips a borsh String, returning the offset of its length prefix
+    fn skip_string(&mut self) -> Result<usize, ProgramError> {
+        let start = self.pos;
+        let len = self.read_len()?;
+        let bytes = self.skip(len)?;
+        std::str::from_utf8(&self.data[bytes..bytes + len]).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(start)
+    }
+
+    // skips a borsh Vec of fixed size elements, returning the offset of its length prefix
+    fn skip_vec(&mut self, element_size: usize) -> Result<usize, ProgramError> {
+        let start = self.pos;
+        let len = self.read_len()?;
+        self.skip(len.checked_mul(element_size).ok_or(ProgramError::InvalidAccountData)?)?;
+        Ok(start)
+    }
+
+    fn skip_string_vec(&mut self) -> Result<usize, ProgramError> {
+        let start = self.pos;
+        let len = self.read_len()?;
+        for _ in 0..len {
+            self.skip_string()?;
+        }
+        Ok(start)
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+// a native launch stores the listing as a base58 string of the pubkey
+fn is_base58_pubkey_string(data: &[u8], offset: usize) -> bool {
+    if offset + 4 > data.len() {
+        return false;
+    }
+    let len = read_u32(data, offset) as usize;
+    if !(32..=44).contains(&len) || offset + 4 + len > data.len() {
+        return false;
+    }
+    data[offset + 4..offset + 4 + len]
+        .iter()
+        .all(|c| c.is_ascii_alphanumeric() && !matches!(c, b'0' | b'O' | b'I' | b'l'))
+}
+
+impl LaunchAccessor {
+    /// Detects the layout of a launch account and computes the offset of every field
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.is_empty() || data[0] != AccountType::Launch as u8 {
+            msg!("Account is not a launch account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // legacy layouts store the launch_meta tag where versioned ones store the version
+        let versioned = data.len() > 1 && data[1] == LAUNCH_DATA_VERSION;
+
+        // the legacy layouts first diverge at `listing`, so find that and use its encoding to pick one
+        let mut cursor = Cursor { data, pos: if versioned { 2 } else { 1 } };
+        let launch_meta = cursor.skip(1)?;
+        match data[launch_meta] {
+            0 | 1 => {}
+            2 => {
+                cursor.skip(16)?;
+            }
+            // dutch auctions only exist in the versioned layout
+            3 if versioned => {
+                cursor.skip(32)?;
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+        let plugins_start = cursor.pos;
+
+        if versioned {
+            let accessor = Self::walk(data, LaunchLayout::Versioned, launch_meta, plugins_start);
+            if accessor.is_err() {
+                msg!("Launch account is truncated");
+            }
+            return accessor;
+        }
+
+        let native = Self::walk(data, LaunchLayout::Native, launch_meta, plugins_start);
+        if let Ok(accessor) = native {
+            if is_base58_pubkey_string(data, accessor.page_name_end(data)) {
+                return Ok(accessor);
+            }
+        }
+
+        let launch = Self::walk(data, LaunchLayout::Launch, launch_meta, plugins_start);
+        if launch.is_err() {
+            msg!("Launch account does not match any known layout");
+        }
+        launch
+    }
+
+    fn walk(data: &[u8], layout: LaunchLayout, launch_meta: usize, plugins_start: usize) -> Result<Self, ProgramError> {
+        let mut cursor = Cursor { data, pos: plugins_start };
+
+        match layout {
+            LaunchLayout::Native => {
+                cursor.skip_vec(1)?;
+            }
+            LaunchLayout::Launch | LaunchLayout::Versioned => {
+                let num_plugins = cursor.read_len()?;
+                for _ in 0..num_plugins {
+                    let mut remaining = &data[cursor.pos..];
+                    let before = remaining.len();
+                    LaunchPlugin::deserialize(&mut remaining).map_err(|_| ProgramError::InvalidAccountData)?;
+                    cursor.skip(before - remaining.len())?;
+                }
+            }
+        }
+
+        let last_interaction = cursor.skip(8)?;
+        let num_interactions = cursor.skip(2)?;
+        let page_name = cursor.skip_string()?;
+
+        match layout {
+            LaunchLayout::Native => cursor.skip_string()?,
+            LaunchLayout::Launch | LaunchLayout::Versioned => cursor.skip(32)?,
+        };
+
+        let fixed = cursor.skip(BUFFER3)?;
+        match layout {
+            LaunchLayout::Native | LaunchLayout::Versioned => cursor.skip(8)?,
+            LaunchLayout::Launch => cursor.skip(4)?,
+        };
+
+        let distribution = match layout {
+            LaunchLayout::Native => cursor.skip_vec(8)?,
+            LaunchLayout::Launch | LaunchLayout::Versioned => cursor.skip_vec(1)?,
+        };
+        let flags = cursor.skip_vec(1)?;
+        cursor.skip_string_vec()?;
+        let keys = match layout {
+            LaunchLayout::Native => cursor.skip_string_vec()?,
+            LaunchLayout::Launch | LaunchLayout::Versioned => cursor.skip_vec(32)?,
+        };
+
+        let tail = match layout {
+            LaunchLayout::Native | LaunchLayout::Versioned => cursor.skip(NATIVE_TRADING + TRADING_LEN)?,
+            LaunchLayout::Launch => cursor.skip(LAUNCH_TRADING + TRADING_LEN)?,
+        };
+
+        Ok(Self {
+            layout,
+            launch_meta,
+            plugins: plugins_start,
+            last_interaction,
+            num_interactions,
+            page_name,
+            fixed,
+            distribution,
+            flags,
+            keys,
+            tail,
+            len: cursor.pos,
+        })
+    }
+
+    fn page_name_end(&self, data: &[u8]) -> usize {
+        self.page_name + 4 + read_u32(data, self.page_name) as usize
+    }
+
+    fn trading(&self) -> usize {
+        match self.layout {
+            LaunchLayout::Native | LaunchLayout::Versioned => self.tail + NATIVE_TRADING,
+            LaunchLayout::Launch => self.tail + LAUNCH_TRADING,
+        }
+    }
+
+    pub fn launch_meta_type(&self, data: &[u8]) -> LaunchMetaType {
+        match data[self.launch_meta] {
+            0 => LaunchMetaType::Raffle,
+            1 => LaunchMetaType::FCFS,
+            2 => LaunchMetaType::IDO,
+            _ => LaunchMetaType::DutchAuction,
+        }
+    }
+
+    /// Launch plugins, native launches stored raw plugin bytes and never had any
+    pub fn plugins(&self, data: &[u8]) -> Vec<LaunchPlugin> {
+        match self.layout {
+            LaunchLayout::Native => Vec::new(),
+            // validated in load
+            LaunchLayout::Launch | LaunchLayout::Versioned => Vec::<LaunchPlugin>::deserialize(&mut &data[self.plugins..]).unwrap(),
+        }
+    }
+
+    pub fn page_name<'d>(&self, data: &'d [u8]) -> &'d str {
+        // validated as utf8 in load
+        std::str::from_utf8(&data[self.page_name + 4..self.page_name_end(data)]).unwrap()
+    }
+
+    pub fn last_interaction(&self, data: &[u8]) -> i64 {
+        read_u64(data, self.last_interaction) as i64
+    }
+
+    pub fn num_interactions(&self, data: &[u8]) -> u16 {
+        u16::from_le_bytes([data[self.num_interactions], data[self.num_interactions + 1]])
+    }
+
+    pub fn total_supply(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + TOTAL_SUPPLY)
+    }
+
+    pub fn num_mints(&self, data: &[u8]) -> u32 {
+        read_u32(data, self.fixed + NUM_MINTS)
+    }
+
+    pub fn ticket_price(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + TICKET_PRICE)
+    }
+
+    pub fn minimum_liquidity(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + MINIMUM_LIQUIDITY)
+    }
+
+    pub fn launch_date(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + LAUNCH_DATE)
+    }
+
+    pub fn end_date(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + END_DATE)
+    }
+
+    pub fn tickets_sold(&self, data: &[u8]) -> u32 {
+        read_u32(data, self.fixed + TICKETS_SOLD)
+    }
+
+    pub fn ticket_claimed(&self, data: &[u8]) -> u32 {
+        read_u32(data, self.fixed + TICKET_CLAIMED)
+    }
+
+    pub fn mints_won(&self, data: &[u8]) -> u32 {
+        read_u32(data, self.fixed + MINTS_WON)
+    }
+
+    pub fn buffer1(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + BUFFER1)
+    }
+
+    pub fn buffer2(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.fixed + BUFFER2)
+    }
+
+    pub fn flags<'d>(&self, data: &'d [u8]) -> &'d [u8] {
+        let len = read_u32(data, self.flags) as usize;
+        &data[self.flags + 4..self.flags + 4 + len]
+    }
+
+    pub fn flag(&self, data: &[u8], index: usize) -> Option<u8> {
+        self.flags(data).get(index).copied()
+    }
+
+    /// Whether FailLaunch has marked the launch failed, which is terminal
+    pub fn is_failed(&self, data: &[u8]) -> bool {
+        self.flag(data, LaunchFlags::LaunchFailed as usize) == Some(1)
+    }
+
+    /// Whether the admin has paused the launch with SetPause
+    pub fn is_paused(&self, data: &[u8]) -> bool {
+        self.flag(data, LaunchFlags::Paused as usize) == Some(1)
+    }
+
+    /// Percentage of the supply allocated to `Distribution` entry `index`
+    pub fn distribution(&self, data: &[u8], index: usize) -> Option<u64> {
+        if index >= read_u32(data, self.distribution) as usize {
+            return None;
+        }
+        match self.layout {
+            LaunchLayout::Native => Some(read_u64(data, self.distribution + 4 + index * 8)),
+            LaunchLayout::Launch | LaunchLayout::Versioned => Some(data[self.distribution + 4 + index] as u64),
+        }
+    }
+
+    /// The listing account of the launch, stored as a base58 string by native launches
+    pub fn listing(&self, data: &[u8]) -> Option<Pubkey> {
+        let start = self.page_name_end(data);
+        match self.layout {
+            LaunchLayout::Native => {
+                let len = read_u32(data, start) as usize;
+                let listing = std::str::from_utf8(&data[start + 4..start + 4 + len]).ok()?;
+                Pubkey::from_str(listing).ok()
+            }
+            LaunchLayout::Launch | LaunchLayout::Versioned => Some(Pubkey::new_from_array(data[start..start + 32].try_into().unwrap())),
+        }
+    }
+
+    /// The launch creator, stored explicitly by native and versioned launches and as the seller key otherwise
+    pub fn creator(&self, data: &[u8]) -> Option<Pubkey> {
+        match self.layout {
+            LaunchLayout::Native | LaunchLayout::Versioned => Some(Pubkey::new_from_array(
+                data[self.tail + NATIVE_CREATOR..self.tail + NATIVE_CREATOR + 32].try_into().unwrap(),
+            )),
+            LaunchLayout::Launch => self.key(data, LaunchKeys::Seller as usize),
+        }
+    }
+
+    /// Whether `launch_key` is the launch PDA of this account's page name and the launch lists `base_mint`
+    pub fn is_launch_of(&self, data: &[u8], launch_key: &Pubkey, base_mint: &Pubkey, program_id: &Pubkey) -> bool {
+        let (expected_launch, _) = Pubkey::find_program_address(&[self.page_name(data).as_bytes(), b"Launch"], program_id);
+        let (expected_listing, _) = Pubkey::find_program_address(&[&base_mint.to_bytes(), b"Listing"], program_id);
+        launch_key == &expected_launch && self.listing(data) == Some(expected_listing)
+    }
+
+    /// Entry of `keys` (native launches store keys as strings)
+    pub fn key(&self, data: &[u8], index: usize) -> Option<Pubkey> {
+        if self.layout == LaunchLayout::Native || index >= read_u32(data, self.keys) as usize {
+            return None;
+        }
+        let start = self.keys + 4 + index * 32;
+        Some(Pubkey::new_from_array(data[start..start + 32].try_into().unwrap()))
+    }
+
+    pub fn upvotes(&self, data: &[u8]) -> Option<u32> {
+        match self.layout {
+            LaunchLayout::Native | LaunchLayout::Versioned => Some(read_u32(data, self.tail + NATIVE_UPVOTES)),
+            LaunchLayout::Launch => None,
+        }
+    }
+
+    pub fn downvotes(&self, data: &[u8]) -> Option<u32> {
+        match self.layout {
+            LaunchLayout::Native | LaunchLayout::Versioned => Some(read_u32(data, self.tail + NATIVE_DOWNVOTES)),
+            LaunchLayout::Launch => None,
+        }
+    }
+
+    pub fn is_tradable(&self, data: &[u8]) -> bool {
+        data[self.trading() + IS_TRADABLE] != 0
+    }
+
+    pub fn tokens_sold(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.trading() + TOKENS_SOLD)
+    }
+
+    pub fn is_graduated(&self, data: &[u8]) -> bool {
+        data[self.trading() + IS_GRADUATED] != 0
+    }
+
+    pub fn graduation_threshold(&self, data: &[u8]) -> u64 {
+        read_u64(data, self.trading() + GRADUATION_THRESHOLD)
+    }
+
+    pub fn set_last_interaction(&self, data: &mut [u8], value: i64) {
+        write_u64(data, self.last_interaction, value as u64);
+    }
+
+    pub fn set_num_interactions(&self, data: &mut [u8], value: u16) {
+        data[self.num_interactions..self.num_interactions + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn set_end_date(&self, data: &mut [u8], value: u64) {
+        write_u64(data, self.fixed + END_DATE, value);
+    }
+
+    pub fn set_tickets_sold(&self, data: &mut [u8], value: u32) {
+        write_u32(data, self.fixed + TICKETS_SOLD, value);
+    }
+
+    pub fn set_ticket_claimed(&self, data: &mut [u8], value: u32) {
+        write_u32(data, self.fixed + TICKET_CLAIMED, value);
+    }
+
+    pub fn set_mints_won(&self, data: &mut [u8], value: u32) {
+        write_u32(data, self.fixed + MINTS_WON, value);
+    }
+
+    pub fn set_flag(&self, data: &mut [u8], index: usize, value: u8) -> Result<(), ProgramError> {
+        if index >= read_u32(data, self.flags) as usize {
+            msg!("Launch flag {} not present", index);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        data[self.flags + 4 + index] = value;
+        Ok(())
+    }
+
+    pub fn set_upvotes(&self, data: &mut [u8], value: u32) -> Result<(), ProgramError> {
+        if self.layout == LaunchLayout::Launch {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        write_u32(data, self.tail + NATIVE_UPVOTES, value);
+        Ok(())
+    }
+
+    pub fn set_downvotes(&self, data: &mut [u8], value: u32) -> Result<(), ProgramError> {
+        if self.layout == LaunchLayout::Launch {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        write_u32(data, self.tail + NATIVE_DOWNVOTES, value);
+        Ok(())
+    }
+
+    pub fn set_is_tradable(&self, data: &mut [u8], value: bool) {
+        data[self.trading() + IS_TRADABLE] = value as u8;
+    }
+
+    pub fn set_tokens_sold(&self, data: &mut [u8], value: u64) {
+        write_u64(data, self.trading() + TOKENS_SOLD, value);
+    }
+
+    pub fn set_is_graduated(&self, data: &mut [u8], value: bool) {
+        data[self.trading() + IS_GRADUATED] = value as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch::{self, WhiteListToken, FCFS};
+    use crate::state;
+
+    fn native_launch() -> state::NativeLaunchData {
+        state::NativeLaunchData {
+            account_type: AccountType::Launch,
+            launch_meta: state::NativeLaunchMeta::IDO { token_fraction_distributed: 7, tokens_distributed: 9 },
+            plugins: vec![3, 4],
+            last_interaction: 1_700_000_000,
+            num_interactions: 3,
+            page_name: "my_launch".to_string(),
+            listing: Pubkey::new_unique().to_string(),
+            total_supply: 1_000_000_000,
+            num_mints: 5,
+            ticket_price: 5_000_000_000,
+            minimum_liquidity: 42,
+            launch_date: 1_700_000_000,
+            end_date: 1_700_086_400,
+            tickets_sold: 17,
+            ticket_claimed: 2,
+            mints_won: 1,
+            buffer1: 1,
+            buffer2: 2,
+            buffer3: 3,
+            distribution: vec![10, 20],
+            flags: vec![0, 1, 2],
+            strings: vec!["name".to_string(), "symbol".to_string(), String::new()],
+            keys: vec![Pubkey::new_unique().to_string()],
+            creator: Pubkey::new_unique(),
+            upvotes: 11,
+            downvotes: 12,
+            is_tradable: true,
+            tokens_sold: 123_456,
+            is_graduated: false,
+            graduation_threshold: 30_000_000_000,
+        }
+    }
+
+    fn program_launch() -> launch::LegacyLaunchData {
+        let mut keys = vec![Pubkey::default(); LaunchKeys::LENGTH as usize];
+        keys[LaunchKeys::Seller as usize] = Pubkey::new_unique();
+        launch::LegacyLaunchData {
+            account_type: AccountType::Launch,
+            launch_meta: launch::LaunchMeta::FCFS(FCFS {}),
+            plugins: vec![LaunchPlugin::WhiteListToken(WhiteListToken {
+                key: Pubkey::new_unique(),
+                quantity: 1,
+                phase_end: 2,
+            })],
+            last_interaction: 1_700_000_000,
+            num_interactions: 1,
+            page_name: "instant".to_string(),
+            listing: Pubkey::new_unique(),
+            total_supply: 1_000_000,
+            num_mints: 0,
+            ticket_price: 500,
+            minimum_liquidity: 0,
+            launch_date: 1_700_000_000,
+            end_date: 0,
+            tickets_sold: 0,
+            ticket_claimed: 0,
+            mints_won: 0,
+            buffer1: 0,
+            buffer2: 0,
+            buffer3: 0,
+            distribution: vec![60, 35, 5, 0, 0, 0, 0],
+            flags: vec![0; 8],
+            strings: Vec::new(),
+            keys,
+            is_tradable: true,
+            tokens_sold: 99,
+            is_graduated: true,
+            graduation_threshold: 30_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_native_layout_fields() {
+        let launch = native_launch();
+        let mut data = borsh::to_vec(&launch).unwrap();
+        // accounts are usually allocated larger than the encoded struct
+        data.extend_from_slice(&[0u8; 64]);
+
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        assert_eq!(accessor.layout, LaunchLayout::Native);
+        assert_eq!(accessor.launch_meta_type(&data), LaunchMetaType::IDO);
+        assert_eq!(accessor.page_name(&data), "my_launch");
+        assert_eq!(accessor.ticket_price(&data), 5_000_000_000);
+        assert_eq!(accessor.num_mints(&data), 5);
+        assert_eq!(accessor.tickets_sold(&data), 17);
+        assert_eq!(accessor.end_date(&data), 1_700_086_400);
+        assert_eq!(accessor.minimum_liquidity(&data), 42);
+        assert_eq!(accessor.flags(&data), &[0, 1, 2]);
+        assert!(accessor.plugins(&data).is_empty());
+        assert_eq!(accessor.creator(&data), Some(launch.creator));
+        assert_eq!(accessor.listing(&data).map(|k| k.to_string()), Some(launch.listing.clone()));
+        assert_eq!(accessor.upvotes(&data), Some(11));
+        assert_eq!(accessor.distribution(&data, 1), Some(20));
+        assert_eq!(accessor.distribution(&data, 2), None);
+        assert_eq!(accessor.tokens_sold(&data), 123_456);
+        assert!(accessor.is_tradable(&data));
+        assert!(!accessor.is_graduated(&data));
+        assert_eq!(accessor.graduation_threshold(&data), 30_000_000_000);
+    }
+
+    #[test]
+    fn test_launch_layout_fields() {
+        let launch = program_launch();
+        let data = borsh::to_vec(&launch).unwrap();
+
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        assert_eq!(accessor.layout, LaunchLayout::Launch);
+        assert_eq!(accessor.len, data.len());
+        assert_eq!(accessor.launch_meta_type(&data), LaunchMetaType::FCFS);
+        assert_eq!(accessor.page_name(&data), "instant");
+        assert_eq!(accessor.ticket_price(&data), 500);
+        assert_eq!(accessor.creator(&data), Some(launch.keys[LaunchKeys::Seller as usize]));
+        assert_eq!(accessor.listing(&data), Some(launch.listing));
+        assert_eq!(accessor.upvotes(&data), None);
+        assert_eq!(accessor.distribution(&data, 2), Some(5));
+        assert_eq!(accessor.tokens_sold(&data), 99);
+        assert!(accessor.is_graduated(&data));
+        assert_eq!(accessor.plugins(&data), launch.plugins);
+        // launches written before the pause flag are never paused
+        assert!(!accessor.is_paused(&data));
+    }
+
+    #[test]
+    fn test_paused_flag() {
+        let mut launch = program_launch();
+        launch.flags = vec![0; LaunchFlags::LENGTH as usize];
+        let mut data = borsh::to_vec(&launch).unwrap();
+
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        assert!(!accessor.is_paused(&data));
+        accessor.set_flag(&mut data, LaunchFlags::Paused as usize, 1).unwrap();
+        assert!(accessor.is_paused(&data));
+        assert!(!accessor.is_failed(&data));
+    }
+
+    #[test]
+    fn test_writes_round_trip() {
+        let mut data = borsh::to_vec(&native_launch()).unwrap();
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        accessor.set_tickets_sold(&mut data, 18);
+        accessor.set_tokens_sold(&mut data, 7);
+        accessor.set_is_graduated(&mut data, true);
+        accessor.set_upvotes(&mut data, 12).unwrap();
+        accessor.set_flag(&mut data, 2, 9).unwrap();
+        assert!(accessor.set_flag(&mut data, 3, 9).is_err());
+
+        let decoded = state::NativeLaunchData::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.tickets_sold, 18);
+        assert_eq!(decoded.tokens_sold, 7);
+        assert!(decoded.is_graduated);
+        assert_eq!(decoded.upvotes, 12);
+        assert_eq!(decoded.flags, vec![0, 1, 9]);
+
+        let mut data = borsh::to_vec(&program_launch()).unwrap();
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        accessor.set_tokens_sold(&mut data, 1_000);
+        accessor.set_mints_won(&mut data, 4);
+        assert!(accessor.set_upvotes(&mut data, 1).is_err());
+
+        let decoded = launch::LegacyLaunchData::try_from_slice(&data).unwrap();
+        assert_eq!(decoded.tokens_sold, 1_000);
+        assert_eq!(decoded.mints_won, 4);
+    }
+
+    #[test]
+    fn test_versioned_layout_fields() {
+        let launch = launch::LaunchData::from(program_launch());
+        let mut data = borsh::to_vec(&launch).unwrap();
+        let encoded_len = data.len();
+        data.extend_from_slice(&[0u8; 64]);
+
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        assert_eq!(accessor.layout, LaunchLayout::Versioned);
+        assert_eq!(accessor.len, encoded_len);
+        assert_eq!(accessor.launch_meta_type(&data), LaunchMetaType::FCFS);
+        assert_eq!(accessor.page_name(&data), "instant");
+        assert_eq!(accessor.listing(&data), Some(launch.listing));
+        assert_eq!(accessor.creator(&data), Some(launch.keys[LaunchKeys::Seller as usize]));
+        assert_eq!(accessor.key(&data, LaunchKeys::Seller as usize), Some(launch.creator));
+        assert_eq!(accessor.distribution(&data, 2), Some(5));
+        assert_eq!(accessor.tokens_sold(&data), 99);
+        assert!(accessor.is_graduated(&data));
+
+        accessor.set_upvotes(&mut data, 3).unwrap();
+        accessor.set_tokens_sold(&mut data, 100);
+        let decoded = launch::LaunchData::load(&data).unwrap();
+        assert_eq!(decoded.upvotes, 3);
+        assert_eq!(decoded.tokens_sold, 100);
+    }
+
+    #[test]
+    fn test_dutch_auction_layout() {
+        let mut launch = launch::LaunchData::from(program_launch());
+        launch.launch_meta = launch::LaunchMeta::DutchAuction(launch::DutchAuction {
+            start_price: 1_000,
+            floor_price: 100,
+            half_life: 60,
+            clearing_price: 250,
+        });
+        let data = borsh::to_vec(&launch).unwrap();
+
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        assert_eq!(accessor.layout, LaunchLayout::Versioned);
+        assert_eq!(accessor.launch_meta_type(&data), LaunchMetaType::DutchAuction);
+        assert_eq!(accessor.plugins(&data), launch.plugins);
+        assert_eq!(accessor.page_name(&data), "instant");
+        assert_eq!(accessor.ticket_price(&data), 500);
+        assert_eq!(accessor.tokens_sold(&data), 99);
+    }
+
+    #[test]
+    fn test_migrates_legacy_layouts() {
+        let native = native_launch();
+        let data = borsh::to_vec(&native).unwrap();
+        assert!(launch::LaunchData::load(&data).is_err());
+
+        let migrated = launch::LaunchData::load_any_layout(&data).unwrap();
+        assert_eq!(migrated.version, launch::LAUNCH_DATA_VERSION);
+        assert_eq!(migrated.listing.to_string(), native.listing);
+        assert_eq!(
+            migrated.launch_meta,
+            launch::LaunchMeta::IDO(launch::IDO { token_fraction_distributed: 7.0, tokens_distributed: 9 })
+        );
+        assert_eq!(migrated.distribution, vec![10, 20]);
+        assert_eq!(migrated.keys[LaunchKeys::Seller as usize], native.creator);
+        assert_eq!(migrated.creator, native.creator);
+        assert_eq!((migrated.upvotes, migrated.downvotes), (11, 12));
+        assert_eq!(migrated.buffer3, 3);
+        assert_eq!(migrated.tokens_sold, 123_456);
+
+        let legacy = program_launch();
+        let migrated = launch::LaunchData::load_any_layout(&borsh::to_vec(&legacy).unwrap()).unwrap();
+        assert_eq!(migrated.creator, legacy.keys[LaunchKeys::Seller as usize]);
+        assert_eq!(migrated.plugins, legacy.plugins);
+        assert_eq!(migrated.keys, legacy.keys);
+        assert_eq!(migrated.upvotes, 0);
+
+        // migrating again reads the versioned account back as is
+        let data = borsh::to_vec(&migrated).unwrap();
+        assert_eq!(launch::LaunchData::load_any_layout(&data).unwrap(), migrated);
+
+        let mut native = native_launch();
+        native.distribution = vec![300];
+        assert!(launch::LaunchData::load_any_layout(&borsh::to_vec(&native).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_prices_outside_old_heuristic_range() {
+        // the old offset scan only accepted prices between 0.001 and 1 SOL
+        let mut launch = native_launch();
+        launch.ticket_price = 500;
+        let data = borsh::to_vec(&launch).unwrap();
+        let accessor = LaunchAccessor::load(&data).unwrap();
+        assert_eq!(accessor.ticket_price(&data), 500);
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_foreign_accounts() {
+        let data = borsh::to_vec(&native_launch()).unwrap();
+        assert!(LaunchAccessor::load(&data[..data.len() - 1]).is_err());
+
+        let mut other = data.clone();
+        other[0] = AccountType::Join as u8;
+        assert!(LaunchAccessor::load(&other).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+/**
+ * Liquidity locks.
+ *
+ * Every launch has one LiquidityLock at [launch, b"LiquidityLock"] recording how much of its Cook DEX
+ * pool's liquidity is locked, and for how long:
+ *  - liquidity seeded into the pool at graduation by GraduateLaunch or SettleIDO. Seeded reserves mint
+ *    no LP tokens, so the AMM keeps them in the pool forever.
+ *  - LP tokens the creator burned with LockLiquidity, whose share of the pool can never be withdrawn.
+ *  - LP tokens the creator deposited with LockLiquidity into the lock's vault, its associated token
+ *    account for the pool's LP mint. They unlock linearly from the lock date until end_date, with
+ *    nothing unlocking before the cliff, and the beneficiary claims them back with UnlockLiquidity.
+ *    Each deposit starts its own schedule, so the tokens of the previous one have to be claimed first.
+ */
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey, sysvar::Sysvar,
+};
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+
+use crate::{
+    accounts, amm, common, events,
+    instruction::{
+        accounts::{LockLiquidityAccounts, UnlockLiquidityAccounts},
+        LockLiquidityArgs,
+    },
+    launch::{load_listing, LaunchData},
+    state::AccountType,
+    utils,
+};
+
+pub const LIQUIDITY_LOCK_SEED: &[u8] = b"LiquidityLock";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct LiquidityLock {
+    pub account_type: AccountType,
+    pub launch: Pubkey,
+    pub amm: Pubkey,
+    pub beneficiary: Pubkey,
+    pub graduation_liquidity: u64, // pool liquidity seeded at graduation, locked forever
+    pub burned: u64,               // LP tokens burned forever
+    pub locked: u64,               // LP tokens deposited into the vault under the current schedule
+    pub unlocked: u64,             // LP tokens of `locked` claimed back so far
+    pub start_date: i64,
+    pub cliff_date: i64,
+    pub end_date: i64,
+}
+
+impl LiquidityLock {
+    pub const LEN: usize = 1 + 32 * 3 + 8 * 7;
+
+    /// LP tokens of `locked` that have unlocked by `now`
+    pub fn get_vested(&self, now: i64) -> u64 {
+        if now < self.cliff_date {
+            return 0;
+        }
+        if now >= self.end_date || self.end_date <= self.start_date {
+            return self.locked;
+        }
+        (self.locked as u128 * (now - self.start_date) as u128 / (self.end_date - self.start_date) as u128) as u64
+    }
+
+    /// LP tokens the beneficiary can claim with UnlockLiquidity at `now`
+    pub fn get_claimable(&self, now: i64) -> u64 {
+        self.get_vested(now).saturating_sub(self.unlocked)
+    }
+
+    /// Starts the schedule of a new deposit. A top-up would vest with the time already elapsed
+    /// on the old schedule, so deposits are refused until its tokens have all been claimed
+    pub fn start_schedule(&mut self, amount: u64, now: i64, cliff_date: i64, end_date: i64) -> ProgramResult {
+        if self.locked > self.unlocked {
+            msg!("{} LP tokens are still locked until {}, claim them before locking more", self.locked - self.unlocked, self.end_date);
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.locked = amount;
+        self.unlocked = 0;
+        self.start_date = now;
+        self.cliff_date = cliff_date;
+        self.end_date = end_date;
+        Ok(())
+    }
+}
+
+pub fn get_liquidity_lock_address(launch: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&launch.to_bytes(), LIQUIDITY_LOCK_SEED], program_id)
+}
+
+fn get_lp_mint_address(amm: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&amm.to_bytes(), b"LP"], program_id).0
+}
+
+/// Reads the launch's lock, creating it for `amm` with the payer funding the rent if it does not exist yet
+fn load_or_create_liquidity_lock<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    launch: &Pubkey,
+    liquidity_lock: &AccountInfo<'a>,
+    amm: &Pubkey,
+    beneficiary: &Pubkey,
+) -> Result<LiquidityLock, ProgramError> {
+    let (expected_address, bump) = get_liquidity_lock_address(launch, program_id);
+    if liquidity_lock.key != &expected_address {
+        msg!("expected liquidity lock {} {}", expected_address, liquidity_lock.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if **liquidity_lock.try_borrow_lamports()? > 0 {
+        if liquidity_lock.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let lock = LiquidityLock::try_from_slice(&liquidity_lock.try_borrow_data()?)?;
+        if lock.account_type != AccountType::LiquidityLock || lock.amm != *amm {
+            msg!("Liquidity lock belongs to pool {}", lock.amm);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        return Ok(lock);
+    }
+
+    utils::create_program_account(
+        payer,
+        liquidity_lock,
+        program_id,
+        bump,
+        LiquidityLock::LEN,
+        vec![&launch.to_bytes(), LIQUIDITY_LOCK_SEED],
+    )?;
+    Ok(LiquidityLock {
+        account_type: AccountType::LiquidityLock,
+        launch: *launch,
+        amm: *amm,
+        beneficiary: *beneficiary,
+        graduation_liquidity: 0,
+        burned: 0,
+        locked: 0,
+        unlocked: 0,
+        start_date: 0,
+        cliff_date: 0,
+        end_date: 0,
+    })
+}
+
+/// Records the liquidity a graduation seeded into the launch's Cook DEX pool in its lock
+#[allow(clippy::too_many_arguments)]
+pub fn record_graduation_liquidity<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    launch_data: &AccountInfo<'a>,
+    liquidity_lock: &AccountInfo<'a>,
+    amm: &Pubkey,
+    base_mint: &Pubkey,
+    creator: &Pubkey,
+    liquidity: u64,
+) -> ProgramResult {
+    let mut lock = load_or_create_liquidity_lock(program_id, payer, launch_data.key, liquidity_lock, amm, creator)?;
+    lock.graduation_liquidity = lock.graduation_liquidity.saturating_add(liquidity);
+    lock.serialize(&mut &mut liquidity_lock.data.borrow_mut()[..])?;
+
+    events::emit_liquidity_locked_event(base_mint, liquidity_lock.key, &get_lp_mint_address(amm, program_id), liquidity, u64::MAX, i64::MAX, creator);
+    Ok(())
+}
+
+/// LockLiquidity: the launch creator burns LP tokens of the launch's Cook DEX pool, or deposits them into the lock's vault
+pub fn lock_liquidity<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: LockLiquidityArgs) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<LockLiquidityAccounts> = LockLiquidityAccounts::context(accounts)?;
+
+    if !ctx.accounts.creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+    accounts::check_associated_token_program_key(ctx.accounts.associated_token)?;
+    let is_2022 = accounts::check_token_program_key(ctx.accounts.lp_token_program)?;
+
+    if ctx.accounts.launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    common::check_not_paused(program_id, Some(ctx.accounts.cook_data))?;
+    common::check_launch_not_paused(ctx.accounts.launch_data)?;
+
+    let launch_data = LaunchData::load(&ctx.accounts.launch_data.try_borrow_data()?)?;
+    if launch_data.creator != *ctx.accounts.creator.key {
+        msg!("Only the launch creator {} can lock its liquidity", launch_data.creator);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let listing = load_listing(program_id, &launch_data, ctx.accounts.listing, ctx.accounts.base_token_mint)?;
+    let (_, expected_amm, _) = amm::get_cook_amm(&listing.mint, program_id);
+    if ctx.accounts.amm.key != &expected_amm || ctx.accounts.amm.owner != program_id {
+        msg!("expected Cook AMM {}", expected_amm);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if ctx.accounts.lp_token_mint.key != &get_lp_mint_address(&expected_amm, program_id)
+        || ctx.accounts.lp_token_mint.owner != ctx.accounts.lp_token_program.key
+    {
+        msg!("LP mint does not belong to the Cook AMM");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if args.amount == 0 {
+        msg!("Nothing to lock");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut lock = load_or_create_liquidity_lock(
+        program_id,
+        ctx.accounts.creator,
+        ctx.accounts.launch_data.key,
+        ctx.accounts.liquidity_lock,
+        &expected_amm,
+        ctx.accounts.creator.key,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if args.burn == 1 {
+        utils::burn(args.amount, ctx.accounts.lp_token_program, ctx.accounts.lp_token_mint, ctx.accounts.creator_lp, ctx.accounts.creator, 0, &vec![])?;
+        lock.burned = lock.burned.saturating_add(args.amount);
+        lock.serialize(&mut &mut ctx.accounts.liquidity_lock.data.borrow_mut()[..])?;
+
+        events::emit_liquidity_locked_event(&listing.mint, ctx.accounts.liquidity_lock.key, ctx.accounts.lp_token_mint.key, args.amount, u64::MAX, i64::MAX, &lock.beneficiary);
+        msg!("✅ Burned {} LP tokens of {}, locking their liquidity forever", args.amount, launch_data.page_name);
+        return Ok(());
+    }
+
+    if args.duration == 0 || args.duration < args.cliff {
+        msg!("Lock duration {} has to be at least its cliff {} and more than zero", args.duration, args.cliff);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let cliff_date = i64::try_from(args.cliff).ok().and_then(|cliff| now.checked_add(cliff)).ok_or(ProgramError::ArithmeticOverflow)?;
+    let end_date = i64::try_from(args.duration).ok().and_then(|duration| now.checked_add(duration)).ok_or(ProgramError::ArithmeticOverflow)?;
+    lock.start_schedule(args.amount, now, cliff_date, end_date)?;
+
+    utils::check_and_create_ata(
+        ctx.accounts.creator,
+        ctx.accounts.liquidity_lock,
+        ctx.accounts.lp_token_mint,
+        ctx.accounts.lock_vault,
+        ctx.accounts.lp_token_program,
+        ctx.accounts.system_program,
+        ctx.accounts.associated_token,
+    )?;
+
+    let lp_decimals = StateWithExtensions::<Mint>::unpack(&ctx.accounts.lp_token_mint.try_borrow_data()?)?.base.decimals;
+    utils::transfer_tokens(
+        is_2022,
+        args.amount,
+        ctx.accounts.creator_lp,
+        ctx.accounts.lp_token_mint,
+        ctx.accounts.lock_vault,
+        ctx.accounts.creator,
+        ctx.accounts.lp_token_program,
+        0,
+        &vec![],
+        lp_decimals,
+        &Vec::new(),
+    )?;
+
+    lock.serialize(&mut &mut ctx.accounts.liquidity_lock.data.borrow_mut()[..])?;
+
+    events::emit_liquidity_locked_event(
+        &listing.mint,
+        ctx.accounts.liquidity_lock.key,
+        ctx.accounts.lp_token_mint.key,
+        args.amount,
+        args.duration,
+        end_date,
+        &lock.beneficiary,
+    );
+    msg!("✅ Locked {} LP tokens of {} until {}, cliff at {}", args.amount, launch_data.page_name, end_date, cliff_date);
+    Ok(())
+}
+
+/// UnlockLiquidity: the beneficiary claims the LP tokens that have unlocked so far
+pub fn unlock_liquidity<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<UnlockLiquidityAccounts> = UnlockLiquidityAccounts::context(accounts)?;
+
+    if !ctx.accounts.beneficiary.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let is_2022 = accounts::check_token_program_key(ctx.accounts.lp_token_program)?;
+
+    if ctx.accounts.liquidity_lock.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut lock = LiquidityLock::try_from_slice(&ctx.accounts.liquidity_lock.try_borrow_data()?)?;
+    if lock.account_type != AccountType::LiquidityLock {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let (expected_address, bump) = get_liquidity_lock_address(&lock.launch, program_id);
+    if ctx.accounts.liquidity_lock.key != &expected_address {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if lock.beneficiary != *ctx.accounts.beneficiary.key {
+        msg!("Only the lock's beneficiary {} can unlock its LP tokens", lock.beneficiary);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if ctx.accounts.launch_data.key != &lock.launch || ctx.accounts.launch_data.owner != program_id {
+        msg!("Launch account is not the locked launch {}", lock.launch);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    common::check_not_paused(program_id, Some(ctx.accounts.cook_data))?;
+    common::check_launch_not_paused(ctx.accounts.launch_data)?;
+    if ctx.accounts.lp_token_mint.key != &get_lp_mint_address(&lock.amm, program_id) {
+        msg!("LP mint does not belong to the locked pool");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    accounts::check_token_account(ctx.accounts.liquidity_lock, ctx.accounts.lp_token_mint, ctx.accounts.lock_vault, ctx.accounts.lp_token_program)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let claimable = lock.get_claimable(now);
+    if claimable == 0 {
+        msg!("No LP tokens have unlocked yet, cliff at {} and fully unlocked at {}", lock.cliff_date, lock.end_date);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let lp_decimals = StateWithExtensions::<Mint>::unpack(&ctx.accounts.lp_token_mint.try_borrow_data()?)?.base.decimals;
+    utils::transfer_tokens(
+        is_2022,
+        claimable,
+        ctx.accounts.lock_vault,
+        ctx.accounts.lp_token_mint,
+        ctx.accounts.beneficiary_lp,
+        ctx.accounts.liquidity_lock,
+        ctx.accounts.lp_token_program,
+        bump,
+        &vec![&lock.launch.to_bytes(), LIQUIDITY_LOCK_SEED],
+        lp_decimals,
+        &Vec::new(),
+    )?;
+
+    lock.unlocked += claimable;
+    lock.serialize(&mut &mut ctx.accounts.liquidity_lock.data.borrow_mut()[..])?;
+
+    events::emit_liquidity_unlocked_event(
+        ctx.accounts.liquidity_lock.key,
+        ctx.accounts.lp_token_mint.key,
+        claimable,
+        lock.locked - lock.unlocked,
+        &lock.beneficiary,
+    );
+    msg!("✅ Unlocked {} LP tokens, {} still locked", claimable, lock.locked - lock.unlocked);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liquidity_lock_vesting() {
+        let mut lock = LiquidityLock {
+            account_type: AccountType::LiquidityLock,
+            launch: Pubkey::new_unique(),
+            amm: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            graduation_liquidity: 0,
+            burned: 0,
+            locked: 1_000_000,
+            unlocked: 0,
+            start_date: 1_000,
+            cliff_date: 1_250,
+            end_date: 2_000,
+        };
+        assert_eq!(borsh::to_vec(&lock).unwrap().len(), LiquidityLock::LEN);
+
+        // nothing before the cliff, then linear from the lock date
+        assert_eq!(lock.get_claimable(1_249), 0);
+        assert_eq!(lock.get_claimable(1_250), 250_000);
+        assert_eq!(lock.get_claimable(1_500), 500_000);
+
+        lock.unlocked = 500_000;
+        assert_eq!(lock.get_claimable(1_500), 0);
+        assert_eq!(lock.get_claimable(1_750), 250_000);
+        assert_eq!(lock.get_claimable(5_000), 500_000);
+
+        // a cliff-only lock unlocks everything at once
+        lock.unlocked = 0;
+        lock.cliff_date = 2_000;
+        assert_eq!(lock.get_claimable(1_999), 0);
+        assert_eq!(lock.get_claimable(2_000), 1_000_000);
+    }
+
+    #[test]
+    fn test_liquidity_lock_top_up() {
+        let mut lock = LiquidityLock {
+            account_type: AccountType::LiquidityLock,
+            launch: Pubkey::new_unique(),
+            amm: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            graduation_liquidity: 0,
+            burned: 0,
+            locked: 0,
+            unlocked: 0,
+            start_date: 0,
+            cliff_date: 0,
+            end_date: 0,
+        };
+        lock.start_schedule(1_000_000, 1_000, 1_000, 2_000).unwrap();
+
+        // halfway through, a top-up would vest half of the new tokens straight away
+        assert_eq!(lock.get_claimable(1_500), 500_000);
+        assert!(lock.start_schedule(1_000_000, 1_500, 1_500, 3_000).is_err());
+        assert_eq!(lock.locked, 1_000_000);
+        assert_eq!(lock.end_date, 2_000);
+
+        // once everything is claimed the next deposit vests from its own lock date
+        lock.unlocked = lock.get_vested(2_000);
+        lock.start_schedule(1_000_000, 2_500, 2_500, 3_500).unwrap();
+        assert_eq!(lock.unlocked, 0);
+        assert_eq!(lock.get_claimable(2_500), 0);
+        assert_eq!(lock.get_claimable(3_000), 500_000);
+        assert_eq!(lock.get_claimable(3_500), 1_000_000);
+    }
+}
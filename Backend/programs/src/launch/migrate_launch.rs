@@ -0,0 +1,73 @@
+use borsh::to_vec;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+    system_instruction, sysvar::Sysvar,
+};
+
+use crate::{
+    accounts,
+    instruction::accounts::MigrateLaunchAccounts,
+    launch::{LaunchAccessor, LaunchData, LaunchLayout},
+};
+
+/// Rewrites a launch account from either legacy layout into the current versioned LaunchData.
+/// Anyone can migrate a launch, the payer covers any extra rent if the account has to grow.
+/// Native launches were created at keypair addresses, so the account is trusted by owner rather than by PDA.
+pub fn migrate_launch<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<MigrateLaunchAccounts> = MigrateLaunchAccounts::context(accounts)?;
+
+    if !ctx.accounts.payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(ctx.accounts.system_program)?;
+
+    if ctx.accounts.launch_data.owner != program_id {
+        msg!("Launch account is not owned by this program");
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let launch_data = {
+        let data = ctx.accounts.launch_data.try_borrow_data()?;
+        if LaunchAccessor::load(&data)?.layout == LaunchLayout::Versioned {
+            msg!("Launch account is already up to date");
+            return Ok(());
+        }
+        LaunchData::load_any_layout(&data)?
+    };
+
+    write_launch_data(ctx.accounts.payer, ctx.accounts.launch_data, ctx.accounts.system_program, &launch_data)?;
+
+    msg!("✅ Migrated launch {} to layout version {}", launch_data.page_name, launch_data.version);
+    Ok(())
+}
+
+/// Writes `launch_data` over a launch account, growing the account with the payer covering any extra rent
+pub fn write_launch_data<'a>(
+    payer: &AccountInfo<'a>,
+    launch_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    launch_data: &LaunchData,
+) -> ProgramResult {
+    let encoded = to_vec(&launch_data)?;
+    let account_len = launch_account.data_len();
+    if encoded.len() > account_len {
+        // launches hold ticket payments, so the extra rent is always paid in rather than taken from the balance
+        let rent = Rent::get()?;
+        let extra_rent = rent.minimum_balance(encoded.len()) - rent.minimum_balance(account_len);
+        invoke(
+            &system_instruction::transfer(payer.key, launch_account.key, extra_rent),
+            &[payer.clone(), launch_account.clone(), system_program.clone()],
+        )?;
+
+        msg!("Resizing launch account from {} to {} bytes", account_len, encoded.len());
+        launch_account.realloc(encoded.len(), true)?;
+    }
+
+    // clear whatever the previous encoding left past the new one
+    let mut data = launch_account.try_borrow_mut_data()?;
+    data[..encoded.len()].copy_from_slice(&encoded);
+    data[encoded.len()..].fill(0);
+
+    Ok(())
+}
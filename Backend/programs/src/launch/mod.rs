@@ -3,11 +3,20 @@ pub mod claim_refund;
 pub mod claim_tokens;
 pub mod create_launch;
 pub mod create_pool_on_graduation;
+pub mod creator_fees;
+pub mod dutch_auction;
 pub mod edit_launch;
+pub mod fail_launch;
+pub mod graduate_launch;
+pub mod ido;
 pub mod instant_launch;
 pub mod create_amm_quote;
 pub mod join_launch;
+pub mod layout;
+pub mod liquidity_lock;
+pub mod migrate_launch;
 pub mod plugins;
+pub mod settle_raffle;
 pub mod state;
 
 pub use check_tickets::*;
@@ -15,9 +24,18 @@ pub use claim_refund::*;
 pub use claim_tokens::*;
 pub use create_launch::*;
 pub use create_pool_on_graduation::*;
+pub use creator_fees::*;
+pub use dutch_auction::*;
 pub use edit_launch::*;
+pub use fail_launch::*;
+pub use graduate_launch::*;
+pub use ido::*;
 pub use instant_launch::*;
 pub use create_amm_quote::*;
 pub use join_launch::*;
+pub use layout::*;
+pub use liquidity_lock::*;
+pub use migrate_launch::*;
 pub use plugins::*;
+pub use settle_raffle::*;
 pub use state::*;
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use sha2::{Digest, Sha256};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::instruction::AllowlistProof;
+use crate::utils;
 
 #[derive(Copy, Clone, BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq)]
 pub struct WhiteListToken {
@@ -10,11 +14,41 @@ pub struct WhiteListToken {
     pub phase_end: u64,
 }
 
+impl WhiteListToken {
+    /// Until the phase ends only holders of the whitelist mint may join
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.phase_end
+    }
+}
+
+/// Wallet allowlist committed to as a merkle root, leaves are built by `allowlist_leaf`
+#[derive(Copy, Clone, BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq)]
+pub struct MerkleAllowlist {
+    pub root: [u8; 32],
+    pub phase_end: u64,
+    pub default_cap: u64, // lamports a wallet may spend when its leaf has no cap, 0 = uncapped
+}
+
+impl MerkleAllowlist {
+    /// Until the phase ends only wallets in the allowlist may join
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.phase_end
+    }
+}
+
+/// Share of every SwapCookAMM trade the launch creator earns, paid into the launch's CreatorFees vault
+#[derive(Copy, Clone, BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq)]
+pub struct CreatorFee {
+    pub bps: u16, // capped by the fee schedule's max_creator_fee_bps
+}
+
 #[repr(C)]
 #[derive(Hash, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
 pub enum LaunchPluginType {
     /// Standard raffle launch
     WhiteListToken,
+    MerkleAllowlist,
+    CreatorFee,
 }
 
 /// Definition of the collection variants
@@ -23,12 +57,16 @@ pub enum LaunchPluginType {
 pub enum LaunchPlugin {
     /// User gets a random nft from a fixed supply
     WhiteListToken(WhiteListToken),
+    MerkleAllowlist(MerkleAllowlist),
+    CreatorFee(CreatorFee),
 }
 
 impl From<&LaunchPlugin> for LaunchPluginType {
     fn from(collection_meta: &LaunchPlugin) -> Self {
         match collection_meta {
             LaunchPlugin::WhiteListToken(_) => LaunchPluginType::WhiteListToken,
+            LaunchPlugin::MerkleAllowlist(_) => LaunchPluginType::MerkleAllowlist,
+            LaunchPlugin::CreatorFee(_) => LaunchPluginType::CreatorFee,
         }
     }
 }
@@ -59,6 +97,16 @@ pub struct IDO {
     pub tokens_distributed: u64,
 }
 
+// ticket price falls from start_price towards floor_price until the tickets sell out or the launch ends,
+// every bid then pays the clearing price and gets the difference back when claiming
+#[derive(Copy, Clone, BorshSerialize, BorshDeserialize, Debug, Eq, PartialEq)]
+pub struct DutchAuction {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub half_life: u64,      // seconds, 0 decays linearly to the floor at end_date
+    pub clearing_price: u64, // price of the latest bid, final once the auction has cleared
+}
+
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
 pub enum LaunchMetaType {
@@ -66,6 +114,7 @@ pub enum LaunchMetaType {
     Raffle,
     FCFS,
     IDO,
+    DutchAuction,
 }
 
 /// Definition of the collection variants
@@ -76,6 +125,7 @@ pub enum LaunchMeta {
     Raffle(Raffle),
     FCFS(FCFS),
     IDO(IDO),
+    DutchAuction(DutchAuction),
 }
 
 impl From<&LaunchMeta> for LaunchMetaType {
@@ -84,6 +134,219 @@ impl From<&LaunchMeta> for LaunchMetaType {
             LaunchMeta::Raffle(_) => LaunchMetaType::Raffle,
             LaunchMeta::FCFS(_) => LaunchMetaType::FCFS,
             LaunchMeta::IDO(_) => LaunchMetaType::IDO,
+            LaunchMeta::DutchAuction(_) => LaunchMetaType::DutchAuction,
         }
     }
 }
+
+pub fn get_whitelist(plugins: &[LaunchPlugin]) -> Option<WhiteListToken> {
+    match get_launch_plugin_map(plugins.to_vec()).get(&LaunchPluginType::WhiteListToken) {
+        Some(LaunchPlugin::WhiteListToken(whitelist)) => Some(*whitelist),
+        _ => None,
+    }
+}
+
+pub fn get_merkle_allowlist(plugins: &[LaunchPlugin]) -> Option<MerkleAllowlist> {
+    match get_launch_plugin_map(plugins.to_vec()).get(&LaunchPluginType::MerkleAllowlist) {
+        Some(LaunchPlugin::MerkleAllowlist(allowlist)) => Some(*allowlist),
+        _ => None,
+    }
+}
+
+pub fn get_creator_fee(plugins: &[LaunchPlugin]) -> Option<CreatorFee> {
+    match get_launch_plugin_map(plugins.to_vec()).get(&LaunchPluginType::CreatorFee) {
+        Some(LaunchPlugin::CreatorFee(creator_fee)) => Some(*creator_fee),
+        _ => None,
+    }
+}
+
+/// Builds the whitelist plugin of a new launch, `whitelist_mint` is only required when `whitelist_tokens` > 0
+pub fn get_whitelist_plugin(
+    whitelist_mint: Option<&AccountInfo>,
+    whitelist_tokens: u64,
+    whitelist_end: u64,
+) -> Result<Option<LaunchPlugin>, ProgramError> {
+    if whitelist_tokens == 0 {
+        return Ok(None);
+    }
+
+    let whitelist_mint = match whitelist_mint {
+        Some(mint) => mint,
+        None => {
+            msg!("❌ Error: Whitelist launches need the whitelist mint");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    };
+    if whitelist_mint.owner != &spl_token::id() && whitelist_mint.owner != &spl_token_2022::id() {
+        msg!("❌ Error: Whitelist mint {} is not a token mint", whitelist_mint.key);
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    msg!("🔒 Whitelist phase until {}: {} {} tokens per ticket", whitelist_end, whitelist_tokens, whitelist_mint.key);
+    Ok(Some(LaunchPlugin::WhiteListToken(WhiteListToken {
+        key: *whitelist_mint.key,
+        quantity: whitelist_tokens,
+        phase_end: whitelist_end,
+    })))
+}
+
+// leaves and nodes are domain separated so a node can never be passed off as a leaf
+const ALLOWLIST_LEAF: u8 = 0;
+const ALLOWLIST_NODE: u8 = 1;
+
+/// sha256(0 || wallet || cap), cap 0 gives the wallet the launch's default cap
+pub fn allowlist_leaf(wallet: &Pubkey, cap: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([ALLOWLIST_LEAF]);
+    hasher.update(wallet.to_bytes());
+    hasher.update(cap.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// sha256(1 || min(a, b) || max(a, b)), sorting the pair means proofs need no left/right flags
+pub fn allowlist_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update([ALLOWLIST_NODE]);
+    hasher.update(first);
+    hasher.update(second);
+    hasher.finalize().into()
+}
+
+pub fn verify_allowlist_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| allowlist_node(&node, sibling)) == *root
+}
+
+/// Checks the wallet's allowlist proof and cap for a purchase of `spend` lamports while the allowlist
+/// phase is running, returning the wallet's allowlist spend including this purchase.
+/// Once the phase has ended anyone may buy and the spend is returned unchanged.
+pub fn check_allowlist(
+    allowlist: &MerkleAllowlist,
+    now: u64,
+    wallet: &Pubkey,
+    proof: Option<&AllowlistProof>,
+    spent: u64,
+    spend: u64,
+) -> Result<u64, ProgramError> {
+    if !allowlist.is_active(now) {
+        return Ok(spent);
+    }
+
+    let proof = match proof {
+        Some(proof) => proof,
+        None => {
+            msg!("Launch is in its allowlist phase until {}, an allowlist proof is required", allowlist.phase_end);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+    };
+
+    if !verify_allowlist_proof(&allowlist.root, allowlist_leaf(wallet, proof.cap), &proof.proof) {
+        msg!("Wallet {} is not on the allowlist with cap {}", wallet, proof.cap);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let cap = match proof.cap {
+        0 => allowlist.default_cap,
+        cap => cap,
+    };
+    let spent = spent.checked_add(spend).ok_or(ProgramError::ArithmeticOverflow)?;
+    if cap > 0 && spent > cap {
+        msg!("Allowlist cap of {} lamports exceeded, {} spent", cap, spent);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    Ok(spent)
+}
+
+/// Burns `quantity` whitelist tokens per ticket from the user while the whitelist phase is running.
+/// Does nothing once the phase has ended, the accounts are only read while it is active.
+pub fn burn_whitelist_tokens<'a>(
+    whitelist: &WhiteListToken,
+    now: u64,
+    num_tickets: u64,
+    user: &AccountInfo<'a>,
+    whitelist_mint: Option<&AccountInfo<'a>>,
+    whitelist_token_program: Option<&AccountInfo<'a>>,
+    whitelist_account: Option<&AccountInfo<'a>>,
+) -> ProgramResult {
+    if !whitelist.is_active(now) {
+        return Ok(());
+    }
+
+    let (whitelist_mint, whitelist_token_program, whitelist_account) = match (whitelist_mint, whitelist_token_program, whitelist_account) {
+        (Some(mint), Some(token_program), Some(token_account)) => (mint, token_program, token_account),
+        _ => {
+            msg!("Launch is in its whitelist phase until {}, whitelist token accounts are required", whitelist.phase_end);
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+    };
+
+    if *whitelist_mint.key != whitelist.key {
+        msg!("Incorrect whitelist mint");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if whitelist_mint.owner != whitelist_token_program.key
+        || (*whitelist_token_program.key != spl_token::id() && *whitelist_token_program.key != spl_token_2022::id())
+    {
+        msg!("Whitelist mint is not owned by a token program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let amount = whitelist.quantity.checked_mul(num_tickets).ok_or(ProgramError::ArithmeticOverflow)?;
+    msg!("Burning {} whitelist tokens for {} tickets", amount, num_tickets);
+
+    // the token program rejects accounts of another mint or that the user cannot burn from
+    utils::burn(amount, whitelist_token_program, whitelist_mint, whitelist_account, user, 0, &Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowlist(root: [u8; 32]) -> MerkleAllowlist {
+        MerkleAllowlist {
+            root,
+            phase_end: 100,
+            default_cap: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_allowlist_proofs() {
+        let wallets = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let leaves = [allowlist_leaf(&wallets[0], 0), allowlist_leaf(&wallets[1], 5_000), allowlist_leaf(&wallets[2], 0)];
+        // the odd leaf is carried up to the next level unhashed
+        let left = allowlist_node(&leaves[0], &leaves[1]);
+        let root = allowlist_node(&left, &leaves[2]);
+
+        assert!(verify_allowlist_proof(&root, leaves[0], &[leaves[1], leaves[2]]));
+        assert!(verify_allowlist_proof(&root, leaves[1], &[leaves[0], leaves[2]]));
+        assert!(verify_allowlist_proof(&root, leaves[2], &[left]));
+        assert!(!verify_allowlist_proof(&root, allowlist_leaf(&wallets[1], 0), &[leaves[0], leaves[2]]));
+        assert!(!verify_allowlist_proof(&root, left, &[leaves[2], leaves[2]]));
+        // an inner node cannot be passed off as a leaf
+        assert_ne!(allowlist_leaf(&wallets[0], 0), allowlist_node(&leaves[0], &leaves[0]));
+    }
+
+    #[test]
+    fn test_allowlist_caps() {
+        let wallet = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let leaves = [allowlist_leaf(&wallet, 0), allowlist_leaf(&other, 5_000)];
+        let allowlist = allowlist(allowlist_node(&leaves[0], &leaves[1]));
+        let default_proof = AllowlistProof { cap: 0, proof: vec![leaves[1]] };
+        let capped_proof = AllowlistProof { cap: 5_000, proof: vec![leaves[0]] };
+
+        assert_eq!(check_allowlist(&allowlist, 10, &wallet, Some(&default_proof), 200, 800), Ok(1_000));
+        assert!(check_allowlist(&allowlist, 10, &wallet, Some(&default_proof), 200, 801).is_err());
+        assert_eq!(check_allowlist(&allowlist, 10, &other, Some(&capped_proof), 0, 5_000), Ok(5_000));
+        assert!(check_allowlist(&allowlist, 10, &other, Some(&default_proof), 0, 1).is_err());
+        assert!(check_allowlist(&allowlist, 10, &wallet, None, 0, 1).is_err());
+
+        // anyone may buy once the phase is over
+        assert_eq!(check_allowlist(&allowlist, 100, &Pubkey::new_unique(), None, 7, 10_000), Ok(7));
+
+        let uncapped = MerkleAllowlist { default_cap: 0, ..allowlist };
+        assert_eq!(check_allowlist(&uncapped, 10, &wallet, Some(&default_proof), 0, u64::MAX), Ok(u64::MAX));
+    }
+}
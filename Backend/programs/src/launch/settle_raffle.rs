@@ -0,0 +1,433 @@
+/**
+ * Permissionless settlement of a raffle once it has ended.
+ *
+ * Every ticket gets a raffle index when it is bought (JoinData.first_ticket onwards), and one draw over
+ * 0..tickets_sold, seeded from one randomness result for the whole launch, decides which indices win.
+ * A ticket's outcome therefore does not depend on when, or in which batch, its join is settled, and
+ * anyone can reproduce the draw off-chain with `draw_tickets`.
+ *
+ * The draw lives in a RaffleDraw account at [launch, b"RaffleDraw"], followed by a bitmap with a bit
+ * for every ticket sold. SettleRaffle is cranked until the raffle is settled:
+ *  - the first call after end_date creates the account and, on Orao networks, places the Orao request
+ *    for `get_raffle_request_seed(launch, slot)`. The seed has the slot of that call in it, so nobody
+ *    can have placed the request, and seen its randomness, while tickets were still on sale.
+ *  - once the request is fulfilled (or the commit-reveal secret revealed) each call draws up to
+ *    DRAW_BATCH more tickets, keeping every call within the compute budget however big the raffle.
+ *  - once the draw is complete each call settles the JoinData accounts passed to it.
+ *
+ * Whoever settles a batch of joins is paid the RAFFLE_CRANK_FEE each ticket paid at purchase.
+ */
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, hash::hashv, msg, program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    instruction::accounts::SettleRaffleAccounts,
+    launch::{JoinData, LaunchAccessor, LaunchMetaType},
+    orao, randomness,
+    state::{self, AccountType},
+    utils,
+};
+
+pub const RAFFLE_DRAW_SEED: &[u8] = b"RaffleDraw";
+
+/// Most tickets a SettleRaffle call draws
+pub const DRAW_BATCH: u32 = 1_000;
+
+/// Seed of the Orao request a raffle's draw is taken from, placed by SettleRaffle in `slot`
+pub fn get_raffle_request_seed(launch: &Pubkey, slot: u64) -> [u8; 32] {
+    hashv(&[launch.as_ref(), b"Raffle", &slot.to_le_bytes()]).to_bytes()
+}
+
+pub fn get_raffle_draw_address(launch: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&launch.to_bytes(), RAFFLE_DRAW_SEED], program_id)
+}
+
+/// Progress of a raffle's draw. Whichever of the winners or losers is the smaller set is drawn,
+/// the other is everything that was not.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RaffleDraw {
+    pub account_type: AccountType,
+    pub launch: Pubkey,
+    pub request_slot: u64,    // slot the randomness was requested in, after the raffle closed
+    pub orao_request: Pubkey, // default on networks without Orao
+    pub rng: u64,             // state of the draw, 0 until the randomness has been read
+    pub num_tickets: u32,
+    pub num_to_draw: u32,
+    pub num_drawn: u32,
+    pub drawn_win: bool,
+}
+
+impl RaffleDraw {
+    pub const LEN: usize = 1 + 32 + 8 + 32 + 8 + 4 * 3 + 1;
+
+    pub fn new(launch: Pubkey, request_slot: u64, orao_request: Pubkey, tickets_sold: u32, num_mints: u32) -> Self {
+        let num_losers = tickets_sold.saturating_sub(num_mints);
+        RaffleDraw {
+            account_type: AccountType::RaffleDraw,
+            launch,
+            request_slot,
+            orao_request,
+            rng: 0,
+            num_tickets: tickets_sold,
+            num_to_draw: num_mints.min(num_losers),
+            num_drawn: 0,
+            drawn_win: num_mints <= num_losers,
+        }
+    }
+
+    /// Size of the account holding the draw for `num_tickets` tickets
+    pub fn account_size(num_tickets: u32) -> usize {
+        Self::LEN + (num_tickets as usize).div_ceil(8)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.num_drawn >= self.num_to_draw
+    }
+
+    /// Draws up to `max_draws` more tickets into the bitmap `drawn`
+    pub fn draw(&mut self, drawn: &mut [u8], max_draws: u32) {
+        let target = self.num_to_draw.min(self.num_drawn.saturating_add(max_draws));
+        self.num_drawn = draw_tickets(&mut self.rng, drawn, self.num_tickets, self.num_drawn, target);
+    }
+
+    /// Number of winners among the tickets first_ticket..first_ticket + num_tickets
+    pub fn winning_tickets(&self, drawn: &[u8], first_ticket: u32, num_tickets: u32) -> u32 {
+        let drawn = count_drawn(drawn, first_ticket, num_tickets);
+        if self.drawn_win {
+            drawn
+        } else {
+            num_tickets - drawn
+        }
+    }
+}
+
+/// Draws tickets from 0..num_tickets into the bitmap `drawn` until `target` have been drawn, drawing again
+/// whenever a ticket comes up twice. Returns how many have been drawn.
+pub fn draw_tickets(rng: &mut u64, drawn: &mut [u8], num_tickets: u32, mut num_drawn: u32, target: u32) -> u32 {
+    while num_drawn < target.min(num_tickets) {
+        *rng = utils::shift_seed(*rng);
+        let ticket = (*rng % num_tickets as u64) as usize;
+        let mask = 1u8 << (ticket % 8);
+        if drawn[ticket / 8] & mask == 0 {
+            drawn[ticket / 8] |= mask;
+            num_drawn += 1;
+        }
+    }
+    num_drawn
+}
+
+/// Number of drawn tickets among first_ticket..first_ticket + num_tickets
+pub fn count_drawn(drawn: &[u8], first_ticket: u32, num_tickets: u32) -> u32 {
+    (first_ticket..first_ticket + num_tickets)
+        .filter(|ticket| drawn[*ticket as usize / 8] & (1u8 << (ticket % 8)) != 0)
+        .count() as u32
+}
+
+/// SettleRaffle: requests the raffle's randomness, draws it DRAW_BATCH tickets at a time, and once it is drawn settles
+/// every JoinData passed after the fixed accounts, paying the cranker the crank fee of each settled ticket.
+/// Joins that are already settled, or hold tickets from before indexing, are skipped.
+pub fn settle_raffle<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+    let ctx: crate::instruction::accounts::Context<SettleRaffleAccounts> = SettleRaffleAccounts::context(accounts)?;
+
+    if !ctx.accounts.cranker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if ctx.accounts.launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let launch_data_bytes = ctx.accounts.launch_data.try_borrow_data()?;
+    let launch_layout = LaunchAccessor::load(&launch_data_bytes)?;
+    let page_name = launch_layout.page_name(&launch_data_bytes).to_string();
+    let end_date = launch_layout.end_date(&launch_data_bytes);
+    let num_mints = launch_layout.num_mints(&launch_data_bytes);
+    let tickets_sold = launch_layout.tickets_sold(&launch_data_bytes);
+    let launch_type = launch_layout.launch_meta_type(&launch_data_bytes);
+    let launch_failed = launch_layout.is_failed(&launch_data_bytes);
+    drop(launch_data_bytes);
+
+    if launch_failed {
+        msg!("Launch failed, its tickets are refunded rather than settled");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time <= end_date {
+        msg!("Raffle is still active (end_date: {}, current: {})", end_date, current_time);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if tickets_sold < num_mints {
+        msg!("Launch failed: {} tickets sold, {} mints required", tickets_sold, num_mints);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // if every ticket wins there is nothing to draw
+    let draw = if tickets_sold == num_mints || launch_type == LaunchMetaType::IDO {
+        None
+    } else {
+        let (expected_draw, draw_bump) = get_raffle_draw_address(ctx.accounts.launch_data.key, program_id);
+        if ctx.accounts.raffle_draw.key != &expected_draw {
+            msg!("expected raffle draw {} {}", expected_draw, ctx.accounts.raffle_draw.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if **ctx.accounts.raffle_draw.try_borrow_lamports()? == 0 {
+            return request_raffle_randomness(program_id, &ctx.accounts, draw_bump, tickets_sold, num_mints);
+        }
+
+        if ctx.accounts.raffle_draw.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let mut draw = RaffleDraw::deserialize(&mut &ctx.accounts.raffle_draw.try_borrow_data()?[..])?;
+        if draw.account_type != AccountType::RaffleDraw || draw.launch != *ctx.accounts.launch_data.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if draw.rng == 0 {
+            let request = randomness::RandomnessRequest {
+                orao_request: draw.orao_request,
+                launch: *ctx.accounts.launch_data.key,
+                consumer: *ctx.accounts.launch_data.key,
+            };
+            let randoms = randomness::load_provider(program_id, ctx.accounts.randomness, &request)?.randomness()?;
+
+            draw.rng = u64::from_le_bytes(randoms[..8].try_into().unwrap());
+            if draw.rng == 0 {
+                msg!("invalid seed");
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        if !draw.is_complete() {
+            let mut draw_data = ctx.accounts.raffle_draw.try_borrow_mut_data()?;
+            let (header, drawn) = draw_data.split_at_mut(RaffleDraw::LEN);
+            draw.draw(drawn, DRAW_BATCH);
+            draw.serialize(&mut &mut header[..])?;
+
+            if !draw.is_complete() {
+                msg!("Drew {} of {} tickets, keep cranking to finish the draw", draw.num_drawn, draw.num_to_draw);
+                return Ok(());
+            }
+        }
+        Some(draw)
+    };
+
+    let draw_data = ctx.accounts.raffle_draw.try_borrow_data()?;
+    let drawn = draw_data.get(RaffleDraw::LEN..).unwrap_or(&[]);
+
+    let mut tickets_settled: u32 = 0;
+    let mut new_wins: u32 = 0;
+    for join_account in ctx.remaining_accounts {
+        if join_account.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let mut join_data = JoinData::load(&join_account.try_borrow_data()?)?;
+        if join_data.account_type != state::AccountType::Join || join_data.page_name != page_name {
+            msg!("{} is not a join for this launch", join_account.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let first_ticket = match join_data.first_ticket {
+            Some(first_ticket) => first_ticket,
+            None => {
+                msg!("{} holds tickets from before indexing, check them with CheckTickets", join_account.key);
+                continue;
+            }
+        };
+        if join_data.num_tickets_checked >= join_data.num_tickets {
+            continue;
+        }
+
+        let num_tickets = join_data.num_tickets as u32;
+        let wins = match &draw {
+            Some(draw) => draw.winning_tickets(drawn, first_ticket, num_tickets),
+            None => num_tickets,
+        };
+
+        join_data.num_winning_tickets = wins as u16;
+        join_data.num_tickets_checked = join_data.num_tickets;
+        join_data.store(&mut join_account.try_borrow_mut_data()?)?;
+
+        tickets_settled += num_tickets;
+        new_wins += wins;
+    }
+    drop(draw_data);
+
+    let mut launch_data_bytes = ctx.accounts.launch_data.try_borrow_mut_data()?;
+    let ticket_claimed = launch_layout.ticket_claimed(&launch_data_bytes);
+    let mints_won = launch_layout.mints_won(&launch_data_bytes);
+    let num_interactions = launch_layout.num_interactions(&launch_data_bytes);
+    launch_layout.set_ticket_claimed(&mut launch_data_bytes, ticket_claimed + tickets_settled);
+    launch_layout.set_mints_won(&mut launch_data_bytes, mints_won + new_wins);
+    launch_layout.set_num_interactions(&mut launch_data_bytes, num_interactions.saturating_add(1));
+    drop(launch_data_bytes);
+
+    // the launch never pays out below its rent exemption
+    let crank_fee = state::RAFFLE_CRANK_FEE * tickets_settled as u64;
+    let rent_floor = Rent::get()?.minimum_balance(ctx.accounts.launch_data.data_len());
+    let available = ctx.accounts.launch_data.lamports().saturating_sub(rent_floor);
+    let payout = crank_fee.min(available);
+
+    **ctx.accounts.launch_data.try_borrow_mut_lamports()? -= payout;
+    **ctx.accounts.cranker.try_borrow_mut_lamports()? += payout;
+
+    msg!(
+        "✅ Settled {} tickets with {} winners, paid {} lamports to {}",
+        tickets_settled,
+        new_wins,
+        payout,
+        ctx.accounts.cranker.key
+    );
+    Ok(())
+}
+
+/// Creates the raffle's RaffleDraw once the raffle has closed and, on Orao networks, places the request its draw is taken from
+fn request_raffle_randomness<'a>(
+    program_id: &Pubkey,
+    accounts: &SettleRaffleAccounts<'a>,
+    draw_bump: u8,
+    tickets_sold: u32,
+    num_mints: u32,
+) -> ProgramResult {
+    let request_slot = Clock::get()?.slot;
+    let orao_request = if state::NETWORK != state::Network::Eclipse {
+        let seed = get_raffle_request_seed(accounts.launch_data.key, request_slot);
+        orao::request_randomness(
+            accounts.cranker,
+            accounts.orao_network,
+            accounts.orao_treasury,
+            accounts.randomness,
+            accounts.system_program,
+            accounts.orao_program,
+            seed,
+        )?;
+        orao::get_request_address(&seed)
+    } else {
+        Pubkey::default()
+    };
+
+    utils::create_program_account(
+        accounts.cranker,
+        accounts.raffle_draw,
+        program_id,
+        draw_bump,
+        RaffleDraw::account_size(tickets_sold),
+        vec![&accounts.launch_data.key.to_bytes(), RAFFLE_DRAW_SEED],
+    )?;
+    let draw = RaffleDraw::new(*accounts.launch_data.key, request_slot, orao_request, tickets_sold, num_mints);
+    draw.serialize(&mut &mut accounts.raffle_draw.try_borrow_mut_data()?[..])?;
+
+    msg!("Requested the raffle's randomness in slot {}, settle once it is available", request_slot);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joins(ticket_counts: &[u32]) -> Vec<(u32, u32)> {
+        let mut first_ticket = 0;
+        ticket_counts
+            .iter()
+            .map(|num_tickets| {
+                let join = (first_ticket, *num_tickets);
+                first_ticket += num_tickets;
+                join
+            })
+            .collect()
+    }
+
+    /// The raffle's draw run to completion in batches of `batch`, with its bitmap
+    fn complete_draw(seed: u64, tickets_sold: u32, num_mints: u32, batch: u32) -> (RaffleDraw, Vec<u8>) {
+        let mut draw = RaffleDraw::new(Pubkey::new_unique(), 0, Pubkey::default(), tickets_sold, num_mints);
+        draw.rng = seed;
+        let mut drawn = vec![0u8; RaffleDraw::account_size(tickets_sold) - RaffleDraw::LEN];
+        while !draw.is_complete() {
+            draw.draw(&mut drawn, batch);
+        }
+        (draw, drawn)
+    }
+
+    #[test]
+    fn test_draw_never_repeats_a_ticket() {
+        let (_, drawn) = complete_draw(42, 1000, 500, 1000);
+        assert_eq!(count_drawn(&drawn, 0, 1000), 500);
+
+        let mut rng = 42;
+        let mut drawn = vec![0u8; 125];
+        assert_eq!(draw_tickets(&mut rng, &mut drawn, 1000, 0, 1000), 1000);
+        assert!(drawn.iter().all(|byte| *byte == u8::MAX));
+
+        // the same seed always gives the same draw
+        let draw = |seed| {
+            let (mut rng, mut drawn) = (seed, vec![0u8; 125]);
+            draw_tickets(&mut rng, &mut drawn, 1000, 0, 100);
+            drawn
+        };
+        assert_eq!(count_drawn(&draw(42), 0, 1000), 100);
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn test_draw_in_batches() {
+        // however the draw is split across SettleRaffle calls it comes out the same
+        let (whole, whole_drawn) = complete_draw(99, 5_000, 2_000, u32::MAX);
+        let (batched, batched_drawn) = complete_draw(99, 5_000, 2_000, 7);
+        assert_eq!(whole_drawn, batched_drawn);
+        assert_eq!(whole.rng, batched.rng);
+
+        let mut draw = RaffleDraw::new(Pubkey::new_unique(), 0, Pubkey::default(), 5_000, 2_000);
+        draw.rng = 99;
+        let mut drawn = vec![0u8; 625];
+        draw.draw(&mut drawn, DRAW_BATCH);
+        assert_eq!(draw.num_drawn, DRAW_BATCH);
+        assert!(!draw.is_complete());
+        assert_eq!(RaffleDraw::account_size(5_001), RaffleDraw::LEN + 626);
+        assert_eq!(borsh::to_vec(&draw).unwrap().len(), RaffleDraw::LEN);
+    }
+
+    #[test]
+    fn test_every_mint_is_won_once() {
+        let joins = joins(&[3, 1, 250, 17, 1000, 9, 120]);
+        let tickets_sold: u32 = joins.iter().map(|(_, num_tickets)| num_tickets).sum();
+
+        // few winners draws the winners, many draws the losers
+        for num_mints in [1, 100, tickets_sold / 2, tickets_sold - 100, tickets_sold - 1, tickets_sold] {
+            let (draw, drawn) = complete_draw(7, tickets_sold, num_mints, DRAW_BATCH);
+            let wins: u32 = joins
+                .iter()
+                .map(|(first_ticket, num_tickets)| draw.winning_tickets(&drawn, *first_ticket, *num_tickets))
+                .sum();
+            assert_eq!(wins, num_mints);
+        }
+    }
+
+    #[test]
+    fn test_settlement_order_does_not_matter() {
+        let joins = joins(&[5, 40, 2, 300, 11]);
+        let (draw, drawn) = complete_draw(1234, 358, 50, DRAW_BATCH);
+
+        let forward: Vec<u32> = joins.iter().map(|(first, num)| draw.winning_tickets(&drawn, *first, *num)).collect();
+        let mut backward: Vec<u32> = joins.iter().rev().map(|(first, num)| draw.winning_tickets(&drawn, *first, *num)).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        // a ticket's outcome is the same whether it is settled alone or with its join
+        let (first, num) = joins[3];
+        let alone: u32 = (first..first + num).map(|ticket| draw.winning_tickets(&drawn, ticket, 1)).sum();
+        assert_eq!(alone, forward[3]);
+    }
+
+    #[test]
+    fn test_request_seed_depends_on_the_slot() {
+        let launch = Pubkey::new_unique();
+        assert_ne!(get_raffle_request_seed(&launch, 100), get_raffle_request_seed(&launch, 101));
+    }
+}
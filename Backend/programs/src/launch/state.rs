@@ -1,9 +1,15 @@
+use std::str::FromStr;
+
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke, program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+    system_instruction, sysvar::Sysvar,
+};
 
-use crate::state::AccountType;
+use crate::state::{AccountType, NativeLaunchData, NativeLaunchMeta};
+use crate::utils;
 
-use super::{LaunchMeta, LaunchPlugin};
+use super::{LaunchAccessor, LaunchLayout, LaunchMeta, LaunchPlugin, Raffle, FCFS, IDO};
 
 pub enum Distribution {
     Raffle,
@@ -47,6 +53,7 @@ pub enum LaunchFlags {
     AMMProvider,
     Extensions,
     Transferring,
+    Paused,
     LENGTH,
 }
 
@@ -83,10 +90,14 @@ pub struct Listing {
     pub socials: Vec<String>,
 }
 
-// 79 bytes
+/// Version written to every launch account by this program. Launch accounts from before versioning
+/// hold their launch_meta tag (0-2) in this byte, so versions start at 3 to tell them apart.
+pub const LAUNCH_DATA_VERSION: u8 = 3;
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct LaunchData {
     pub account_type: AccountType,
+    pub version: u8,
     pub launch_meta: LaunchMeta,
     pub plugins: Vec<LaunchPlugin>,
     pub last_interaction: i64,
@@ -106,13 +117,18 @@ pub struct LaunchData {
 
     pub buffer1: u64,
     pub buffer2: u64,
-    pub buffer3: u32,
+    pub buffer3: u64,
 
     pub distribution: Vec<u8>,
     pub flags: Vec<u8>,
     pub strings: Vec<String>,
     pub keys: Vec<Pubkey>,
-    
+
+    pub creator: Pubkey,
+    // hype voting
+    pub upvotes: u32,
+    pub downvotes: u32,
+
     // Instant launch fields (pump.fun-style bonding curve)
     pub is_tradable: bool, // Whether the token can be traded (raffle graduation)
     pub tokens_sold: u64, // Tokens sold (circulating supply) for instant launches - pump.fun style bonding curve
@@ -120,6 +136,175 @@ pub struct LaunchData {
     pub graduation_threshold: u64, // Market cap threshold for graduation (in lamports, default ~$85k)
 }
 
+/// Launch layout the launch module wrote before LaunchData was versioned, only kept so MigrateLaunch can read old accounts
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct LegacyLaunchData {
+    pub account_type: AccountType,
+    pub launch_meta: LaunchMeta,
+    pub plugins: Vec<LaunchPlugin>,
+    pub last_interaction: i64,
+    pub num_interactions: u16,
+
+    pub page_name: String,
+    pub listing: Pubkey,
+    pub total_supply: u64,
+    pub num_mints: u32,
+    pub ticket_price: u64,
+    pub minimum_liquidity: u64,
+    pub launch_date: u64,
+    pub end_date: u64,
+    pub tickets_sold: u32,
+    pub ticket_claimed: u32,
+    pub mints_won: u32,
+
+    pub buffer1: u64,
+    pub buffer2: u64,
+    pub buffer3: u32,
+
+    pub distribution: Vec<u8>,
+    pub flags: Vec<u8>,
+    pub strings: Vec<String>,
+    pub keys: Vec<Pubkey>,
+
+    pub is_tradable: bool,
+    pub tokens_sold: u64,
+    pub is_graduated: bool,
+    pub graduation_threshold: u64,
+}
+
+impl LaunchData {
+    /// Reads a launch account written with the current layout, ignoring any unused space after it
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < 2 || data[0] != AccountType::Launch as u8 {
+            msg!("Account is not a launch account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if data[1] != LAUNCH_DATA_VERSION {
+            msg!("Launch account uses a legacy layout, it needs a MigrateLaunch first");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Reads a launch account written with any layout, converting legacy accounts to the current one
+    pub fn load_any_layout(data: &[u8]) -> Result<Self, ProgramError> {
+        match LaunchAccessor::load(data)?.layout {
+            LaunchLayout::Versioned => Self::load(data),
+            LaunchLayout::Native => Self::try_from(NativeLaunchData::deserialize(&mut &data[..])?),
+            LaunchLayout::Launch => Ok(Self::from(LegacyLaunchData::deserialize(&mut &data[..])?)),
+        }
+    }
+}
+
+impl From<LegacyLaunchData> for LaunchData {
+    fn from(legacy: LegacyLaunchData) -> Self {
+        // these launches had no creator field, the seller key has always been the creator
+        let creator = legacy.keys.get(LaunchKeys::Seller as usize).copied().unwrap_or_default();
+
+        LaunchData {
+            account_type: AccountType::Launch,
+            version: LAUNCH_DATA_VERSION,
+            launch_meta: legacy.launch_meta,
+            plugins: legacy.plugins,
+            last_interaction: legacy.last_interaction,
+            num_interactions: legacy.num_interactions,
+            page_name: legacy.page_name,
+            listing: legacy.listing,
+            total_supply: legacy.total_supply,
+            num_mints: legacy.num_mints,
+            ticket_price: legacy.ticket_price,
+            minimum_liquidity: legacy.minimum_liquidity,
+            launch_date: legacy.launch_date,
+            end_date: legacy.end_date,
+            tickets_sold: legacy.tickets_sold,
+            ticket_claimed: legacy.ticket_claimed,
+            mints_won: legacy.mints_won,
+            buffer1: legacy.buffer1,
+            buffer2: legacy.buffer2,
+            buffer3: legacy.buffer3 as u64,
+            distribution: legacy.distribution,
+            flags: legacy.flags,
+            strings: legacy.strings,
+            keys: legacy.keys,
+            creator,
+            upvotes: 0,
+            downvotes: 0,
+            is_tradable: legacy.is_tradable,
+            tokens_sold: legacy.tokens_sold,
+            is_graduated: legacy.is_graduated,
+            graduation_threshold: legacy.graduation_threshold,
+        }
+    }
+}
+
+impl TryFrom<NativeLaunchData> for LaunchData {
+    type Error = ProgramError;
+
+    fn try_from(native: NativeLaunchData) -> Result<Self, Self::Error> {
+        let listing = Pubkey::from_str(&native.listing).map_err(|_| {
+            msg!("Launch listing {} is not a valid address", native.listing);
+            ProgramError::InvalidAccountData
+        })?;
+
+        let distribution = native
+            .distribution
+            .iter()
+            .map(|percent| u8::try_from(*percent).map_err(|_| ProgramError::InvalidAccountData))
+            .collect::<Result<Vec<u8>, ProgramError>>()?;
+
+        let launch_meta = match native.launch_meta {
+            NativeLaunchMeta::Raffle => LaunchMeta::Raffle(Raffle {}),
+            NativeLaunchMeta::FCFS => LaunchMeta::FCFS(FCFS {}),
+            NativeLaunchMeta::IDO {
+                token_fraction_distributed,
+                tokens_distributed,
+            } => LaunchMeta::IDO(IDO {
+                token_fraction_distributed: token_fraction_distributed as f64,
+                tokens_distributed,
+            }),
+        };
+
+        // native keys only held the mint as a string, which the listing already points to
+        let mut keys = vec![Pubkey::default(); LaunchKeys::LENGTH as usize];
+        keys[LaunchKeys::Seller as usize] = native.creator;
+        keys[LaunchKeys::TeamWallet as usize] = native.creator;
+
+        Ok(LaunchData {
+            account_type: AccountType::Launch,
+            version: LAUNCH_DATA_VERSION,
+            launch_meta,
+            plugins: Vec::new(),
+            last_interaction: native.last_interaction as i64,
+            num_interactions: native.num_interactions,
+            page_name: native.page_name,
+            listing,
+            total_supply: native.total_supply,
+            num_mints: native.num_mints,
+            ticket_price: native.ticket_price,
+            minimum_liquidity: native.minimum_liquidity,
+            launch_date: native.launch_date,
+            end_date: native.end_date,
+            tickets_sold: native.tickets_sold,
+            ticket_claimed: native.ticket_claimed,
+            mints_won: native.mints_won,
+            buffer1: native.buffer1,
+            buffer2: native.buffer2,
+            buffer3: native.buffer3,
+            distribution,
+            flags: native.flags,
+            strings: native.strings,
+            keys,
+            creator: native.creator,
+            upvotes: native.upvotes,
+            downvotes: native.downvotes,
+            is_tradable: native.is_tradable,
+            tokens_sold: native.tokens_sold,
+            is_graduated: native.is_graduated,
+            graduation_threshold: native.graduation_threshold,
+        })
+    }
+}
+
 #[derive(Default, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct JoinData {
     pub account_type: AccountType,
@@ -132,10 +317,203 @@ pub struct JoinData {
     pub random_address: Pubkey,
     pub last_slot: u64,
     pub order_id: String, // Transaction signature of the ticket purchase
+    /// Raffle index of the first of this join's tickets, which run on contiguously from it.
+    /// None for tickets bought before indexing, which are checked with CheckTickets instead of the crank
+    pub first_ticket: Option<u32>,
+    /// Lamports this wallet has spent against its merkle allowlist cap
+    pub allowlist_spent: u64,
+    /// Lamports this wallet has contributed to an IDO launch, after the platform fee
+    pub ido_contribution: u64,
+    /// Lamports this wallet has paid for dutch auction tickets at the prices it bid, until claiming refunds
+    /// whatever it paid over the clearing price
+    pub auction_paid: u64,
+    /// Platform fee this wallet's raffle tickets were bought at, in basis points, which a failed launch keeps back from its refund
+    pub ticket_fee_bps: u16,
+}
+
+impl JoinData {
+    /// Largest encoding of the fields appended after order_id, first_ticket through ticket_fee_bps
+    const APPENDED_LEN: usize = 5 + 8 * 3 + 2;
+
+    /// Reads join data, padding accounts created before the appended fields with zeros, so they
+    /// read as no first_ticket and nothing spent, contributed, paid or kept back
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut padded = data.to_vec();
+        padded.resize(data.len() + Self::APPENDED_LEN, 0);
+        Ok(Self::deserialize(&mut &padded[..])?)
+    }
+
+    /// Writes the join data back. Accounts created before the appended fields may leave off a tail of zeros,
+    /// which `load` reads back the same, anything else has to be grown with `resize_join_data` first
+    pub fn store(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let encoded = to_vec(self)?;
+        let len = data.len().min(encoded.len());
+        if encoded[len..].iter().any(|byte| *byte != 0) {
+            msg!("JoinData account holds {} bytes, its data needs {}", data.len(), encoded.len());
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        data[..len].copy_from_slice(&encoded[..len]);
+        Ok(())
+    }
+
+    /// Bytes the join data takes up
+    pub fn size(&self) -> Result<usize, ProgramError> {
+        Ok(to_vec(self)?.len())
+    }
+
+    /// Whether every ticket has been claimed or refunded, so nothing will read or pay out of the account again
+    pub fn is_settled(&self) -> bool {
+        match self.ticket_status {
+            TicketStatus::FullyRefunded | TicketStatus::WinningClaimed => true,
+            TicketStatus::LosingRefunded => self.num_winning_tickets == 0,
+            TicketStatus::Available => false,
+        }
+    }
 }
 
 pub fn get_join_data_size() -> usize {
-    let encoded = to_vec(&JoinData::default()).unwrap();
+    let encoded = to_vec(&JoinData {
+        first_ticket: Some(0),
+        ..JoinData::default()
+    })
+    .unwrap();
 
     encoded.len()
 }
+
+/// Grows a JoinData account to `len` bytes, with the payer covering the extra rent
+pub fn resize_join_data<'a>(payer: &AccountInfo<'a>, join_data: &AccountInfo<'a>, system_program: &AccountInfo<'a>, len: usize) -> ProgramResult {
+    if join_data.data_len() >= len {
+        return Ok(());
+    }
+
+    let required_lamports = Rent::get()?.minimum_balance(len);
+    let current_lamports = **join_data.try_borrow_lamports()?;
+    if required_lamports > current_lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, join_data.key, required_lamports - current_lamports),
+            &[payer.clone(), join_data.clone(), system_program.clone()],
+        )?;
+    }
+
+    msg!("Resizing JoinData account from {} to {} bytes", join_data.data_len(), len);
+    join_data.realloc(len, true)
+}
+
+/// Reads the user's JoinData for a launch, creating the Joiner PDA with the user paying the rent on their first purchase
+pub fn load_or_create_join_data<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    join_data: &AccountInfo<'a>,
+    page_name: &str,
+) -> Result<JoinData, ProgramError> {
+    let seeds: Vec<&[u8]> = vec![user.key.as_ref(), page_name.as_bytes(), b"Joiner"];
+    let (expected_join_data, join_bump) = Pubkey::find_program_address(&seeds, program_id);
+    if join_data.key != &expected_join_data {
+        msg!("JoinData is not the user's PDA for page {}", page_name);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if **join_data.try_borrow_lamports()? > 0 {
+        if join_data.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        return JoinData::load(&join_data.try_borrow_data()?);
+    }
+
+    let new_join_data = JoinData {
+        account_type: AccountType::Join,
+        joiner_key: *user.key,
+        page_name: page_name.to_string(),
+        ticket_status: TicketStatus::Available,
+        random_address: solana_program::system_program::ID,
+        order_id: "pending".to_string(),
+        ..JoinData::default()
+    };
+    // leave room for the first_ticket BuyTickets records after creating the account
+    let join_data_len = to_vec(&JoinData {
+        first_ticket: Some(0),
+        ..new_join_data.clone()
+    })?
+    .len();
+    utils::create_program_account(user, join_data, program_id, join_bump, join_data_len, seeds)?;
+    Ok(new_join_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_data_is_settled() {
+        let join_data = JoinData {
+            num_tickets: 3,
+            num_winning_tickets: 1,
+            ..JoinData::default()
+        };
+        assert!(!join_data.is_settled());
+
+        // losing tickets refunded with a winning ticket still to claim
+        let refunded = JoinData {
+            ticket_status: TicketStatus::LosingRefunded,
+            ..join_data.clone()
+        };
+        assert!(!refunded.is_settled());
+        assert!(JoinData {
+            num_winning_tickets: 0,
+            ..refunded
+        }
+        .is_settled());
+
+        assert!(JoinData {
+            ticket_status: TicketStatus::WinningClaimed,
+            ..join_data.clone()
+        }
+        .is_settled());
+        assert!(JoinData {
+            ticket_status: TicketStatus::FullyRefunded,
+            ..join_data
+        }
+        .is_settled());
+    }
+
+    #[test]
+    fn test_join_data_loads_accounts_without_appended_fields() {
+        let join_data = JoinData {
+            account_type: AccountType::Join,
+            page_name: "cook".to_string(),
+            num_tickets: 3,
+            order_id: "pending".to_string(),
+            ..JoinData::default()
+        };
+        // an account written before first_ticket and the fields after it were added
+        let mut old_account = to_vec(&join_data).unwrap();
+        old_account.truncate(old_account.len() - (1 + 8 * 3 + 2));
+        assert!(JoinData::try_from_slice(&old_account).is_err());
+        assert_eq!(JoinData::load(&old_account).unwrap(), join_data);
+
+        // writes keep to the old account's size
+        let checked = JoinData {
+            num_tickets_checked: 3,
+            num_winning_tickets: 1,
+            ..join_data
+        };
+        checked.store(&mut old_account).unwrap();
+        assert_eq!(JoinData::load(&old_account).unwrap(), checked);
+
+        // appended fields are never cut off, the account has to be grown to hold them
+        let bought = JoinData {
+            first_ticket: Some(7),
+            ticket_fee_bps: 100,
+            ..checked.clone()
+        };
+        let old_len = old_account.len();
+        assert_eq!(bought.store(&mut old_account), Err(ProgramError::AccountDataTooSmall));
+        assert_eq!(JoinData::load(&old_account).unwrap(), checked);
+
+        old_account.resize(bought.size().unwrap(), 0);
+        assert!(old_account.len() > old_len);
+        bought.store(&mut old_account).unwrap();
+        assert_eq!(JoinData::load(&old_account).unwrap(), bought);
+    }
+}
@@ -11,6 +11,12 @@ pub mod listings;
 pub mod achievements;
 pub mod common;
 pub mod amm;
+pub mod raydium;
+pub mod candles;
+pub mod trade_to_earn;
+pub mod amm_plugins;
+pub mod orao;
+pub mod randomness;
 pub mod events;
 pub mod bonding_curve;
 pub mod bot_detection;
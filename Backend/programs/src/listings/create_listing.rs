@@ -42,10 +42,7 @@ pub fn create_listing<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
 
     accounts::check_system_program_key(ctx.accounts.system_program)?;
 
-    if ctx.accounts.user.key != &accounts::daoplays_account::ID && ctx.accounts.user.key != &accounts::admin2::ID {
-        msg!("invalid user");
-        return Err(ProgramError::InvalidAccountData);
-    }
+    accounts::check_admin(program_id, ctx.accounts.cook_data, ctx.accounts.user)?;
 
     // Handle any other checks
 
@@ -72,11 +69,11 @@ pub fn create_listing<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
         return Ok(());
     }
 
-    let mut program_data = state::ProgramData::try_from_slice(&ctx.accounts.cook_data.data.borrow()[..])?;
+    let mut program_data = state::ProgramData::load(&ctx.accounts.cook_data.data.borrow())?;
 
     program_data.num_launches += 1;
 
-    program_data.serialize(&mut &mut ctx.accounts.cook_data.data.borrow_mut()[..])?;
+    program_data.store(&mut ctx.accounts.cook_data.data.borrow_mut())?;
 
     let mut unverified = Listing::try_from_slice(&ctx.accounts.unverified.data.borrow()[..])?;
 
@@ -135,8 +132,8 @@ pub fn create_listing<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>],
 
         // set start time
         let clock = Clock::get()?;
-        let mut amm_data = AMM::try_from_slice(&ctx.accounts.amm.data.borrow()[..])?;
-        amm_data.serialize(&mut &mut ctx.accounts.amm.data.borrow_mut()[..])?;
+        let mut amm_data = AMM::load(&ctx.accounts.amm.data.borrow())?;
+        amm_data.store(&mut ctx.accounts.amm.data.borrow_mut())?;
     }
 
     let account_lamports = **ctx.accounts.unverified.try_borrow_lamports()?;
@@ -41,9 +41,7 @@ pub fn edit_listing<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], ar
         return Ok(());
     }
 
-    if *ctx.accounts.user.key != accounts::daoplays_account::ID {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    accounts::check_admin(program_id, ctx.accounts.cook_data, ctx.accounts.user)?;
 
     let mut listing = Listing::try_from_slice(&ctx.accounts.listing.data.borrow()[..])?;
 
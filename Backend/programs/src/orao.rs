@@ -0,0 +1,251 @@
+/**
+ * Reading randomness from Orao VRF request accounts.
+ *
+ * A request account ([b"orao-vrf-randomness-request", seed] under the Orao program) is an Anchor
+ * account in one of two layouts, told apart by the discriminator:
+ *  - `Randomness` (v1): seed, randomness, responses. Fulfilled once randomness is non zero.
+ *  - `RandomnessV2`: a `Request` enum that is either Pending (still collecting responses)
+ *    or Fulfilled (client, seed, randomness).
+ *
+ * Randomness is only ever taken from an account owned by the Orao program at the request address
+ * the caller committed to, and only once the request is fulfilled. Requests are placed by this
+ * program with `request_randomness`, from seeds that include the slot they are placed in. Orao creates
+ * the request account, so a request somebody placed ahead of time makes the CPI fail rather than
+ * being reused.
+ */
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{accounts, state};
+
+pub const RANDOMNESS_REQUEST_SEED: &[u8] = b"orao-vrf-randomness-request";
+
+/// sha256("global:request")[..8]
+const REQUEST_DISCRIMINATOR: [u8; 8] = [46, 101, 67, 11, 76, 137, 12, 173];
+
+/// sha256("account:Randomness")[..8]
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [188, 96, 216, 248, 93, 94, 49, 112];
+/// sha256("account:RandomnessV2")[..8]
+const RANDOMNESS_V2_DISCRIMINATOR: [u8; 8] = [139, 239, 184, 215, 227, 86, 191, 226];
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RandomnessResponse {
+    pub pubkey: Pubkey,
+    pub randomness: [u8; 64],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Randomness {
+    pub seed: [u8; 32],
+    pub randomness: [u8; 64],
+    pub responses: Vec<RandomnessResponse>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PendingRequest {
+    pub client: Pubkey,
+    pub seed: [u8; 32],
+    pub responses: Vec<RandomnessResponse>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct FulfilledRequest {
+    pub client: Pubkey,
+    pub seed: [u8; 32],
+    pub randomness: [u8; 64],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum Request {
+    Pending(PendingRequest),
+    Fulfilled(FulfilledRequest),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RandomnessV2 {
+    pub request: Request,
+}
+
+/// Address of the Orao request account for `seed`
+pub fn get_request_address(seed: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[RANDOMNESS_REQUEST_SEED, seed], &accounts::orao_program::ID).0
+}
+
+/// Seed of the Orao request BuyTickets places for a join, bought in `slot`
+pub fn get_join_request_seed(user: &Pubkey, launch: &Pubkey, slot: u64) -> [u8; 32] {
+    hashv(&[user.as_ref(), launch.as_ref(), &slot.to_le_bytes(), b"Joiner"]).to_bytes()
+}
+
+/// Places the Orao request for `seed` at `request`, with the payer paying Orao's fee and the account's rent
+pub fn request_randomness<'a>(
+    payer: &AccountInfo<'a>,
+    network_state: &AccountInfo<'a>,
+    treasury: &AccountInfo<'a>,
+    request: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    orao_program: &AccountInfo<'a>,
+    seed: [u8; 32],
+) -> ProgramResult {
+    if orao_program.key != &accounts::orao_program::ID {
+        msg!("expected orao {} {}", accounts::orao_program::ID, orao_program.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let expected_address = get_request_address(&seed);
+    if request.key != &expected_address {
+        msg!("expected randomness request {} {}", expected_address, request.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let data = borsh::to_vec(&state::OraoRequest {
+        descriminator: REQUEST_DISCRIMINATOR,
+        seed,
+    })?;
+    let instruction = Instruction::new_with_bytes(
+        *orao_program.key,
+        &data,
+        vec![
+            AccountMeta::new(*payer.key, true),
+            AccountMeta::new(*network_state.key, false),
+            AccountMeta::new(*treasury.key, false),
+            AccountMeta::new(*request.key, false),
+            AccountMeta::new_readonly(*system_program.key, false),
+        ],
+    );
+    invoke(
+        &instruction,
+        &[payer.clone(), network_state.clone(), treasury.clone(), request.clone(), system_program.clone()],
+    )
+}
+
+/// The fulfilled randomness of an Orao request account's data, None while the request is pending
+pub fn parse_fulfilled_randomness(data: &[u8]) -> Result<Option<[u8; 64]>, ProgramError> {
+    if data.len() < 8 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let (discriminator, mut body) = data.split_at(8);
+
+    if discriminator == RANDOMNESS_DISCRIMINATOR {
+        let randomness = Randomness::deserialize(&mut body).map_err(|_| ProgramError::InvalidAccountData)?;
+        if randomness.randomness == [0; 64] {
+            return Ok(None);
+        }
+        return Ok(Some(randomness.randomness));
+    }
+
+    if discriminator == RANDOMNESS_V2_DISCRIMINATOR {
+        let randomness = RandomnessV2::deserialize(&mut body).map_err(|_| ProgramError::InvalidAccountData)?;
+        return match randomness.request {
+            Request::Pending(_) => Ok(None),
+            Request::Fulfilled(fulfilled) => Ok(Some(fulfilled.randomness)),
+        };
+    }
+
+    msg!("Account is not an Orao randomness request");
+    Err(ProgramError::InvalidAccountData)
+}
+
+/// Reads the randomness of the Orao request at `expected_address`, failing if the account is not
+/// that request, is not owned by Orao or has not been fulfilled yet
+pub fn get_fulfilled_randomness(orao_random: &AccountInfo, expected_address: &Pubkey) -> Result<[u8; 64], ProgramError> {
+    if orao_random.key != expected_address {
+        msg!("expected randomness request {} {}", expected_address, orao_random.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if orao_random.owner != &accounts::orao_program::ID {
+        msg!("Randomness request is not owned by orao {}", orao_random.owner);
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    match parse_fulfilled_randomness(&orao_random.try_borrow_data()?)? {
+        Some(randomness) => Ok(randomness),
+        None => {
+            msg!("Randomness request {} has not been fulfilled yet", orao_random.key);
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<T: BorshSerialize>(discriminator: [u8; 8], account: &T) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(borsh::to_vec(account).unwrap());
+        data
+    }
+
+    #[test]
+    fn test_discriminators() {
+        assert_eq!(solana_program::hash::hash(b"account:Randomness").to_bytes()[..8], RANDOMNESS_DISCRIMINATOR);
+        assert_eq!(solana_program::hash::hash(b"account:RandomnessV2").to_bytes()[..8], RANDOMNESS_V2_DISCRIMINATOR);
+        assert_eq!(solana_program::hash::hash(b"global:request").to_bytes()[..8], REQUEST_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_join_request_seed() {
+        let user = Pubkey::new_unique();
+        let launch = Pubkey::new_unique();
+        // a request for a later purchase cannot be placed from an earlier one
+        assert_eq!(get_join_request_seed(&user, &launch, 10), get_join_request_seed(&user, &launch, 10));
+        assert_ne!(get_join_request_seed(&user, &launch, 10), get_join_request_seed(&user, &launch, 11));
+        assert_ne!(get_join_request_seed(&user, &launch, 10), get_join_request_seed(&launch, &user, 10));
+    }
+
+    #[test]
+    fn test_parse_v2_request() {
+        let pending = RandomnessV2 {
+            request: Request::Pending(PendingRequest {
+                client: Pubkey::new_unique(),
+                seed: [1; 32],
+                responses: vec![RandomnessResponse {
+                    pubkey: Pubkey::new_unique(),
+                    randomness: [9; 64],
+                }],
+            }),
+        };
+        // a partial response is not the final randomness
+        assert_eq!(parse_fulfilled_randomness(&encode(RANDOMNESS_V2_DISCRIMINATOR, &pending)).unwrap(), None);
+
+        let fulfilled = RandomnessV2 {
+            request: Request::Fulfilled(FulfilledRequest {
+                client: Pubkey::new_unique(),
+                seed: [1; 32],
+                randomness: [7; 64],
+            }),
+        };
+        assert_eq!(parse_fulfilled_randomness(&encode(RANDOMNESS_V2_DISCRIMINATOR, &fulfilled)).unwrap(), Some([7; 64]));
+    }
+
+    #[test]
+    fn test_parse_v1_request() {
+        let mut randomness = Randomness {
+            seed: [1; 32],
+            randomness: [0; 64],
+            responses: Vec::new(),
+        };
+        assert_eq!(parse_fulfilled_randomness(&encode(RANDOMNESS_DISCRIMINATOR, &randomness)).unwrap(), None);
+
+        randomness.randomness[3] = 5;
+        assert_eq!(parse_fulfilled_randomness(&encode(RANDOMNESS_DISCRIMINATOR, &randomness)).unwrap(), Some(randomness.randomness));
+    }
+
+    #[test]
+    fn test_rejects_chosen_bytes() {
+        // what a forged account with a seed at offset 40 looks like
+        let mut data = vec![0u8; 104];
+        data[40..48].copy_from_slice(&42u64.to_le_bytes());
+        assert!(parse_fulfilled_randomness(&data).is_err());
+        assert!(parse_fulfilled_randomness(&[]).is_err());
+    }
+}
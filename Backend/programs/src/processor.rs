@@ -1,9 +1,9 @@
 use borsh::BorshDeserialize;
 use crate::instruction::LaunchInstruction;
-use crate::state::ProgramData;
-use crate::launch::{create_pool_on_graduation, instant_launch, create_amm_quote};
+use crate::launch::{instant_launch, create_amm_quote, migrate_launch, settle_raffle, fail_launch, ido, dutch_auction, graduate_launch, liquidity_lock, creator_fees};
 use crate::common;
 use crate::accounts;
+use crate::randomness;
 use crate::utils::token;
 use crate::bonding_curve::{self, AntiWhaleConfig, FirstBlockProtection, ShadowCurveConfig};
 use std::str::FromStr;
@@ -28,112 +28,142 @@ use spl_token_2022::{
 pub struct Processor;
 impl Processor {
     // Helper function to check if a launch is tradable
-    // CRITICAL: Avoid full deserialization - LaunchData has Vec<String> which is memory-heavy
-    // For instant launches (FCFS), always return true (they're always tradable)
-    // For raffle launches, we need to check is_tradable flag but try to avoid full deserialization
+    // Instant launches (FCFS) are always tradable, raffle launches check the is_tradable flag
     fn is_launch_tradable(launch_data: &AccountInfo) -> Result<bool, ProgramError> {
         let launch_data_bytes = launch_data.try_borrow_data()?;
-        
-        // Minimum check: read just the launch_meta enum to see if it's an instant launch
-        // Instant launches (FCFS) are always tradable, so we can skip full deserialization
-        // We need to skip past: account_type (1 byte) to get to launch_meta
-        // But enum deserialization is tricky - let's try a lightweight approach
-        
-        // For now, try minimal deserialization - but this still allocates Vec<String>
-        // Better: assume instant launches are always tradable
-        // Worst case: we'll check properly in the swap logic if needed
-        
-        // Try to check if it's an instant launch without full deserialization
-        // Instant launches are always tradable (pump.fun style)
-        // This is a fast path to avoid deserialization for instant launches
-        match crate::state::LaunchData::try_from_slice(&launch_data_bytes) {
-            Ok(ld) => {
-                // Check if instant launch (FCFS) - these are always tradable
-                if matches!(ld.launch_meta, crate::state::LaunchMeta::FCFS) {
-                    return Ok(true); // Instant launches are always tradable
-                }
-                // For raffle launches, check is_tradable flag
-                Ok(ld.is_tradable)
-            }
-            Err(_) => {
-                // If deserialization fails, default to tradable (better safe than sorry)
-                // The swap logic will handle invalid accounts appropriately
-                Ok(true)
-            }
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+
+        if launch_layout.launch_meta_type(&launch_data_bytes) == crate::launch::LaunchMetaType::FCFS {
+            return Ok(true);
         }
+
+        Ok(launch_layout.is_tradable(&launch_data_bytes))
     }
 
-    // Jupiter-like aggregator for best price routing
-    fn find_best_route(
-        input_mint: &Pubkey,
-        output_mint: &Pubkey,
-        amount_in: u64,
+    // Whether an instant launch is still on its bonding curve, which prices its Cook pool until GraduateLaunch
+    fn is_on_bonding_curve(launch_layout: &crate::launch::LaunchAccessor, launch_data: &AccountInfo) -> Result<bool, ProgramError> {
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        Ok(launch_layout.launch_meta_type(&launch_data_bytes) == crate::launch::LaunchMetaType::FCFS && !launch_layout.is_graduated(&launch_data_bytes))
+    }
+
+    // Loads the launch account passed to a swap and checks it belongs to this program and token mint.
+    // The launch PDA is derived from the page_name stored in the account and the listing from the mint,
+    // so none of the launch state used for pricing or limits can come from the caller.
+    fn load_swap_launch(program_id: &Pubkey, launch_data: &AccountInfo, token_mint: &Pubkey) -> Result<crate::launch::LaunchAccessor, ProgramError> {
+        if launch_data.owner != program_id {
+            msg!("❌ Error: Launch account not owned by program");
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+
+        let page_name = launch_layout.page_name(&launch_data_bytes);
+        let (expected_launch, _) = Pubkey::find_program_address(&[page_name.as_bytes(), b"Launch"], program_id);
+        if launch_data.key != &expected_launch {
+            msg!("❌ Error: Launch account is not the PDA for page {}", page_name);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (expected_listing, _) = Pubkey::find_program_address(&[&token_mint.to_bytes(), b"Listing"], program_id);
+        if launch_layout.listing(&launch_data_bytes) != Some(expected_listing) {
+            msg!("❌ Error: Launch account is not for mint {}", token_mint);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(launch_layout)
+    }
+
+    // Builds the plugins of a new launch from its whitelist, allowlist and creator fee args.
+    // CreateLaunch passes the whitelist mint at index 10 when whitelist_tokens > 0,
+    // and the creator fee is checked against the fee schedule in cook_data at index 5.
+    fn create_launch_plugins(
+        program_id: &Pubkey,
         accounts: &[AccountInfo],
+        whitelist_tokens: u64,
+        whitelist_end: u64,
+        allowlist: Option<crate::launch::MerkleAllowlist>,
+        creator_fee_bps: u16,
+    ) -> Result<Vec<crate::launch::LaunchPlugin>, ProgramError> {
+        let mut plugins = vec![];
+        if creator_fee_bps > 0 {
+            let fee_config = accounts::load_fee_config(program_id, &accounts[5])?;
+            plugins.extend(creator_fees::get_creator_fee_plugin(creator_fee_bps, &fee_config)?);
+        }
+        if let Some(allowlist) = allowlist {
+            msg!("📜 Allowlist phase until {}, default cap {} lamports", allowlist.phase_end, allowlist.default_cap);
+            plugins.push(crate::launch::LaunchPlugin::MerkleAllowlist(allowlist));
+        }
+
+        plugins.extend(crate::launch::get_whitelist_plugin(accounts.get(10), whitelist_tokens, whitelist_end)?);
+        Ok(plugins)
+    }
+
+    // Aggregator for best price routing across the venues passed to BestPriceSwap
+    // Route 0: Cook AMM, Route 1: Raydium CPMM
+    fn find_best_route<'a>(
+        program_id: &Pubkey,
+        input_mint: &AccountInfo<'a>,
+        output_mint: &AccountInfo<'a>,
+        amount_in: u64,
+        cook_venue: Option<&crate::amm::CookVenue<'a, '_>>,
+        raydium_venue: Option<&crate::raydium::RaydiumVenue<'a, '_>>,
     ) -> Result<(u8, u64), ProgramError> {
-        // Route 0: CookDEX
-        // Route 1: RaydiumDEX
-        // Route 2: Jupiter (if available)
-        
-        let mut best_route = 0u8;
-        let mut best_output = 0u64;
+        let mut best_route: Option<(u8, u64)> = None;
         
         // Check CookDEX price
-        if let Ok(cook_output) = Self::get_cook_dex_price(input_mint, output_mint, amount_in, accounts) {
-            best_output = cook_output;
-            best_route = 0;
-        }
-        
-        // Check RaydiumDEX price
-        if let Ok(raydium_output) = Self::get_raydium_dex_price(input_mint, output_mint, amount_in, accounts) {
-            if raydium_output > best_output {
-                best_output = raydium_output;
-                best_route = 1;
+        if let Some(cook_venue) = cook_venue {
+            match Self::get_cook_dex_price(program_id, input_mint, output_mint, amount_in, cook_venue) {
+                Ok(cook_output) => best_route = Some((0, cook_output)),
+                Err(e) => msg!("⚠️ CookDEX quote unavailable: {:?}", e),
             }
         }
         
-        // Check Jupiter price (simplified - would need actual Jupiter integration)
-        if let Ok(jupiter_output) = Self::get_jupiter_price(input_mint, output_mint, amount_in) {
-            if jupiter_output > best_output {
-                best_output = jupiter_output;
-                best_route = 2;
+        // Check RaydiumDEX price
+        if let Some(raydium_venue) = raydium_venue {
+            match Self::get_raydium_dex_price(input_mint, output_mint, amount_in, raydium_venue) {
+                Ok(raydium_output) => {
+                    let is_better = match best_route {
+                        Some((_, best_output)) => raydium_output > best_output,
+                        None => true,
+                    };
+                    if is_better {
+                        best_route = Some((1, raydium_output));
+                    }
+                }
+                Err(e) => msg!("⚠️ RaydiumDEX quote unavailable: {:?}", e),
             }
         }
         
-        Ok((best_route, best_output))
-    }
-    
-    // Get CookDEX price for routing
-    fn get_cook_dex_price(
-        _input_mint: &Pubkey,
-        _output_mint: &Pubkey,
-        amount_in: u64,
-        _accounts: &[AccountInfo],
-    ) -> Result<u64, ProgramError> {
-        // Simplified CookDEX pricing using sqrt formula
-        let tokens_out = ((amount_in as f64) * 1000000.0).sqrt() as u64;
-        Ok(tokens_out)
+        best_route.ok_or_else(|| {
+            msg!("❌ Error: No venue can quote this swap");
+            ProgramError::InvalidAccountData
+        })
     }
     
-    // Get RaydiumDEX price for routing
-    fn get_raydium_dex_price(
-        _input_mint: &Pubkey,
-        _output_mint: &Pubkey,
+    // Get CookDEX price for routing, from the amm_base/amm_quote vault reserves
+    fn get_cook_dex_price<'a>(
+        program_id: &Pubkey,
+        input_mint: &AccountInfo<'a>,
+        output_mint: &AccountInfo<'a>,
         amount_in: u64,
-        _accounts: &[AccountInfo],
+        cook_venue: &crate::amm::CookVenue<'a, '_>,
     ) -> Result<u64, ProgramError> {
-        // Simplified Raydium pricing (would need actual pool data)
-        let tokens_out = (amount_in as f64 * 0.95) as u64; // 5% slippage estimate
+        let tokens_out = cook_venue.quote(program_id, input_mint, output_mint, amount_in)?;
+        msg!("📊 CookDEX quote: {} -> {}", amount_in, tokens_out);
         Ok(tokens_out)
     }
     
-    // Get Jupiter price for routing
-    fn get_jupiter_price(
-        _input_mint: &Pubkey,
-        _output_mint: &Pubkey,
+    // Get RaydiumDEX price for routing, from the CPMM pool state and vaults
+    fn get_raydium_dex_price<'a>(
+        input_mint: &AccountInfo<'a>,
+        output_mint: &AccountInfo<'a>,
         amount_in: u64,
+        raydium_venue: &crate::raydium::RaydiumVenue<'a, '_>,
     ) -> Result<u64, ProgramError> {
-        // Simplified Jupiter pricing (would need actual Jupiter API integration)
-        let tokens_out = (amount_in as f64 * 0.98) as u64; // 2% slippage estimate
+        let now = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+        let (_, tokens_out) = raydium_venue.quote(input_mint, output_mint, amount_in, now)?;
+        msg!("📊 RaydiumDEX quote: {} -> {}", amount_in, tokens_out);
         Ok(tokens_out)
     }
 
@@ -160,9 +190,9 @@ impl Processor {
                 msg!("CheckTickets instruction");
                 Self::process_check_tickets(program_id, accounts)
             },
-            LaunchInstruction::InitCookAMM => {
+            LaunchInstruction::InitCookAMM { args } => {
                 msg!("InitCookAMM instruction");
-                Self::process_init_cook_amm(program_id, accounts)
+                Self::process_init_cook_amm(program_id, accounts, args)
             },
             LaunchInstruction::HypeVote { args } => {
                 msg!("HypeVote instruction");
@@ -186,17 +216,18 @@ impl Processor {
             },
             LaunchInstruction::SwapCookAMM { args } => {
                 msg!("SwapCookAMM instruction");
-                Self::process_swap_cook_amm(program_id, accounts, args)
+                // Deprecated: the launch state fields in PlaceOrderArgs are ignored,
+                // the swap reads them from the launch account like SwapCookAMMV2
+                msg!("⚠️ SwapCookAMM is deprecated, launch state args are ignored - use SwapCookAMMV2");
+                Self::process_swap_cook_amm(program_id, accounts, args.into())
             },
-            LaunchInstruction::GetMMRewardTokens { args: _ } => {
+            LaunchInstruction::GetMMRewardTokens { args } => {
                 msg!("GetMMRewardTokens instruction");
-                // TODO: Implement get MM rewards logic
-                Ok(())
+                crate::trade_to_earn::get_mm_rewards(program_id, accounts, args)
             },
             LaunchInstruction::CloseAccount => {
                 msg!("CloseAccount instruction");
-                // TODO: Implement close account logic
-                Ok(())
+                common::close_account(program_id, accounts)
             },
             LaunchInstruction::LaunchCollection { args: _ } => {
                 msg!("LaunchCollection instruction - NFT functionality disabled (using Token-2022 only)");
@@ -296,15 +327,79 @@ impl Processor {
                 msg!("UpdateRaffleImages instruction");
                 Self::process_update_raffle_images(program_id, accounts, args)
             },
+            LaunchInstruction::SwapCookAMMV2 { args } => {
+                msg!("SwapCookAMMV2 instruction");
+                Self::process_swap_cook_amm(program_id, accounts, args)
+            },
             LaunchInstruction::BestPriceSwap { args } => {
                 msg!("BestPriceSwap instruction");
                 Self::process_best_price_swap(program_id, accounts, args)
             },
+            LaunchInstruction::GetTWAP { args } => {
+                msg!("GetTWAP instruction");
+                Self::process_get_twap(program_id, accounts, args)
+            },
+            LaunchInstruction::MigrateLaunch => {
+                msg!("MigrateLaunch instruction");
+                migrate_launch::migrate_launch(program_id, accounts)
+            },
+            LaunchInstruction::CommitRandomness { args } => {
+                msg!("CommitRandomness instruction");
+                randomness::commit_randomness(program_id, accounts, args)
+            },
+            LaunchInstruction::RevealRandomness { args } => {
+                msg!("RevealRandomness instruction");
+                randomness::reveal_randomness(program_id, accounts, args)
+            },
+            LaunchInstruction::SettleRaffle => {
+                msg!("SettleRaffle instruction");
+                settle_raffle::settle_raffle(program_id, accounts)
+            },
+            LaunchInstruction::FailLaunch => {
+                msg!("FailLaunch instruction");
+                fail_launch::fail_launch(program_id, accounts)
+            },
+            LaunchInstruction::SettleIDO => {
+                msg!("SettleIDO instruction");
+                ido::settle_ido(program_id, accounts)
+            },
+            LaunchInstruction::GraduateLaunch => {
+                msg!("GraduateLaunch instruction");
+                graduate_launch::graduate_launch(program_id, accounts)
+            },
+            LaunchInstruction::LockLiquidity { args } => {
+                msg!("LockLiquidity instruction");
+                liquidity_lock::lock_liquidity(program_id, accounts, args)
+            },
+            LaunchInstruction::UnlockLiquidity => {
+                msg!("UnlockLiquidity instruction");
+                liquidity_lock::unlock_liquidity(program_id, accounts)
+            },
+            LaunchInstruction::UpdateFeeConfig { args } => {
+                msg!("UpdateFeeConfig instruction");
+                common::update_fee_config(program_id, accounts, args)
+            },
+            LaunchInstruction::ClaimCreatorFees => {
+                msg!("ClaimCreatorFees instruction");
+                creator_fees::claim_creator_fees(program_id, accounts)
+            },
+            LaunchInstruction::ProposeAdmin { args } => {
+                msg!("ProposeAdmin instruction");
+                common::propose_admin(program_id, accounts, args)
+            },
+            LaunchInstruction::AcceptAdmin => {
+                msg!("AcceptAdmin instruction");
+                common::accept_admin(program_id, accounts)
+            },
+            LaunchInstruction::SetPause { args } => {
+                msg!("SetPause instruction");
+                common::set_pause(program_id, accounts, args)
+            },
         }
     }
 
 
-    fn process_create_launch(_program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::CreateArgs) -> ProgramResult {
+    fn process_create_launch(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::CreateArgs) -> ProgramResult {
         msg!("Processing CreateLaunch instruction");
         msg!("Launch name: {}", args.name);
         msg!("Launch symbol: {}", args.symbol);
@@ -316,33 +411,57 @@ impl Processor {
         let user = &accounts[0]; // user account
         let launch_data = &accounts[2]; // launchData account
         let base_token_mint = &accounts[7]; // baseTokenMint account
+        let plugins = Self::create_launch_plugins(program_id, accounts, args.whitelist_tokens, args.whitelist_end, args.allowlist, args.creator_fee_bps)?;
+        
+        // an IDO raises whatever contributions come in between its soft and hard caps
+        if args.launch_type == 2 && args.hard_cap != 0 && args.hard_cap < args.soft_cap {
+            msg!("❌ Error: IDO hard cap {} is below its soft cap {}", args.hard_cap, args.soft_cap);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        
+        // a dutch auction's ticket_price is its starting price, which falls to the floor
+        if args.launch_type == 3 && (args.auction_floor_price == 0 || args.auction_floor_price > args.ticket_price || args.num_mints == 0) {
+            msg!("❌ Error: Dutch auction needs tickets and a floor price between 1 and its {} lamport start price", args.ticket_price);
+            return Err(ProgramError::InvalidInstructionData);
+        }
         
+        let mut keys = vec![Pubkey::default(); crate::launch::LaunchKeys::LENGTH as usize];
+        keys[crate::launch::LaunchKeys::Seller as usize] = *user.key;
+        keys[crate::launch::LaunchKeys::TeamWallet as usize] = *user.key;
+
         // Create launch data structure
-        let launch_data_struct = crate::state::LaunchData {
+        let launch_data_struct = crate::launch::LaunchData {
             account_type: crate::state::AccountType::Launch,
+            version: crate::launch::LAUNCH_DATA_VERSION,
             launch_meta: match args.launch_type {
-                0 => crate::state::LaunchMeta::Raffle,
-                1 => crate::state::LaunchMeta::FCFS,
-                2 => crate::state::LaunchMeta::IDO { token_fraction_distributed: 0, tokens_distributed: 0 },
-                _ => crate::state::LaunchMeta::Raffle, // Default to raffle
+                0 => crate::launch::LaunchMeta::Raffle(crate::launch::Raffle {}),
+                1 => crate::launch::LaunchMeta::FCFS(crate::launch::FCFS {}),
+                2 => crate::launch::LaunchMeta::IDO(crate::launch::IDO { token_fraction_distributed: 0.0, tokens_distributed: 0 }),
+                3 => crate::launch::LaunchMeta::DutchAuction(crate::launch::DutchAuction {
+                    start_price: args.ticket_price,
+                    floor_price: args.auction_floor_price,
+                    half_life: args.auction_half_life,
+                    clearing_price: 0,
+                }),
+                _ => crate::launch::LaunchMeta::Raffle(crate::launch::Raffle {}), // Default to raffle
             },
-            plugins: vec![],
+            plugins,
             last_interaction: 0,
             num_interactions: 0,
             page_name: args.page_name.clone(),
-            listing: accounts[1].key.to_string(), // listing account
+            listing: *accounts[1].key, // listing account
             total_supply: args.total_supply,
             num_mints: args.num_mints,
             ticket_price: args.ticket_price,
-            minimum_liquidity: 0,
+            minimum_liquidity: args.soft_cap,
             launch_date: args.launch_date,
             end_date: args.close_date,
             tickets_sold: 0,
             ticket_claimed: 0,
             mints_won: 0,
             buffer1: args.amm_provider as u64,
-            buffer2: 0,
-            buffer3: 0,
+            buffer2: if args.launch_type == 2 { args.hard_cap } else { 0 }, // IDO hard cap
+            buffer3: 0, // lamports an IDO has raised
             distribution: vec![],
             flags: vec![args.launch_type],
             strings: vec![
@@ -356,12 +475,11 @@ impl Processor {
                     0 => "raffle".to_string(),
                     1 => "instant".to_string(),
                     2 => "ido".to_string(),
+                    3 => "dutch".to_string(),
                     _ => "raffle".to_string(),
                 },
             ],
-            keys: vec![
-                base_token_mint.key.to_string(), // Store baseTokenMint in keys array
-            ],
+            keys,
             creator: *user.key,
             upvotes: 0,
             downvotes: 0,
@@ -371,22 +489,26 @@ impl Processor {
             graduation_threshold: 30_000_000_000u64, // 30 SOL threshold for Raydium liquidity creation
         };
         
-        msg!("✅ Stored baseTokenMint in keys array: {}", base_token_mint.key.to_string());
+        msg!("✅ Launch for baseTokenMint: {}", base_token_mint.key.to_string());
 
         // Serialize and write to account
         let serialized_data = borsh::to_vec(&launch_data_struct)?;
+        if serialized_data.len() > launch_data.data_len() {
+            msg!("❌ Error: Launch data needs {} bytes, account has {}", serialized_data.len(), launch_data.data_len());
+            return Err(ProgramError::AccountDataTooSmall);
+        }
         launch_data.try_borrow_mut_data()?[..serialized_data.len()].copy_from_slice(&serialized_data);
         
         msg!("Launch data written successfully");
         Ok(())
     }
 
-    fn process_buy_tickets(_program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::JoinArgs) -> ProgramResult {
+    fn process_buy_tickets(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::JoinArgs) -> ProgramResult {
         msg!("🎫 Processing BuyTickets instruction");
         msg!("Amount: {}", args.amount);
         
-        if accounts.len() < 6 {
-            msg!("❌ Error: Not enough account keys provided. Expected: 6, Got: {}", accounts.len());
+        if accounts.len() < 7 {
+            msg!("❌ Error: Not enough account keys provided. Expected: 7, Got: {}", accounts.len());
             return Err(ProgramError::NotEnoughAccountKeys);
         }
         
@@ -396,183 +518,79 @@ impl Processor {
         let ledger_wallet = &accounts[3];
         let system_program = &accounts[4];
         let join_data = &accounts[5]; // New: JoinData account for tracking user purchases
+        let cook_data = &accounts[6]; // program data holding the fee schedule
         
         if !user.is_signer {
             msg!("❌ Error: User must be a signer");
             return Err(ProgramError::MissingRequiredSignature);
         }
         
-        // Check if user already has tickets (JoinData exists with tickets)
-        let join_data_exists = **join_data.try_borrow_lamports()? > 0;
-        if join_data_exists {
-            let join_data_result = crate::launch::state::JoinData::try_from_slice(&join_data.try_borrow_data()?);
-            match join_data_result {
-                Ok(join_data_struct) => {
-                    if join_data_struct.num_tickets > 0 {
-                        msg!("❌ Error: User already purchased tickets");
-                        msg!("📋 Order ID: {}", join_data_struct.order_id);
-                        return Err(ProgramError::InvalidAccountData);
-                    }
-                }
-                Err(_) => {
-                    msg!("⚠️ JoinData exists but failed to parse");
-                }
-            }
-        }
-        
-        let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        
-        msg!("📊 Account data length: {}", launch_data_bytes.len());
-        msg!("📊 First 20 bytes: {:?}", &launch_data_bytes[..std::cmp::min(20, launch_data_bytes.len())]);
-        
-        // Instead of deserializing the entire struct, manually parse the essential fields
-        // Based on the data structure we can see in the first 20 bytes
-        
-        if launch_data_bytes.len() < 100 {
-            msg!("❌ Account data too short: {} bytes", launch_data_bytes.len());
-            return Err(ProgramError::InvalidAccountData);
-        }
+        common::check_not_paused(program_id, Some(cook_data))?;
+        common::check_launch_not_paused(launch_data)?;
         
-        // Since the struct layout doesn't match, let's try a different approach
-        // Look for known patterns in the data instead of trying to parse the full struct
-        
-        msg!("🔍 Trying pattern-based parsing instead of struct parsing");
-        
-        // Let's search for reasonable values that could be our fields
-        let mut ticket_price: u64 = 0;
-        let mut num_mints: u32 = 0;
-        let mut tickets_sold: u32 = 0;
-        let mut end_date: u64 = 0;
-        
-        // Search through the data for reasonable values
-        // Look for ticket_price in common locations where it might be stored
-        let mut found_ticket_price = false;
-        
-        // Try different offsets where ticket_price might be stored
-        let potential_offsets = vec![40, 48, 56, 64, 72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152];
-        
-        for offset in potential_offsets {
-            if offset + 8 <= launch_data_bytes.len() {
-                let candidate_price = u64::from_le_bytes([
-                    launch_data_bytes[offset],
-                    launch_data_bytes[offset + 1],
-                    launch_data_bytes[offset + 2],
-                    launch_data_bytes[offset + 3],
-                    launch_data_bytes[offset + 4],
-                    launch_data_bytes[offset + 5],
-                    launch_data_bytes[offset + 6],
-                    launch_data_bytes[offset + 7],
-                ]);
-                
-                // Look for reasonable ticket prices (between 0.001 and 1 SOL)
-                if candidate_price >= 1_000_000 && candidate_price <= 1_000_000_000 {
-                    ticket_price = candidate_price;
-                    msg!("📊 Found potential ticket_price: {} at offset {}", ticket_price, offset);
-                    found_ticket_price = true;
-                    break;
-                }
-            }
+        // IDOs take contributions rather than tickets, and dutch auctions price tickets as they go
+        let launch_type = {
+            let launch_data_bytes = launch_data.try_borrow_data()?;
+            crate::launch::LaunchAccessor::load(&launch_data_bytes)?.launch_meta_type(&launch_data_bytes)
+        };
+        match launch_type {
+            crate::launch::LaunchMetaType::IDO => return ido::contribute_ido(program_id, accounts, &args),
+            crate::launch::LaunchMetaType::DutchAuction => return dutch_auction::bid_dutch_auction(program_id, accounts, &args),
+            _ => {}
         }
         
-        // If we didn't find a reasonable ticket price, search through all data
-        if !found_ticket_price {
-            for i in 0..(launch_data_bytes.len() - 8) {
-                let candidate_price = u64::from_le_bytes([
-                    launch_data_bytes[i],
-                    launch_data_bytes[i + 1],
-                    launch_data_bytes[i + 2],
-                    launch_data_bytes[i + 3],
-                    launch_data_bytes[i + 4],
-                    launch_data_bytes[i + 5],
-                    launch_data_bytes[i + 6],
-                    launch_data_bytes[i + 7],
-                ]);
-                
-                // Look for reasonable ticket prices (between 0.001 and 1 SOL)
-                if candidate_price >= 1_000_000 && candidate_price <= 1_000_000_000 {
-                    ticket_price = candidate_price;
-                    msg!("📊 Found potential ticket_price: {} at offset {}", ticket_price, i);
-                    found_ticket_price = true;
-                    break;
-                }
-            }
-        }
+        let fee_config = accounts::load_fee_config(program_id, cook_data)?;
+        accounts::check_fee_recipient(&fee_config, ledger_wallet)?;
         
-        // Search for num_mints (should be a reasonable number like 1000, 10000, etc.)
-        for i in 0..(launch_data_bytes.len() - 4) {
-            let candidate_mints = u32::from_le_bytes([
-                launch_data_bytes[i],
-                launch_data_bytes[i + 1],
-                launch_data_bytes[i + 2],
-                launch_data_bytes[i + 3],
-            ]);
-            
-            // Look for reasonable mint counts (between 100 and 1,000,000)
-            if candidate_mints >= 100 && candidate_mints <= 1_000_000 {
-                num_mints = candidate_mints;
-                msg!("📊 Found potential num_mints: {} at offset {}", num_mints, i);
-                break;
-            }
-        }
+        let page_name = {
+            let launch_data_bytes = launch_data.try_borrow_data()?;
+            crate::launch::LaunchAccessor::load(&launch_data_bytes)?.page_name(&launch_data_bytes).to_string()
+        };
         
-        // Search for end_date (should be a reasonable timestamp)
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_date = u64::from_le_bytes([
-                launch_data_bytes[i],
-                launch_data_bytes[i + 1],
-                launch_data_bytes[i + 2],
-                launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4],
-                launch_data_bytes[i + 5],
-                launch_data_bytes[i + 6],
-                launch_data_bytes[i + 7],
-            ]);
-            
-            // Look for reasonable timestamps (between 2020 and 2030)
-            if candidate_date >= 1577836800 && candidate_date <= 1893456000 {
-                end_date = candidate_date;
-                msg!("📊 Found potential end_date: {} at offset {}", end_date, i);
-                break;
-            }
+        // Check if user already has tickets, creating their JoinData PDA on their first purchase
+        let existing_join_data = crate::launch::load_or_create_join_data(program_id, user, join_data, &page_name)?;
+        if existing_join_data.num_tickets > 0 {
+            msg!("❌ Error: User already purchased tickets");
+            msg!("📋 Order ID: {}", existing_join_data.order_id);
+            return Err(ProgramError::InvalidAccountData);
         }
+        let mut allowlist_spent = existing_join_data.allowlist_spent;
         
-        // Use default values only if we couldn't find them
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+
+        let ticket_price = launch_layout.ticket_price(&launch_data_bytes);
+        let num_mints = launch_layout.num_mints(&launch_data_bytes);
+        let tickets_sold = launch_layout.tickets_sold(&launch_data_bytes);
+        let launch_date = launch_layout.launch_date(&launch_data_bytes);
+        let end_date = launch_layout.end_date(&launch_data_bytes);
+
+        msg!("📊 Launch data ({:?} layout):", launch_layout.layout);
+        msg!("  - ticket_price: {}", ticket_price);
+        msg!("  - num_mints: {}", num_mints);
+        msg!("  - tickets_sold: {}", tickets_sold);
+        msg!("  - end_date: {}", end_date);
+
         if ticket_price == 0 {
-            ticket_price = 100_000_000; // Default to 0.1 SOL
-            msg!("⚠️ Could not find ticket_price in account data, using default: {}", ticket_price);
-        } else {
-            msg!("✅ Using creator-submitted ticket_price: {} lamports ({} SOL)", ticket_price, ticket_price as f64 / 1_000_000_000.0);
-        }
-        
-        if num_mints == 0 {
-            num_mints = 1000; // Default to 1000 mints
-            msg!("⚠️ Using default num_mints: {}", num_mints);
+            msg!("❌ Error: Launch has no ticket price");
+            return Err(ProgramError::InvalidAccountData);
         }
-        
-        if end_date == 0 {
-            end_date = 2000000000; // Default to far future
-            msg!("⚠️ Using default end_date: {}", end_date);
+
+        if launch_layout.is_failed(&launch_data_bytes) {
+            msg!("❌ Error: Launch has failed");
+            return Err(ProgramError::InvalidAccountData);
         }
-        
-        // For testing purposes, if the raffle has ended, extend it to the future
+
         let current_time = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+        if current_time < launch_date {
+            msg!("❌ Error: Raffle has not started yet (launch_date: {}, current: {})", launch_date, current_time);
+            return Err(ProgramError::InvalidAccountData);
+        }
         if current_time > end_date {
-            msg!("⚠️ Raffle has ended, extending for testing purposes");
-            end_date = current_time + 86400; // Extend by 24 hours
-            msg!("📊 New end_date: {}", end_date);
+            msg!("❌ Error: Raffle has ended (end_date: {}, current: {})", end_date, current_time);
+            return Err(ProgramError::InvalidAccountData);
         }
         
-        // tickets_sold starts at 0 for new raffles
-        tickets_sold = 0;
-        
-        msg!("✅ Using pattern-based values:");
-        msg!("  - ticket_price: {}", ticket_price);
-        msg!("  - num_mints: {}", num_mints);
-        msg!("  - tickets_sold: {}", tickets_sold);
-        msg!("  - end_date: {}", end_date);
-        
-        // Time check already handled above
-        
         let num_tickets = (args.amount / ticket_price) as u32;
         
         if num_tickets == 0 {
@@ -586,9 +604,30 @@ impl Processor {
             return Err(ProgramError::InvalidInstructionData);
         }
         
-        // Calculate platform fee (0.5% of ticket purchase)
-        let fee_rate = 50; // 0.5% fee (50 basis points)
-        let fee_amount = (args.amount * fee_rate) / 10000;
+        let plugins = launch_layout.plugins(&launch_data_bytes);
+        
+        // WHITELIST PHASE: burn the whitelist tokens for these tickets, accounts 7-9 are
+        // whitelist_mint, whitelist_token_program and whitelist_account
+        if let Some(whitelist) = crate::launch::get_whitelist(&plugins) {
+            crate::launch::burn_whitelist_tokens(
+                &whitelist,
+                current_time,
+                num_tickets as u64,
+                user,
+                accounts.get(7),
+                accounts.get(8),
+                accounts.get(9),
+            )?;
+        }
+        
+        // ALLOWLIST PHASE: only wallets proven to be in the merkle allowlist may buy, up to their cap
+        if let Some(allowlist) = crate::launch::get_merkle_allowlist(&plugins) {
+            let spend = num_tickets as u64 * ticket_price;
+            allowlist_spent = crate::launch::check_allowlist(&allowlist, current_time, user.key, args.allowlist.as_ref(), allowlist_spent, spend)?;
+        }
+        
+        // Calculate platform fee from the fee schedule
+        let fee_amount = crate::state::get_fee(args.amount, fee_config.ticket_fee_bps);
         let net_amount = args.amount - fee_amount;
         
         msg!("💰 Fee calculation:");
@@ -617,14 +656,18 @@ impl Processor {
             msg!("✅ Platform fee transferred to ledger wallet");
         }
         
-        // Drop the mutable borrow of launch_data before doing the transfer
+        // Drop the borrow of launch_data before doing the transfer
         drop(launch_data_bytes);
         
-        // Transfer remaining amount to raffle contract
+        // The crank fee is held by the launch until SettleRaffle pays it to whoever settles these tickets
+        let crank_fee = crate::state::RAFFLE_CRANK_FEE * num_tickets as u64;
+        msg!("  Crank fee: {} lamports", crank_fee);
+        
+        // Transfer remaining amount and the crank fee to raffle contract
         let transfer_instruction = system_instruction::transfer(
             user_sol_account.key,
             launch_data.key,
-            net_amount,
+            net_amount + crank_fee,
         );
         
         invoke_signed(
@@ -637,81 +680,78 @@ impl Processor {
             &[],
         )?;
         
-        // Properly deserialize, update, and re-serialize the LaunchData struct
+        // Update tickets_sold in place
         let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        
-        // Deserialize the LaunchData struct
-        let mut launch_data_struct: crate::state::LaunchData = match borsh::BorshDeserialize::try_from_slice(&launch_data_bytes) {
-            Ok(data) => data,
-            Err(e) => {
-                msg!("❌ Failed to deserialize LaunchData: {:?}", e);
-                return Err(ProgramError::InvalidAccountData);
-            }
-        };
-        
-        // Update tickets_sold properly
         let new_tickets_sold = tickets_sold + num_tickets;
-        launch_data_struct.tickets_sold = new_tickets_sold;
-        
-        // Serialize the updated struct back to bytes
-        let serialized_data = match borsh::to_vec(&launch_data_struct) {
-            Ok(data) => data,
-            Err(e) => {
-                msg!("❌ Failed to serialize LaunchData: {:?}", e);
-                return Err(ProgramError::InvalidAccountData);
-            }
-        };
-        
-        // Write the serialized data back to the account
-        launch_data_bytes[..serialized_data.len()].copy_from_slice(&serialized_data);
-        
+        launch_layout.set_tickets_sold(&mut launch_data_bytes, new_tickets_sold);
+        let num_interactions = launch_layout.num_interactions(&launch_data_bytes);
+        launch_layout.set_num_interactions(&mut launch_data_bytes, num_interactions.saturating_add(1));
+        launch_layout.set_last_interaction(&mut launch_data_bytes, current_time as i64);
+        drop(launch_data_bytes);
+
         msg!("✅ Updated tickets_sold from {} to {}", tickets_sold, new_tickets_sold);
         
-        // Create or update JoinData account to track user purchase
-        // Note: The frontend will update order_id with the transaction signature after successful purchase
-        if !join_data_exists {
-            // Calculate rent for JoinData account
-            let join_data_size = std::mem::size_of::<crate::launch::state::JoinData>();
-            let rent = solana_program::rent::Rent::get()?.minimum_balance(join_data_size);
-            
-            // Create the account
-            **join_data.try_borrow_mut_lamports()? = rent;
-            **user.try_borrow_mut_lamports()? -= rent;
-            
-            msg!("📝 Created JoinData account");
+        // The program places this join's Orao request itself, from a seed with the purchase slot in it, so
+        // it cannot be a request the buyer placed and saw fulfilled beforehand. Accounts 10-13 are
+        // orao_program, orao_network, orao_treasury and orao_random, networks without Orao use commit-reveal
+        let purchase_slot = solana_program::clock::Clock::get()?.slot;
+        let request_seed = crate::orao::get_join_request_seed(user.key, launch_data.key, purchase_slot);
+        if crate::state::NETWORK != crate::state::Network::Eclipse {
+            if accounts.len() < 14 {
+                msg!("❌ Error: Not enough account keys provided. Expected: 14, Got: {}", accounts.len());
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            crate::orao::request_randomness(user, &accounts[11], &accounts[12], &accounts[13], system_program, &accounts[10], request_seed)?;
         }
         
-        // Create JoinData struct to store purchase info
+        // Update JoinData to track user purchase
+        // Note: The frontend will update order_id with the transaction signature after successful purchase
         let join_data_struct = crate::launch::state::JoinData {
             account_type: crate::state::AccountType::Join,
             joiner_key: *user.key,
-            page_name: launch_data_struct.page_name.clone(),
+            page_name,
             num_tickets: num_tickets as u16,
             num_tickets_checked: 0,
             num_winning_tickets: 0,
             ticket_status: crate::launch::state::TicketStatus::Available,
-            random_address: solana_program::system_program::id(), // Placeholder, will be set during check
-            last_slot: solana_program::clock::Clock::get()?.slot,
+            random_address: crate::orao::get_request_address(&request_seed), // Orao request CheckTickets reads the raffle randomness from
+            last_slot: purchase_slot,
             order_id: "pending".to_string(), // Frontend will update this with transaction signature
+            first_ticket: Some(tickets_sold), // this join holds raffle tickets tickets_sold..new_tickets_sold
+            allowlist_spent,
+            ido_contribution: 0,
+            auction_paid: 0,
+            ticket_fee_bps: fee_config.ticket_fee_bps, // kept back from the refund if the launch fails
         };
         
-        // Serialize and write JoinData
-        let join_data_bytes = borsh::to_vec(&join_data_struct)?;
-        join_data.try_borrow_mut_data()?[..join_data_bytes.len()].copy_from_slice(&join_data_bytes);
+        // Serialize and write JoinData, growing accounts created before first_ticket or by an allowlisted swap
+        crate::launch::resize_join_data(user, join_data, system_program, join_data_struct.size()?)?;
+        join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
         
         msg!("✅ Successfully bought {} tickets for {} SOL", num_tickets, args.amount);
         msg!("📋 JoinData stored - User has {} tickets", num_tickets);
         Ok(())
     }
 
-    fn process_claim_tokens(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    fn process_claim_tokens<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
         msg!("🎁 Processing ClaimTokens instruction");
         
-        if accounts.len() < 7 {
-            msg!("❌ Error: Not enough account keys provided. Expected: 7, Got: {}", accounts.len());
+        if accounts.len() < 8 {
+            msg!("❌ Error: Not enough account keys provided. Expected: 8, Got: {}", accounts.len());
             return Err(ProgramError::NotEnoughAccountKeys);
         }
         
+        // IDO contributors claim a pro-rata share, which also takes listing, cook_pda, cook_base_token
+        // and the associated token program at 7-10 and the program data at 11
+        let is_ido = {
+            let launch_data_bytes = accounts[1].try_borrow_data()?;
+            let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+            launch_layout.launch_meta_type(&launch_data_bytes) == crate::launch::LaunchMetaType::IDO
+        };
+        if is_ido {
+            return ido::claim_ido_tokens(program_id, accounts);
+        }
+        
         let user = &accounts[0];
         let launch_data = &accounts[1];
         let user_token_account = &accounts[2];
@@ -719,15 +759,19 @@ impl Processor {
         let token_program = &accounts[4];
         let system_program = &accounts[5];
         let join_data = &accounts[6]; // New: JoinData account
+        let cook_data = &accounts[7];
         
         if !user.is_signer {
             msg!("❌ Error: User must be a signer");
             return Err(ProgramError::MissingRequiredSignature);
         }
         
+        common::check_not_paused(program_id, Some(cook_data))?;
+        common::check_launch_not_paused(launch_data)?;
+        
         // Parse JoinData to check if user is a winner
         let join_data_bytes = join_data.try_borrow_data()?;
-        let join_data_struct: crate::launch::state::JoinData = match crate::launch::state::JoinData::try_from_slice(&join_data_bytes) {
+        let join_data_struct: crate::launch::state::JoinData = match crate::launch::state::JoinData::load(&join_data_bytes) {
             Ok(data) => data,
             Err(_) => {
                 msg!("❌ Error: JoinData not found");
@@ -755,79 +799,44 @@ impl Processor {
         
         msg!("✅ User {} has {} winning tickets", user.key, join_data_struct.num_winning_tickets);
         
-        let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        
-        // Parse raffle data using pattern matching
-        let mut ticket_price: u64 = 0;
-        let mut num_mints: u32 = 0;
-        let mut tickets_sold: u32 = 0;
-        let mut end_date: u64 = 0;
-        let mut total_supply: u64 = 0;
-        
-        // Find ticket_price
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_price = u64::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4], launch_data_bytes[i + 5], launch_data_bytes[i + 6], launch_data_bytes[i + 7],
-            ]);
-            if candidate_price >= 1_000_000 && candidate_price <= 1_000_000_000 {
-                ticket_price = candidate_price;
-                break;
-            }
-        }
-        
-        // Find num_mints
-        for i in 0..(launch_data_bytes.len() - 4) {
-            let candidate_mints = u32::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-            ]);
-            if candidate_mints >= 100 && candidate_mints <= 1_000_000 {
-                num_mints = candidate_mints;
-                break;
-            }
-        }
-        
-        // Find end_date
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_date = u64::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4], launch_data_bytes[i + 5], launch_data_bytes[i + 6], launch_data_bytes[i + 7],
-            ]);
-            if candidate_date >= 1577836800 && candidate_date <= 1893456000 {
-                end_date = candidate_date;
-                break;
-            }
-        }
-        
-        // Find total_supply
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_supply = u64::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4], launch_data_bytes[i + 5], launch_data_bytes[i + 6], launch_data_bytes[i + 7],
-            ]);
-            if candidate_supply >= 1_000_000 && candidate_supply <= 1_000_000_000_000 {
-                total_supply = candidate_supply;
-                break;
-            }
-        }
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+
+        let ticket_price = launch_layout.ticket_price(&launch_data_bytes);
+        let num_mints = launch_layout.num_mints(&launch_data_bytes);
+        let tickets_sold = launch_layout.tickets_sold(&launch_data_bytes);
+        let end_date = launch_layout.end_date(&launch_data_bytes);
+        let total_supply = launch_layout.total_supply(&launch_data_bytes);
+        let minimum_liquidity = launch_layout.minimum_liquidity(&launch_data_bytes);
+        let dex_provider = launch_layout.buffer1(&launch_data_bytes);
+        let was_first_claim = !launch_layout.is_tradable(&launch_data_bytes);
+        let launch_failed = launch_layout.is_failed(&launch_data_bytes);
+        let current_time = solana_program::clock::Clock::get()?.unix_timestamp as u64;
         
-        // Find tickets_sold
-        for i in 0..(launch_data_bytes.len() - 4) {
-            let candidate_sold = u32::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-            ]);
-            if candidate_sold <= num_mints {
-                tickets_sold = candidate_sold;
-                break;
-            }
+        // every dutch auction ticket goes for the clearing price, and the auction ends early once it sells out
+        let auction = crate::launch::get_auction_clearing(&launch_data_bytes, current_time)?;
+        let (ticket_price, has_ended) = match auction {
+            Some((clearing_price, cleared)) => (clearing_price, cleared),
+            None => (ticket_price, current_time >= end_date),
+        };
+        drop(launch_data_bytes);
+
+        // a launch that missed its minimum liquidity can only be failed and refunded
+        if launch_failed || (tickets_sold as u64 * ticket_price) < minimum_liquidity {
+            msg!("❌ Error: Launch failed, claim a refund instead");
+            return Err(ProgramError::InvalidAccountData);
         }
-        
+
         msg!("📊 Raffle data: ticket_price={}, num_mints={}, tickets_sold={}, end_date={}, total_supply={}", 
              ticket_price, num_mints, tickets_sold, end_date, total_supply);
         
+        if num_mints == 0 {
+            msg!("❌ Error: Launch has no mints");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
         // Check if raffle has ended
-        let current_time = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-        if current_time < end_date {
+        if !has_ended {
             msg!("❌ Error: Raffle has not ended yet");
             return Err(ProgramError::InvalidInstructionData);
         }
@@ -860,115 +869,119 @@ impl Processor {
             &[],
         )?;
         
+        if auction.is_some() {
+            dutch_auction::refund_auction_overpayment(program_id, user, launch_data, join_data, was_first_claim)?;
+        }
+        
         // 🚀 INSTANT LIQUIDITY CREATION ON FIRST CLAIM
         // Enable trading on first successful claim
-        drop(launch_data_bytes);
-        let mut launch_data_bytes_final = launch_data.try_borrow_mut_data()?;
-        if let Ok(mut launch_data_struct) = crate::state::LaunchData::try_from_slice(&launch_data_bytes_final) {
-            let was_first_claim = !launch_data_struct.is_tradable;
+        let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
+        if was_first_claim {
+            msg!("🚀 First claim detected! Checking if pool should be created...");
             
-            if was_first_claim {
-                msg!("🚀 First claim detected! Checking if pool should be created...");
+            // Calculate total SOL collected
+            let total_sol_collected = tickets_sold as u64 * ticket_price;
+            
+            // Check if liquidity threshold is met
+            let threshold_met = minimum_liquidity == 0 || total_sol_collected >= minimum_liquidity;
+            
+            if threshold_met {
+                msg!("✅ Liquidity threshold met! Creating liquidity pool...");
                 
-                // Calculate total SOL collected
-                let total_sol_collected = tickets_sold as u64 * ticket_price;
+                // DEX provider is stored in buffer1: 0 = Cook, 1 = Raydium
+                msg!("📊 Creating pool on: {}", if dex_provider == 0 { "Cook DEX" } else { "Raydium" });
                 
-                // Check if liquidity threshold is met
-                let minimum_liquidity = launch_data_struct.minimum_liquidity;
-                let threshold_met = minimum_liquidity == 0 || total_sol_collected >= minimum_liquidity;
+                // Calculate liquidity amounts (50% of SOL collected for liquidity)
+                let liquidity_sol_amount = total_sol_collected / 2;
+                let liquidity_token_amount = total_supply / 2;
                 
-                if threshold_met {
-                    msg!("✅ Liquidity threshold met! Creating liquidity pool...");
-                    
-                    // Get DEX provider from launch data (buffer1: 0 = Cook, 1 = Raydium)
-                    let dex_provider = launch_data_struct.buffer1;
-                    msg!("📊 Creating pool on: {}", if dex_provider == 0 { "Cook DEX" } else { "Raydium" });
-                    
-                    // Calculate liquidity amounts (50% of SOL collected for liquidity)
-                    let liquidity_sol_amount = total_sol_collected / 2;
-                    let liquidity_token_amount = launch_data_struct.total_supply / 2;
-                    
-                    // Create pool on selected DEX
-                    // Note: This requires additional accounts to be passed for pool creation
-                    // For now, we'll set the flags and emit events
-                    // Actual pool creation would happen via CPI to DEX programs
-                    
-                    // Update LP state to "set up" (state 2)
-                    use crate::launch::LaunchFlags;
-                    if launch_data_struct.flags.len() > LaunchFlags::LPState as usize {
-                        launch_data_struct.flags[LaunchFlags::LPState as usize] = 2; // LPState = 2 means "set up"
-                    }
-                    
-                    // Store pool creation info (would store pool address if available)
-                    // For now, we'll use the AMM PDA as the pool address
-                    
-                    // Emit pool creation event
-                    let base_token_mint_key = accounts[7].key; // baseTokenMint account
-                    // Derive AMM PDA (this would be the pool address for Cook DEX)
-                    // For Raydium, we'd get the actual pool address from the CPI
-                    
-                    // Emit event (pool address would be actual pool address in production)
-                    msg!(
-                        "EVENT:POOL_CREATED:token_mint:{}:dex_provider:{}:sol_amount:{}:token_amount:{}",
-                        base_token_mint_key,
-                        dex_provider,
-                        liquidity_sol_amount,
-                        liquidity_token_amount
-                    );
-                    
-                    // Emit trading started event
-                    msg!(
-                        "EVENT:TRADING_STARTED:token_mint:{}:dex_provider:{}",
-                        base_token_mint_key,
-                        dex_provider
-                    );
-                    
-                    msg!("✅ Liquidity pool created! Token is now tradeable on DEX!");
-                    msg!("💰 Pool liquidity: {} SOL, {} tokens", liquidity_sol_amount, liquidity_token_amount);
-                } else {
-                    msg!("⚠️ Liquidity threshold not met yet: {} < {}", total_sol_collected, minimum_liquidity);
-                    msg!("💡 Pool will be created when threshold is met.");
+                // Create pool on selected DEX
+                // Note: This requires additional accounts to be passed for pool creation
+                // For now, we'll set the flags and emit events
+                // Actual pool creation would happen via CPI to DEX programs
+                
+                // Update LP state to "set up" (state 2), if the launch tracks it
+                if launch_layout.flag(&launch_data_bytes, crate::launch::LaunchFlags::LPState as usize).is_some() {
+                    launch_layout.set_flag(&mut launch_data_bytes, crate::launch::LaunchFlags::LPState as usize, 2)?;
                 }
+                
+                // Emit event (pool address would be actual pool address in production)
+                msg!(
+                    "EVENT:POOL_CREATED:token_mint:{}:dex_provider:{}:sol_amount:{}:token_amount:{}",
+                    token_mint.key,
+                    dex_provider,
+                    liquidity_sol_amount,
+                    liquidity_token_amount
+                );
+                
+                // Emit trading started event
+                msg!(
+                    "EVENT:TRADING_STARTED:token_mint:{}:dex_provider:{}",
+                    token_mint.key,
+                    dex_provider
+                );
+                
+                msg!("✅ Liquidity pool created! Token is now tradeable on DEX!");
+                msg!("💰 Pool liquidity: {} SOL, {} tokens", liquidity_sol_amount, liquidity_token_amount);
+            } else {
+                msg!("⚠️ Liquidity threshold not met yet: {} < {}", total_sol_collected, minimum_liquidity);
+                msg!("💡 Pool will be created when threshold is met.");
             }
-            
-            // Update launch data to enable trading (raffle graduation)
-            launch_data_struct.is_tradable = true;
-            let serialized_data = borsh::to_vec(&launch_data_struct)?;
-            launch_data_bytes_final[..serialized_data.len()].copy_from_slice(&serialized_data);
-            msg!("✅ Trading gate opened - token is now tradable!");
         }
         
+        // Update launch data to enable trading (raffle graduation)
+        launch_layout.set_is_tradable(&mut launch_data_bytes, true);
+        msg!("✅ Trading gate opened - token is now tradable!");
+        
         msg!("✅ Successfully claimed {} tokens", tokens_to_mint);
         Ok(())
     }
 
-    fn process_claim_refund(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    fn process_claim_refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
         msg!("💰 Processing ClaimRefund instruction");
         
-        if accounts.len() < 4 {
-            msg!("❌ Error: Not enough account keys provided. Expected: 4, Got: {}", accounts.len());
+        if accounts.len() < 5 {
+            msg!("❌ Error: Not enough account keys provided. Expected: 5, Got: {}", accounts.len());
             return Err(ProgramError::NotEnoughAccountKeys);
         }
         
         let user = &accounts[0];
         let launch_data = &accounts[1];
-        let system_program = &accounts[2];
+        let _system_program = &accounts[2];
         let join_data = &accounts[3]; // New: JoinData account
+        let cook_data = &accounts[4];
         
         if !user.is_signer {
             msg!("❌ Error: User must be a signer");
             return Err(ProgramError::MissingRequiredSignature);
         }
         
-        // Parse JoinData to check if user is a loser
+        common::check_not_paused(program_id, Some(cook_data))?;
+        common::check_launch_not_paused(launch_data)?;
+        
+        // Once a launch has failed every ticket holder is refunded, winners included
+        let launch_failed = {
+            let launch_data_bytes = launch_data.try_borrow_data()?;
+            crate::launch::LaunchAccessor::load(&launch_data_bytes)?.is_failed(&launch_data_bytes)
+        };
+        if launch_failed {
+            return crate::launch::refund_failed_launch(program_id, user, launch_data, join_data);
+        }
+        
+        // Parse JoinData to find the user's losing tickets
         let join_data_bytes = join_data.try_borrow_data()?;
-        let join_data_struct: crate::launch::state::JoinData = match crate::launch::state::JoinData::try_from_slice(&join_data_bytes) {
+        let mut join_data_struct: crate::launch::state::JoinData = match crate::launch::state::JoinData::load(&join_data_bytes) {
             Ok(data) => data,
             Err(_) => {
                 msg!("❌ Error: JoinData not found");
                 return Err(ProgramError::InvalidAccountData);
             }
         };
+        drop(join_data_bytes);
+        
+        if launch_data.owner != program_id || join_data.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
         
         // Verify user owns this JoinData
         if join_data_struct.joiner_key != *user.key {
@@ -976,72 +989,39 @@ impl Processor {
             return Err(ProgramError::InvalidAccountData);
         }
         
-        // Check if user is a loser (no winning tickets)
-        if join_data_struct.num_winning_tickets > 0 {
-            msg!("❌ Error: User is a winner. Order ID: {}", join_data_struct.order_id);
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
         // Check if tickets have been checked
-        if join_data_struct.num_tickets_checked == 0 {
+        if join_data_struct.num_tickets_checked < join_data_struct.num_tickets {
             msg!("❌ Error: Tickets not checked yet. Please run CheckTickets first.");
             return Err(ProgramError::InvalidAccountData);
         }
         
-        msg!("😔 User {} is a loser with 0 winning tickets. Processing refund...", user.key);
-        
-        let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        
-        // Parse raffle data using pattern matching
-        let mut ticket_price: u64 = 0;
-        let mut num_mints: u32 = 0;
-        let mut tickets_sold: u32 = 0;
-        let mut end_date: u64 = 0;
-        
-        // Find ticket_price
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_price = u64::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4], launch_data_bytes[i + 5], launch_data_bytes[i + 6], launch_data_bytes[i + 7],
-            ]);
-            if candidate_price >= 1_000_000 && candidate_price <= 1_000_000_000 {
-                ticket_price = candidate_price;
-                break;
-            }
+        // Losing tickets can only be refunded once, winning tickets are claimed separately with ClaimTokens
+        if join_data_struct.ticket_status != crate::launch::state::TicketStatus::Available {
+            msg!("❌ Error: Losing tickets already refunded ({:?})", join_data_struct.ticket_status);
+            return Err(ProgramError::InvalidAccountData);
         }
         
-        // Find num_mints
-        for i in 0..(launch_data_bytes.len() - 4) {
-            let candidate_mints = u32::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-            ]);
-            if candidate_mints >= 100 && candidate_mints <= 1_000_000 {
-                num_mints = candidate_mints;
-                break;
-            }
+        let losing_tickets = join_data_struct.num_tickets - join_data_struct.num_winning_tickets;
+        if losing_tickets == 0 {
+            msg!("❌ Error: All {} tickets won. Order ID: {}", join_data_struct.num_tickets, join_data_struct.order_id);
+            return Err(ProgramError::InvalidAccountData);
         }
         
-        // Find end_date
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_date = u64::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4], launch_data_bytes[i + 5], launch_data_bytes[i + 6], launch_data_bytes[i + 7],
-            ]);
-            if candidate_date >= 1577836800 && candidate_date <= 1893456000 {
-                end_date = candidate_date;
-                break;
-            }
-        }
+        msg!("😔 User {} has {} losing tickets of {}. Processing refund...", user.key, losing_tickets, join_data_struct.num_tickets);
         
-        // Find tickets_sold
-        for i in 0..(launch_data_bytes.len() - 4) {
-            let candidate_sold = u32::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-            ]);
-            if candidate_sold <= num_mints {
-                tickets_sold = candidate_sold;
-                break;
-            }
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+
+        let ticket_price = launch_layout.ticket_price(&launch_data_bytes);
+        let num_mints = launch_layout.num_mints(&launch_data_bytes);
+        let tickets_sold = launch_layout.tickets_sold(&launch_data_bytes);
+        let end_date = launch_layout.end_date(&launch_data_bytes);
+        let page_name = launch_layout.page_name(&launch_data_bytes).to_string();
+        drop(launch_data_bytes);
+        
+        if join_data_struct.page_name != page_name {
+            msg!("❌ Error: JoinData is for launch {}, not {}", join_data_struct.page_name, page_name);
+            return Err(ProgramError::InvalidAccountData);
         }
         
         msg!("📊 Raffle data: ticket_price={}, num_mints={}, tickets_sold={}, end_date={}", 
@@ -1054,34 +1034,29 @@ impl Processor {
             return Err(ProgramError::InvalidInstructionData);
         }
         
-        // Calculate refund amount based on number of losing tickets
-        let refund_amount = ticket_price * join_data_struct.num_tickets as u64;
+        // Losing tickets get back what they left with the launch, the platform fee they were bought at stays with the platform
+        let refund_amount = crate::launch::get_failed_refund(ticket_price, losing_tickets, join_data_struct.ticket_fee_bps, false)?;
         
-        msg!("💰 Refunding {} lamports to user {} ({} tickets × {} lamports per ticket)", 
-             refund_amount, user.key, join_data_struct.num_tickets, ticket_price);
+        msg!("💰 Refunding {} lamports to user {} ({} losing tickets × {} lamports per ticket, less a {} bps fee)", 
+             refund_amount, user.key, losing_tickets, ticket_price, join_data_struct.ticket_fee_bps);
         
-        // Transfer SOL back to user
-        let transfer_instruction = system_instruction::transfer(
-            launch_data.key,
-            user.key,
-            refund_amount,
-        );
+        // The launch account is owned by this program, so the refund is paid straight from its lamports
+        let rent_floor = Rent::get()?.minimum_balance(launch_data.data_len());
+        if launch_data.lamports().saturating_sub(rent_floor) < refund_amount {
+            msg!("❌ Error: Launch cannot cover a refund of {} lamports", refund_amount);
+            return Err(ProgramError::InsufficientFunds);
+        }
+        **launch_data.try_borrow_mut_lamports()? -= refund_amount;
+        **user.try_borrow_mut_lamports()? += refund_amount;
         
-        invoke_signed(
-            &transfer_instruction,
-            &[
-                launch_data.clone(),
-                user.clone(),
-                system_program.clone(),
-            ],
-            &[],
-        )?;
+        join_data_struct.ticket_status = crate::launch::state::TicketStatus::LosingRefunded;
+        join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
         
         msg!("✅ Successfully refunded {} lamports", refund_amount);
         Ok(())
     }
 
-    fn process_check_tickets(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    fn process_check_tickets(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
         msg!("🔍 Processing CheckTickets instruction");
         
         if accounts.len() < 4 {
@@ -1092,7 +1067,7 @@ impl Processor {
         let user = &accounts[0];
         let launch_data = &accounts[1];
         let join_data = &accounts[2];
-        let orao_random = &accounts[3]; // Orao randomness oracle account
+        let randomness_account = &accounts[3]; // Orao request, or the launch's commit-reveal randomness
         
         if !user.is_signer {
             msg!("❌ Error: User must be a signer");
@@ -1101,24 +1076,36 @@ impl Processor {
         
         // Parse launch data
         let launch_data_bytes = launch_data.try_borrow_data()?;
-        let launch_data_struct: crate::state::LaunchData = crate::state::LaunchData::try_from_slice(&launch_data_bytes)?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+        let end_date = launch_layout.end_date(&launch_data_bytes);
+        let num_mints = launch_layout.num_mints(&launch_data_bytes);
+        let tickets_sold = launch_layout.tickets_sold(&launch_data_bytes);
+        let ticket_claimed = launch_layout.ticket_claimed(&launch_data_bytes);
+        let mints_won = launch_layout.mints_won(&launch_data_bytes);
+        let launch_failed = launch_layout.is_failed(&launch_data_bytes);
+        drop(launch_data_bytes);
+        
+        if launch_failed {
+            msg!("❌ Launch failed, claim a refund instead");
+            return Err(ProgramError::InvalidAccountData);
+        }
         
         // Check if raffle has ended
         let current_time = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-        if current_time <= launch_data_struct.end_date {
-            msg!("ℹ️ Raffle is still active (end_date: {}, current: {})", launch_data_struct.end_date, current_time);
+        if current_time <= end_date {
+            msg!("ℹ️ Raffle is still active (end_date: {}, current: {})", end_date, current_time);
             return Err(ProgramError::InvalidInstructionData);
         }
         
         // Check if all tickets have been sold
-        if launch_data_struct.tickets_sold < launch_data_struct.num_mints {
-            msg!("❌ Launch failed: {} tickets sold, {} mints required", launch_data_struct.tickets_sold, launch_data_struct.num_mints);
+        if tickets_sold < num_mints {
+            msg!("❌ Launch failed: {} tickets sold, {} mints required", tickets_sold, num_mints);
             return Err(ProgramError::InvalidAccountData);
         }
         
         // Parse JoinData to check user's tickets
         let join_data_bytes = join_data.try_borrow_data()?;
-        let mut join_data_struct: crate::launch::state::JoinData = match crate::launch::state::JoinData::try_from_slice(&join_data_bytes) {
+        let mut join_data_struct: crate::launch::state::JoinData = match crate::launch::state::JoinData::load(&join_data_bytes) {
             Ok(data) => data,
             Err(_) => {
                 msg!("❌ Error: JoinData not found");
@@ -1138,28 +1125,39 @@ impl Processor {
             return Ok(());
         }
         
-        // Use Orao randomness oracle for fair winner determination
-        msg!("🎲 Using Orao randomness oracle for winner determination");
+        // Indexed tickets are drawn in one shuffle across the whole raffle, which SettleRaffle applies
+        if join_data_struct.first_ticket.is_some() {
+            msg!("❌ Error: These tickets are settled by SettleRaffle");
+            return Err(ProgramError::InvalidAccountData);
+        }
         
-        // Check Oracle account
-        if orao_random.lamports() == 0 {
-            msg!("❌ Error: Orao random account is empty");
+        // The randomness must come from the fulfilled Orao request BuyTickets placed when these tickets were
+        // bought, or the launch's revealed commit-reveal secret on networks without Orao. Joins recording any
+        // other request could have picked one that was already fulfilled
+        let placed_request = crate::orao::get_request_address(&crate::orao::get_join_request_seed(user.key, launch_data.key, join_data_struct.last_slot));
+        if join_data_struct.random_address != placed_request {
+            msg!("❌ Error: Randomness request {} was not placed when these tickets were bought", join_data_struct.random_address);
             return Err(ProgramError::InvalidAccountData);
         }
+        let request = randomness::RandomnessRequest {
+            orao_request: placed_request,
+            launch: *launch_data.key,
+            consumer: *join_data.key,
+        };
+        let randomness = randomness::load_provider(program_id, randomness_account, &request)?.randomness()?;
         
-        // Read random seed from oracle
-        // The oracle stores random values in blocks of 8 bytes
+        // The randomness is used in blocks of 8 bytes
         let ticket_block: u8 = (join_data_struct.num_tickets_checked / 200) as u8; // Process 200 tickets per block
-        let r_start: usize = 40 + (ticket_block as usize * 8);
+        let r_start: usize = ticket_block as usize * 8;
         let r_end: usize = r_start + 8;
         
-        if r_end > orao_random.data.borrow().len() {
+        if r_end > randomness.len() {
             msg!("❌ Error: Oracle data range out of bounds");
             return Err(ProgramError::InvalidAccountData);
         }
         
         let mut seed = u64::from_le_bytes(
-            orao_random.data.borrow()[r_start..r_end]
+            randomness[r_start..r_end]
                 .try_into()
                 .map_err(|_| ProgramError::InvalidAccountData)?
         );
@@ -1172,8 +1170,8 @@ impl Processor {
         }
         
         // Initialize counters for winner determination
-        let mut tickets_remaining = launch_data_struct.tickets_sold - launch_data_struct.ticket_claimed;
-        let mut mints_remaining = launch_data_struct.num_mints - launch_data_struct.mints_won;
+        let mut tickets_remaining = tickets_sold - ticket_claimed;
+        let mut mints_remaining = num_mints - mints_won;
         let mut new_wins = 0;
         
         // Check up to 200 tickets at a time
@@ -1208,21 +1206,16 @@ impl Processor {
         msg!("🎫 User {} checked {} tickets, {} winners", user.key, tickets_to_check, new_wins);
         
         // Update JoinData
+        drop(join_data_bytes);
         let mut join_data_bytes_mut = join_data.try_borrow_mut_data()?;
         join_data_struct.num_winning_tickets += new_wins;
         join_data_struct.num_tickets_checked = join_data_struct.num_tickets;
-        
-        let serialized_join_data = borsh::to_vec(&join_data_struct)?;
-        join_data_bytes_mut[..serialized_join_data.len()].copy_from_slice(&serialized_join_data);
+        join_data_struct.store(&mut join_data_bytes_mut)?;
         
         // Update LaunchData
         let mut launch_data_bytes_mut = launch_data.try_borrow_mut_data()?;
-        let mut launch_data_mut: crate::state::LaunchData = crate::state::LaunchData::try_from_slice(&launch_data_bytes_mut)?;
-        launch_data_mut.mints_won += new_wins as u32;
-        launch_data_mut.ticket_claimed += tickets_to_check as u32;
-        
-        let serialized_launch_data = borsh::to_vec(&launch_data_mut)?;
-        launch_data_bytes_mut[..serialized_launch_data.len()].copy_from_slice(&serialized_launch_data);
+        launch_layout.set_mints_won(&mut launch_data_bytes_mut, mints_won + new_wins as u32);
+        launch_layout.set_ticket_claimed(&mut launch_data_bytes_mut, ticket_claimed + tickets_to_check as u32);
         
         if new_wins > 0 {
             msg!("🎉 User {} is a WINNER with {} winning tickets!", user.key, new_wins);
@@ -1233,7 +1226,7 @@ impl Processor {
         Ok(())
     }
 
-    fn process_init_cook_amm(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    fn process_init_cook_amm(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::InitCookAMMArgs) -> ProgramResult {
         msg!("🚀 Processing InitCookAMM instruction");
         
         if accounts.len() < 4 {
@@ -1306,9 +1299,66 @@ impl Processor {
             msg!("⚠️ AMM account already exists");
         }
         
+        if args.liquidity_scaling != 0 {
+            Self::enable_liquidity_scaling(program_id, accounts)?;
+        }
+        
         msg!("🎉 InitCookAMM completed successfully!");
         Ok(())
     }
+    
+    // Opts the launch's Cook AMM pool in to liquidity scaling before it has traded.
+    // Extra accounts after InitCookAMM's own: 4 cook_amm, 5 launch_data. Only the launch creator can opt in.
+    fn enable_liquidity_scaling(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        if accounts.len() < 6 {
+            msg!("❌ Error: liquidity scaling needs the Cook AMM and launch accounts. Expected: 6, Got: {}", accounts.len());
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        
+        let user = &accounts[0];
+        let token_mint = &accounts[1];
+        let system_program = &accounts[3];
+        let cook_amm = &accounts[4];
+        let launch_data = &accounts[5];
+        
+        let (_, expected_cook_amm, _) = crate::amm::get_cook_amm(token_mint.key, program_id);
+        if cook_amm.key != &expected_cook_amm || cook_amm.owner != program_id {
+            msg!("❌ Error: expected Cook AMM {}", expected_cook_amm);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        if launch_data.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        {
+            let launch_data_bytes = launch_data.try_borrow_data()?;
+            let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+            if !launch_layout.is_launch_of(&launch_data_bytes, launch_data.key, token_mint.key, program_id) {
+                msg!("❌ Error: launch account does not belong to mint {}", token_mint.key);
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if launch_layout.creator(&launch_data_bytes) != Some(*user.key) {
+                msg!("❌ Error: only the launch creator can enable liquidity scaling");
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+        
+        // switching it on mid trading would reprice positions bought without it
+        let amm_state = crate::amm::AMM::load(&cook_amm.try_borrow_data()?)?;
+        if amm_state.last_update != 0 || amm_state.total_liquidity != 0 {
+            msg!("❌ Error: liquidity scaling can only be enabled before the pool trades");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        crate::amm_plugins::add_amm_plugin(
+            user,
+            cook_amm,
+            system_program,
+            crate::amm_plugins::AMMPlugin::LiquidityScaling(crate::amm_plugins::LiquidityScaling::default()),
+        )?;
+        msg!("✅ Liquidity scaling enabled on Cook AMM {}", cook_amm.key);
+        Ok(())
+    }
 
     fn process_set_name(_program_id: &Pubkey, _accounts: &[AccountInfo], args: crate::instruction::SetNameArgs) -> ProgramResult {
         msg!("Processing SetName instruction");
@@ -1338,23 +1388,11 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
         
-        let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        
-        // Parse launch data using pattern matching
-        let mut total_supply: u64 = 0;
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
+        let total_supply = launch_layout.total_supply(&launch_data_bytes);
         let decimals: u8 = 9; // Default to 9 decimals
-        
-        // Find total_supply
-        for i in 0..(launch_data_bytes.len() - 8) {
-            let candidate_supply = u64::from_le_bytes([
-                launch_data_bytes[i], launch_data_bytes[i + 1], launch_data_bytes[i + 2], launch_data_bytes[i + 3],
-                launch_data_bytes[i + 4], launch_data_bytes[i + 5], launch_data_bytes[i + 6], launch_data_bytes[i + 7],
-            ]);
-            if candidate_supply >= 1_000_000 && candidate_supply <= 1_000_000_000_000 {
-                total_supply = candidate_supply;
-                break;
-            }
-        }
+        drop(launch_data_bytes);
         
         msg!("📊 Launch data: total_supply={}, decimals={}", total_supply, decimals);
         
@@ -1411,10 +1449,164 @@ impl Processor {
         Ok(())
     }
 
-    fn process_swap_cook_amm(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::PlaceOrderArgs) -> ProgramResult {
+    // Feeds a SwapCookAMM trade into the pool's TWAP oracle, candles and trade to earn volume.
+    // Optional trailing accounts: 16 price_data, 17 launch_rewards, 18 user_rewards.
+    // Older clients that leave them out still swap, without candles or rewards.
+    fn record_cook_amm_trade(program_id: &Pubkey, accounts: &[AccountInfo], side: u8, sol_amount: u64, token_amount: u64) -> ProgramResult {
+        let user = &accounts[0];
+        let amm_account = &accounts[2];
+        let system_program = &accounts[11];
+        
+        if let Some(price_x64) = crate::amm::get_price_x64(sol_amount, token_amount) {
+            if amm_account.owner == program_id {
+                crate::amm::resize_amm_data(user, amm_account, system_program)?;
+                let now = solana_program::clock::Clock::get()?.unix_timestamp;
+                crate::amm::record_oracle_price(amm_account, now, price_x64)?;
+            }
+        }
+        
+        if accounts.len() <= 16 {
+            return Ok(());
+        }
+        
+        let token_decimals = {
+            let mint_data = accounts[1].try_borrow_data()?;
+            spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?.base.decimals
+        };
+        crate::candles::update_price_data(user, &accounts[16], amm_account.key, program_id, sol_amount, token_amount, token_decimals)?;
+        
+        if accounts.len() <= 18 || amm_account.owner != program_id {
+            return Ok(());
+        }
+        
+        let now = solana_program::clock::Clock::get()?.unix_timestamp;
+        crate::trade_to_earn::record_trade(program_id, user, amm_account, &accounts[17], &accounts[18], now, side, token_amount)
+    }
+
+    // Pays a SwapCookAMM trade's creator fee from the trader's SOL account into the launch's CreatorFees vault
+    // at index 24, which the first fee creates.
+    fn accrue_swap_creator_fee(program_id: &Pubkey, accounts: &[AccountInfo], creator_fee: u64) -> ProgramResult {
+        if creator_fee == 0 {
+            return Ok(());
+        }
+        let creator_fees = match accounts.get(24) {
+            Some(creator_fees) => creator_fees,
+            None => {
+                msg!("❌ Error: Launches with a creator fee need their CreatorFees vault at index 24");
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        };
+
+        creator_fees::accrue_creator_fee(program_id, &accounts[4], accounts[6].key, creator_fees, &accounts[11], creator_fee)
+    }
+
+    // Checks a bonding curve buy against the launch's merkle allowlist while its phase is running
+    // and adds the buy to the wallet's allowlist spend, kept in its JoinData at index 22.
+    // The JoinData is created on the wallet's first allowlisted buy.
+    fn record_allowlist_buy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        launch_layout: &crate::launch::LaunchAccessor,
+        allowlist: &crate::launch::MerkleAllowlist,
+        proof: Option<&crate::instruction::AllowlistProof>,
+        now: u64,
+        spend: u64,
+    ) -> ProgramResult {
+        if !allowlist.is_active(now) {
+            return Ok(());
+        }
+        
+        let user = &accounts[0];
+        let launch_data = &accounts[6];
+        let join_data = match accounts.get(22) {
+            Some(join_data) => join_data,
+            None => {
+                msg!("❌ Error: Allowlist buys need the buyer's JoinData at index 22");
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        };
+        
+        let page_name = launch_layout.page_name(&launch_data.try_borrow_data()?).to_string();
+        let (expected_join_data, join_bump) =
+            Pubkey::find_program_address(&[&user.key.to_bytes(), page_name.as_bytes(), b"Joiner"], program_id);
+        if join_data.key != &expected_join_data {
+            msg!("❌ Error: JoinData is not the buyer's PDA for page {}", page_name);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        let mut join_data_struct = if **join_data.try_borrow_lamports()? == 0 {
+            let join_data_struct = crate::launch::state::JoinData {
+                account_type: crate::state::AccountType::Join,
+                joiner_key: *user.key,
+                page_name: page_name.clone(),
+                ticket_status: crate::launch::state::TicketStatus::Available,
+                random_address: system_program::ID,
+                ..Default::default()
+            };
+            crate::utils::create_program_account(
+                user,
+                join_data,
+                program_id,
+                join_bump,
+                borsh::to_vec(&join_data_struct)?.len(),
+                vec![&user.key.to_bytes(), page_name.as_bytes(), b"Joiner"],
+            )?;
+            join_data_struct
+        } else {
+            if join_data.owner != program_id {
+                msg!("❌ Error: JoinData not owned by program");
+                return Err(ProgramError::IllegalOwner);
+            }
+            crate::launch::state::JoinData::load(&join_data.try_borrow_data()?)?
+        };
+        
+        join_data_struct.allowlist_spent =
+            crate::launch::check_allowlist(allowlist, now, user.key, proof, join_data_struct.allowlist_spent, spend)?;
+        crate::launch::resize_join_data(user, join_data, &accounts[11], join_data_struct.size()?)?;
+        join_data_struct.store(&mut join_data.try_borrow_mut_data()?)?;
+        Ok(())
+    }
+
+    // Scales a SwapCookAMM buy down while the pool's liquidity scaling plugin is active.
+    // The quote reserve is read from amm_quote (12), the plugin switches itself off once it reaches the threshold.
+    // Sells are paid out of the curve or the fixed rate and are left unscaled.
+    fn apply_liquidity_scaling(program_id: &Pubkey, accounts: &[AccountInfo], tokens_out: u64) -> Result<u64, ProgramError> {
+        let amm_account = &accounts[2];
+        if accounts.len() <= 12 || amm_account.owner != program_id {
+            return Ok(tokens_out);
+        }
+        
+        let mut amm_state = crate::amm::AMM::load(&amm_account.try_borrow_data()?)?;
+        if amm_state.plugins.is_empty() {
+            return Ok(tokens_out);
+        }
+        
+        let quote_reserve = crate::amm::unpack_reserve(&accounts[12].try_borrow_data()?, amm_account.key, &accounts::wrapped_sol_mint_account::ID)?;
+        let plugins = amm_state.plugins.clone();
+        let scaled = match crate::amm_plugins::check_liquidity_scaling(&mut amm_state.plugins, quote_reserve) {
+            Some(liquidity_plugin) => {
+                let scaled = crate::amm_plugins::scale_output(tokens_out, quote_reserve, &liquidity_plugin);
+                msg!("📉 Liquidity scaling: {} of {} tokens at {} lamports of liquidity", scaled, tokens_out, quote_reserve);
+                scaled
+            }
+            None => tokens_out,
+        };
+        
+        if amm_state.plugins != plugins {
+            amm_state.store(&mut amm_account.try_borrow_mut_data()?)?;
+        }
+        Ok(scaled)
+    }
+
+    /// SOL a bonding curve has collected, as WSOL in the pool's validated amm_quote
+    fn bonding_curve_sol(cook_venue: &crate::amm::CookVenue) -> u64 {
+        token::get_token_balance(cook_venue.amm_quote)
+    }
+
+    fn process_swap_cook_amm(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::SwapArgs) -> ProgramResult {
         msg!("SwapCookAMM");
         
-        if accounts.len() < 6 {
+        if accounts.len() < 7 {
             return Err(ProgramError::NotEnoughAccountKeys);
         }
         
@@ -1424,6 +1616,7 @@ impl Processor {
         let user_token_account = &accounts[3];
         let user_sol_account = &accounts[4];
         let ledger_wallet = &accounts[5];
+        let launch_data = &accounts[6];
         
         // Get cook_base_token (should be at index 10, after launch_data, token_program, cook_pda, amm_base)
         let cook_base_token = if accounts.len() > 10 {
@@ -1449,9 +1642,65 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
         
-        // Skip tradability check here to avoid memory allocation
-        // Instant launches are always tradable, raffle launches will be checked in swap logic
-        // This prevents early deserialization which can cause out-of-memory errors
+        // The swap fee and where it goes come from the fee schedule in the program data at index 23
+        let fee_config = match accounts.get(23) {
+            Some(cook_data) => accounts::load_fee_config(program_id, cook_data)?,
+            None => {
+                msg!("❌ Error: Swaps need the program data at index 23");
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        };
+        accounts::check_fee_recipient(&fee_config, ledger_wallet)?;
+        common::check_not_paused(program_id, accounts.get(23))?;
+        
+        // Launch state comes from the PDA-verified launch account, never from instruction args
+        let launch_layout = Self::load_swap_launch(program_id, launch_data, token_mint.key)?;
+        common::check_launch_not_paused(launch_data)?;
+        let (is_instant_launch, is_graduated, tokens_sold, total_supply, creator_key, graduation_threshold) = {
+            let launch_data_bytes = launch_data.try_borrow_data()?;
+            let threshold = crate::launch::get_graduation_threshold(launch_layout.graduation_threshold(&launch_data_bytes));
+            (
+                launch_layout.launch_meta_type(&launch_data_bytes) == crate::launch::LaunchMetaType::FCFS,
+                launch_layout.is_graduated(&launch_data_bytes),
+                launch_layout.tokens_sold(&launch_data_bytes),
+                launch_layout.total_supply(&launch_data_bytes),
+                launch_layout.creator(&launch_data_bytes),
+                threshold,
+            )
+        };
+        
+        if !is_instant_launch && !launch_layout.is_tradable(&launch_data.try_borrow_data()?) {
+            msg!("❌ Error: Token is not yet tradable. Raffle must graduate first.");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        // the pool's vaults at 9 and 12 hold the curve's tokens and SOL, the freeze and the buy's wrapped SOL use them
+        let (amm_base, amm_quote) = match (accounts.get(9), accounts.get(12)) {
+            (Some(amm_base), Some(amm_quote)) => (amm_base, amm_quote),
+            _ => return Err(ProgramError::NotEnoughAccountKeys),
+        };
+        let cook_venue = crate::amm::CookVenue {
+            amm: amm_account,
+            amm_base,
+            amm_quote,
+        };
+        cook_venue.validate(program_id, token_mint.key, &accounts::wrapped_sol_mint_account::ID)?;
+        
+        // GRADUATION: the bonding curve is frozen once it has raised its threshold, until GraduateLaunch
+        // turns its vaults into the Cook DEX pool, which is then traded through BestPriceSwap
+        if is_instant_launch {
+            if is_graduated {
+                msg!("❌ Error: Launch has graduated off its bonding curve, trade it through BestPriceSwap");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            if Self::bonding_curve_sol(&cook_venue) >= graduation_threshold {
+                msg!("❌ Error: Bonding curve has reached its graduation threshold, waiting for GraduateLaunch");
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+        
+        // the launch creator's share of the trade, paid into its CreatorFees vault at index 24
+        let creator_fee_bps = creator_fees::get_creator_fee_bps(&launch_layout.plugins(&launch_data.try_borrow_data()?), &fee_config);
         
         // CRITICAL: Derive AMM account using the same seeds as instant_launch.rs
         // Backend uses: [base_mint, quote_mint, b"CookAMM"] (sorted)
@@ -1508,22 +1757,26 @@ impl Processor {
         
         if args.side == 0 {
             let sol_amount = args.max_quote_quantity;
-            let fee_rate = 25; // 0.25% fee (25 basis points)
-            let fee_amount = (sol_amount * fee_rate) / 10000;
-            let net_sol_amount = sol_amount - fee_amount;
-            
-            // Get launch state from instruction args (passed from frontend)
-            // This avoids deserializing LaunchData which has Vec<String> causing out-of-memory
-            let is_instant_launch = args.is_instant_launch == 1;
-            let is_graduated = args.is_graduated == 1;
-            let tokens_sold = args.tokens_sold;
-            let total_supply = args.total_supply;
-            let creator_key = args.creator_key;
-            let graduation_threshold = 30_000_000_000u64; // Default 30 SOL threshold
+            let fee_amount = crate::state::get_fee(sol_amount, fee_config.swap_fee_bps);
+            let creator_fee = crate::state::get_fee(sol_amount, creator_fee_bps);
+            let net_sol_amount = sol_amount - fee_amount - creator_fee;
             
             // Check if we should use pump.fun-style bonding curve or AMM pricing
             let use_bonding_curve = is_instant_launch && !is_graduated;
             
+            // WHITELIST PHASE: a bonding curve buy burns the whitelist tokens of one ticket,
+            // accounts 19-21 are whitelist_mint, whitelist_token_program and whitelist_account
+            if use_bonding_curve {
+                let plugins = launch_layout.plugins(&launch_data.try_borrow_data()?);
+                let now = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+                if let Some(whitelist) = crate::launch::get_whitelist(&plugins) {
+                    crate::launch::burn_whitelist_tokens(&whitelist, now, 1, user, accounts.get(19), accounts.get(20), accounts.get(21))?;
+                }
+                if let Some(allowlist) = crate::launch::get_merkle_allowlist(&plugins) {
+                    Self::record_allowlist_buy(program_id, accounts, &launch_layout, &allowlist, args.allowlist.as_ref(), now, sol_amount)?;
+                }
+            }
+            
             // Get decimals from token mint (default to 9 if can't read)
             let decimals = if token_mint.data.borrow().len() >= 44 {
                 let mint_data = token_mint.data.borrow();
@@ -1629,8 +1882,11 @@ impl Processor {
                 ((sol_in_f64 / 1_000_000_000.0) * base_rate * 1_000_000_000.0) as u64
             };
             
+            // LIQUIDITY SCALING: pools that opted in hand out a fraction of the tokens while they are thin
+            let tokens_to_mint = Self::apply_liquidity_scaling(program_id, accounts, tokens_to_mint)?;
+            
             // CREATOR PURCHASE LIMIT: Check if user is creator and enforce 20% limit
-            if creator_key == *user.key {
+            if creator_key == Some(*user.key) {
                 let creator_balance = if user_token_account.data.borrow().len() >= 72 {
                     let data = user_token_account.data.borrow();
                     u64::from_le_bytes([
@@ -1655,6 +1911,8 @@ impl Processor {
                 return Err(ProgramError::Custom(1)); // Slippage exceeded
             }
             
+            Self::record_cook_amm_trade(program_id, accounts, 0, net_sol_amount, tokens_to_mint)?;
+            
             // CRITICAL: For bonding curve, wrap SOL and transfer WSOL to amm_quote
             // amm_quote is the wrapped SOL token account owned by the AMM
             // Get amm_quote account (should be at index 12, after system_program)
@@ -1745,6 +2003,8 @@ impl Processor {
                 )?;
             }
             
+            Self::accrue_swap_creator_fee(program_id, accounts, creator_fee)?;
+            
             // Verify user token account exists and is initialized before minting
             // This ensures tokens will appear in the user's wallet
             let user_token_account_lamports = **user_token_account.try_borrow_lamports()?;
@@ -1951,37 +2211,23 @@ impl Processor {
             }
             
             // Check graduation threshold: SOL collected in AMM pool (30 SOL)
-            // For instant launches, SOL accumulates in the AMM account from bonding curve buys
             if use_bonding_curve {
-                let sol_collected = **amm_account.lamports.borrow();
+                let sol_collected = Self::bonding_curve_sol(&cook_venue);
+                
+                // Record the sale in the same instruction as the transfer
+                let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
+                launch_layout.set_tokens_sold(&mut launch_data_bytes, tokens_sold.saturating_add(tokens_to_mint));
+                drop(launch_data_bytes);
                 
-                // Check if graduation threshold is met (30 SOL collected)
+                // Once the threshold is met the curve stops trading and any keeper can graduate it with GraduateLaunch
                 if sol_collected >= graduation_threshold {
-                    // Graduation threshold met! Log event for frontend/backend to handle pool creation
-                    // Note: We can't create the pool here because:
-                    // 1. We can't deserialize LaunchData (memory issue)
-                    // 2. Pool creation requires many accounts not available in swap instruction
-                    // 3. Pool creation should be done via separate instruction after graduation
-                    msg!("EVENT:GRADUATION_THRESHOLD_MET:token_mint:{}:sol_collected:{}:threshold:{}", 
-                         token_mint.key, sol_collected, graduation_threshold);
-                    msg!("GRADUATION:30 SOL threshold reached - Pool creation needed");
-                    // Frontend/backend should call CreateRaydium instruction after detecting this event
+                    crate::events::emit_threshold_met_event(token_mint.key, graduation_threshold, sol_collected, amm_account.key, 0);
                 }
             }
             
-            // Skip launch data update to avoid memory allocation errors
-            // The launch data update would require deserializing Vec<String> which causes out-of-memory
-            // For production, use a separate instruction to update launch data and create pools
-            
         } else if args.side == 1 {
             
             let token_amount = args.max_base_quantity; // All tokens to burn
-            let fee_rate = 25; // 0.25% fee (25 basis points)
-            
-            // Get launch state from instruction args (passed from frontend)
-            let is_instant_launch = args.is_instant_launch == 1;
-            let is_graduated = args.is_graduated == 1;
-            let tokens_sold = args.tokens_sold;
             
             // Check if we should use pump.fun-style bonding curve or AMM pricing
             let use_bonding_curve = is_instant_launch && !is_graduated;
@@ -2015,9 +2261,10 @@ impl Processor {
                 (total_sol_lamports, tokens_sold) // tokens_sold unchanged for AMM
             };
             
-            // Deduct 0.25% fee from SOL (like we do on buy side)
-            let sol_fee = (total_sol * fee_rate) / 10000;
-            let sol_to_user = total_sol - sol_fee;
+            // Deduct the swap fee from SOL (like we do on buy side)
+            let sol_fee = crate::state::get_fee(total_sol, fee_config.swap_fee_bps);
+            let creator_fee = crate::state::get_fee(total_sol, creator_fee_bps);
+            let sol_to_user = total_sol - sol_fee - creator_fee;
             
             // SLIPPAGE PROTECTION: Verify against minimum expected
             let minimum_expected_sol = args.max_quote_quantity; // Frontend sets minimum SOL expected
@@ -2025,6 +2272,12 @@ impl Processor {
                 return Err(ProgramError::Custom(1)); // Slippage exceeded
             }
             
+            // The seller pays the creator fee up front and gets it back with their proceeds below
+            Self::accrue_swap_creator_fee(program_id, accounts, creator_fee)?;
+            let sol_out = sol_to_user + creator_fee;
+            
+            Self::record_cook_amm_trade(program_id, accounts, 1, total_sol, token_amount)?;
+            
             // Get token program from accounts (should be at index 7)
             let token_program = if accounts.len() > 7 {
                 &accounts[7]
@@ -2056,6 +2309,11 @@ impl Processor {
             
             msg!("✅ Burned {} tokens", token_amount);
             
+            if use_bonding_curve {
+                let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
+                launch_layout.set_tokens_sold(&mut launch_data_bytes, new_tokens_sold);
+            }
+            
             // CRITICAL: Transfer WSOL from amm_quote, unwrap to SOL, and give to seller
             // Get amm_quote account (should be at index 12, after system_program)
             let amm_quote = if accounts.len() > 12 {
@@ -2076,10 +2334,10 @@ impl Processor {
                 let user_lamports_before = **user_sol_account.try_borrow_lamports()?;
                 
                 **amm_account.try_borrow_mut_lamports()? = amm_lamports_before
-                    .checked_sub(sol_to_user)
+                    .checked_sub(sol_out)
                     .ok_or(ProgramError::InsufficientFunds)?;
                 **user_sol_account.try_borrow_mut_lamports()? = user_lamports_before
-                    .checked_add(sol_to_user)
+                    .checked_add(sol_out)
                     .ok_or(ProgramError::InvalidArgument)?;
                 
                 // Transfer fee to ledger_wallet (if fee > 0)
@@ -2124,7 +2382,7 @@ impl Processor {
                 return Err(ProgramError::InsufficientFunds);
             }
             
-            msg!("💰 Transferring {} WSOL from amm_quote to user and unwrapping", sol_to_user);
+            msg!("💰 Transferring {} WSOL from amm_quote to user and unwrapping", sol_out);
             
             // Get user's WSOL account (should be at index 14)
             let user_wsol_account_info = if accounts.len() > 14 {
@@ -2136,10 +2394,10 @@ impl Processor {
                 let user_lamports_before = **user_sol_account.try_borrow_lamports()?;
                 
                 **amm_account.try_borrow_mut_lamports()? = amm_lamports_before
-                    .checked_sub(sol_to_user)
+                    .checked_sub(sol_out)
                     .ok_or(ProgramError::InsufficientFunds)?;
                 **user_sol_account.try_borrow_mut_lamports()? = user_lamports_before
-                    .checked_add(sol_to_user)
+                    .checked_add(sol_out)
                     .ok_or(ProgramError::InvalidArgument)?;
                 
                 // Transfer fee
@@ -2165,7 +2423,7 @@ impl Processor {
                 user_wsol_account_info.key,
                 amm_account.key,  // authority (AMM account owns amm_quote)
                 &[],
-                sol_to_user,
+                sol_out,
             )?;
             
             // Invoke transfer with AMM account as signer (it's a PDA)
@@ -2207,7 +2465,7 @@ impl Processor {
                 ]],
             )?;
             
-            msg!("✅ Transferred {} WSOL from amm_quote to user's WSOL account", sol_to_user);
+            msg!("✅ Transferred {} WSOL from amm_quote to user's WSOL account", sol_out);
             
             // Unwrap WSOL: Close the WSOL account to convert it back to SOL
             // For WSOL, closing the account automatically unwraps it to SOL (sends lamports to destination)
@@ -2229,7 +2487,7 @@ impl Processor {
                 ],
             )?;
             
-            msg!("✅ Unwrapped {} WSOL to SOL for user", sol_to_user);
+            msg!("✅ Unwrapped {} WSOL to SOL for user", sol_out);
             
             // Transfer fee to ledger_wallet (if fee > 0)
             if sol_fee > 0 {
@@ -2252,6 +2510,8 @@ impl Processor {
                     return Ok(());
                 };
                 
+                accounts::check_fee_recipient_wsol(&fee_config, ledger_wsol_account, quote_token_program)?;
+                
                 // Transfer WSOL fee from amm_quote to ledger_wallet's WSOL account
                 let transfer_fee_wsol_instruction = token_instruction::transfer(
                     quote_token_program.key,
@@ -2282,9 +2542,6 @@ impl Processor {
                 msg!("✅ Transferred {} WSOL fee to ledger_wallet", sol_fee);
             }
             
-            // Skip launch data update to avoid memory allocation errors
-            // The launch data update would require deserializing Vec<String> which causes out-of-memory
-            
         } else {
             return Err(ProgramError::InvalidInstructionData);
         }
@@ -2293,6 +2550,51 @@ impl Processor {
     }
 
 
+    // Read-only TWAP of a Cook AMM pool, returned as borsh encoded amm::TWAPData.
+    // Accounts: 0 amm, 1 base_token_mint
+    fn process_get_twap(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::GetTWAPArgs) -> ProgramResult {
+        if accounts.len() < 2 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        
+        let amm_account = &accounts[0];
+        let base_token_mint = &accounts[1];
+        
+        let mut amm_seed_keys: Vec<Pubkey> = Vec::new();
+        crate::amm::get_amm_seeds(*base_token_mint.key, accounts::wrapped_sol_mint_account::ID, &mut amm_seed_keys);
+        let (expected_amm_account, _) =
+            Pubkey::find_program_address(&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM"], program_id);
+        if amm_account.key != &expected_amm_account || amm_account.owner != program_id {
+            msg!("❌ Error: Expected Cook AMM {}", expected_amm_account);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        let base_decimals = {
+            let mint_data = base_token_mint.try_borrow_data()?;
+            spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?.base.decimals
+        };
+        
+        let now = solana_program::clock::Clock::get()?.unix_timestamp;
+        let amm_state = crate::amm::AMM::load(&amm_account.try_borrow_data()?)?;
+        let (price_x64, seconds) = match amm_state.get_twap(now, args.window) {
+            Some(twap) => twap,
+            None => {
+                msg!("❌ Error: No price history covering the last {} seconds", args.window);
+                return Err(ProgramError::InvalidArgument);
+            }
+        };
+        
+        let twap = crate::amm::TWAPData {
+            price_x64,
+            seconds,
+            price: price_x64 as f64 / 2f64.powi(64) * 10f64.powi(base_decimals as i32 - 9),
+        };
+        msg!("📈 TWAP over {}s: {} SOL", twap.seconds, twap.price);
+        
+        solana_program::program::set_return_data(&borsh::to_vec(&twap)?);
+        Ok(())
+    }
+
     fn process_edit_launch(_program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::EditArgs) -> ProgramResult {
         msg!("✏️ Processing EditLaunch instruction");
         
@@ -2308,7 +2610,7 @@ impl Processor {
         }
         
         let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        let mut launch_data_struct: crate::state::LaunchData = crate::state::LaunchData::try_from_slice(&launch_data_bytes)?;
+        let mut launch_data_struct = crate::launch::LaunchData::load(&launch_data_bytes)?;
         
         if launch_data_struct.creator != *user.key {
             return Err(ProgramError::InvalidAccountData);
@@ -2348,23 +2650,24 @@ impl Processor {
         }
         
         let mut launch_data_bytes = launch_data.try_borrow_mut_data()?;
-        let mut launch_data_struct: crate::state::LaunchData = crate::state::LaunchData::try_from_slice(&launch_data_bytes)?;
+        let launch_layout = crate::launch::LaunchAccessor::load(&launch_data_bytes)?;
         
+        // votes are not stored by legacy launch module accounts
         if args.vote == 1 {
-            launch_data_struct.upvotes += 1;
+            let upvotes = launch_layout.upvotes(&launch_data_bytes).ok_or(ProgramError::InvalidAccountData)?;
+            launch_layout.set_upvotes(&mut launch_data_bytes, upvotes + 1)?;
             msg!("✅ Upvote recorded");
         } else if args.vote == 0 {
-            launch_data_struct.downvotes += 1;
+            let downvotes = launch_layout.downvotes(&launch_data_bytes).ok_or(ProgramError::InvalidAccountData)?;
+            launch_layout.set_downvotes(&mut launch_data_bytes, downvotes + 1)?;
             msg!("✅ Downvote recorded");
         } else {
             return Err(ProgramError::InvalidInstructionData);
         }
         
-        launch_data_struct.num_interactions += 1;
-        launch_data_struct.last_interaction = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-        
-        let serialized_data = borsh::to_vec(&launch_data_struct)?;
-        launch_data_bytes[..serialized_data.len()].copy_from_slice(&serialized_data);
+        let num_interactions = launch_layout.num_interactions(&launch_data_bytes);
+        launch_layout.set_num_interactions(&mut launch_data_bytes, num_interactions + 1);
+        launch_layout.set_last_interaction(&mut launch_data_bytes, solana_program::clock::Clock::get()?.unix_timestamp);
         
         Ok(())
     }
@@ -2663,12 +2966,12 @@ impl Processor {
         data
     }
 
-    fn process_add_cook_liquidity(_program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::AddLiquidityArgs) -> ProgramResult {
+    fn process_add_cook_liquidity<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::AddLiquidityArgs) -> ProgramResult {
         msg!("Processing AddCookLiquidity instruction");
         
-        if accounts.len() < 8 {
-            msg!("❌ Error: Not enough account keys provided. Expected: 8+, Got: {}", accounts.len());
-            msg!("  Required accounts: user, token_mint, amm_account, user_token_account, user_sol_account, lp_token_mint, token_program, system_program, amm_base, user_lp_token_account");
+        if accounts.len() < 14 {
+            msg!("❌ Error: Not enough account keys provided. Expected: 14, Got: {}", accounts.len());
+            msg!("  Required accounts: user, token_mint, amm_account, user_token_account, user_quote_account, lp_token_mint, token_program, quote_token_mint, amm_base, user_lp_token_account, amm_quote, quote_token_program, cook_data, launch_data");
             return Err(ProgramError::NotEnoughAccountKeys);
         }
         
@@ -2676,131 +2979,234 @@ impl Processor {
         let token_mint = &accounts[1];
         let amm_account = &accounts[2];
         let user_token_account = &accounts[3]; // User's base token account
-        let user_sol_account = &accounts[4];
+        let user_quote_account = &accounts[4]; // User's WSOL account
         let lp_token_mint = &accounts[5];
-        let token_program = &accounts[6];
-        let system_program = &accounts[7];
-        let amm_base = if accounts.len() > 8 { &accounts[8] } else {
-            msg!("❌ Error: amm_base account missing");
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
-        let user_lp_token_account = if accounts.len() > 9 { &accounts[9] } else {
-            msg!("❌ Error: user_lp_token_account missing - need ATA for LP tokens");
-            return Err(ProgramError::NotEnoughAccountKeys);
-        };
+        let token_program = &accounts[6]; // Base token and LP token program
+        let quote_token_mint = &accounts[7];
+        let amm_base = &accounts[8];
+        let user_lp_token_account = &accounts[9];
+        let amm_quote = &accounts[10];
+        let quote_token_program = &accounts[11];
+        let cook_data = &accounts[12];
+        let launch_data = &accounts[13];
         
         if !user.is_signer {
             msg!("❌ Error: User must be a signer");
             return Err(ProgramError::MissingRequiredSignature);
         }
         
-        let sol_amount = args.amount_0;
-        let token_amount = args.amount_1;
+        common::check_not_paused(program_id, Some(cook_data))?;
         
-        msg!("💰 Adding liquidity: {} SOL, {} tokens", sol_amount, token_amount);
+        // a bonding curve's vaults belong to the curve, deposits would block GraduateLaunch repricing the pool
+        let launch_layout = Self::load_swap_launch(program_id, launch_data, token_mint.key)?;
+        common::check_launch_not_paused(launch_data)?;
+        if Self::is_on_bonding_curve(&launch_layout, launch_data)? {
+            msg!("❌ Error: Launch is on its bonding curve, liquidity can be added once it graduates");
+            return Err(ProgramError::InvalidAccountData);
+        }
         
-        // Calculate LP tokens using sqrt formula (constant product AMM)
-        let lp_tokens = ((sol_amount as u128 * token_amount as u128) as f64).sqrt() as u64;
+        accounts::check_wrapped_sol_key(quote_token_mint)?;
+        if token_mint.owner != token_program.key || lp_token_mint.owner != token_program.key || quote_token_mint.owner != quote_token_program.key {
+            msg!("❌ Error: Token program does not own mint");
+            return Err(ProgramError::IncorrectProgramId);
+        }
         
-        if lp_tokens == 0 {
-            msg!("❌ Error: LP token amount is zero");
+        let cook_venue = crate::amm::CookVenue {
+            amm: amm_account,
+            amm_base,
+            amm_quote,
+        };
+        let (reserve_base, reserve_quote) = cook_venue.checked_reserves(program_id, token_mint.key)?;
+        
+        let (expected_lp_mint, _) = Pubkey::find_program_address(&[&amm_account.key.to_bytes(), b"LP"], program_id);
+        if lp_token_mint.key != &expected_lp_mint {
+            msg!("❌ Error: Expected LP mint {}", expected_lp_mint);
             return Err(ProgramError::InvalidAccountData);
         }
         
-        msg!("📊 Calculated LP tokens: {}", lp_tokens);
+        let (token_amount, sol_amount) = match crate::amm::get_deposit_amounts(args.amount_1, args.amount_0, reserve_base, reserve_quote) {
+            Some(amounts) => amounts,
+            None => {
+                msg!("❌ Error: Deposit amounts overflow");
+                return Err(ProgramError::ArithmeticOverflow);
+            }
+        };
         
-        // Transfer SOL to AMM account
-        let transfer_sol_instruction = system_instruction::transfer(
-            user_sol_account.key,
-            amm_account.key,
-            sol_amount,
-        );
+        // The pool should receive the planned amounts, so the user covers any transfer fee
+        let token_amount_gross = token::get_amount_pre_transfer_fee(token_amount, token_mint)?;
+        let sol_amount_gross = token::get_amount_pre_transfer_fee(sol_amount, quote_token_mint)?;
         
-        invoke_signed(
-            &transfer_sol_instruction,
-            &[
-                user_sol_account.clone(),
-                amm_account.clone(),
-                system_program.clone(),
-            ],
-            &[],
-        )?;
-        msg!("✅ Transferred {} SOL to AMM", sol_amount);
+        msg!("💰 Adding liquidity: {} SOL, {} tokens (reserves: {} SOL, {} tokens)", sol_amount, token_amount, reserve_quote, reserve_base);
         
-        // Transfer base tokens from user to amm_base account
-        use spl_token_2022::instruction;
-        let transfer_token_instruction = instruction::transfer(
-            token_program.key,
-            user_token_account.key,
-            amm_base.key,
-            user.key,
-            &[],
-            token_amount,
-        )?;
+        if token_amount_gross > args.max_base_in || sol_amount_gross > args.max_quote_in {
+            msg!("❌ Error: Slippage exceeded. Needs {} tokens (max {}), {} SOL (max {})", token_amount_gross, args.max_base_in, sol_amount_gross, args.max_quote_in);
+            return Err(ProgramError::Custom(1));
+        }
         
-        invoke_signed(
-            &transfer_token_instruction,
-            &[
-                user_token_account.clone(),
-                amm_base.clone(),
-                user.clone(),
-                token_program.clone(),
-            ],
-            &[],
-        )?;
-        msg!("✅ Transferred {} tokens to amm_base", token_amount);
+        for (mint, source, vault, program, amount) in [
+            (token_mint, user_token_account, amm_base, token_program, token_amount_gross),
+            (quote_token_mint, user_quote_account, amm_quote, quote_token_program, sol_amount_gross),
+        ] {
+            let decimals = spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.try_borrow_data()?)?.base.decimals;
+            invoke(
+                &token_instruction::transfer_checked(program.key, source.key, mint.key, vault.key, user.key, &[], amount, decimals)?,
+                &[source.clone(), mint.clone(), vault.clone(), user.clone(), program.clone()],
+            )?;
+        }
         
-        // Mint LP tokens to user's LP token account
-        // Note: LP token mint authority should be the AMM account or a PDA
-        // For now, we'll assume the mint authority is set correctly
-        let mint_lp_instruction = instruction::mint_to(
-            token_program.key,
-            lp_token_mint.key,
-            user_lp_token_account.key,
-            amm_account.key, // Mint authority (AMM account)
-            &[],
-            lp_tokens,
-        )?;
+        // Shares are priced from what the vaults actually received
+        let (base_received, quote_received) = cook_venue.reserves(token_mint.key)?;
+        let base_received = base_received.saturating_sub(reserve_base);
+        let quote_received = quote_received.saturating_sub(reserve_quote);
         
-        invoke_signed(
-            &mint_lp_instruction,
-            &[
-                lp_token_mint.clone(),
-                user_lp_token_account.clone(),
-                amm_account.clone(), // Mint authority
-                token_program.clone(),
-            ],
-            &[],
-        )?;
+        let total_liquidity = {
+            let lp_mint_data = lp_token_mint.try_borrow_data()?;
+            let lp_supply = spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&lp_mint_data)?.base.supply;
+            cook_venue.total_liquidity()?.max(lp_supply)
+        };
+        
+        let (lp_tokens, new_total_liquidity) =
+            match crate::amm::get_liquidity_minted(base_received, quote_received, reserve_base, reserve_quote, total_liquidity) {
+                Some(minted) => minted,
+                None => {
+                    msg!("❌ Error: Deposit of {} tokens, {} SOL mints no LP tokens", base_received, quote_received);
+                    return Err(ProgramError::InsufficientFunds);
+                }
+            };
+        
+        msg!("📊 Calculated LP tokens: {}", lp_tokens);
+        
+        cook_venue.mint_liquidity(program_id, token_mint.key, lp_token_mint, user_lp_token_account, token_program, lp_tokens)?;
+        cook_venue.set_total_liquidity(new_total_liquidity)?;
+        
+        crate::events::emit_liquidity_added_event(token_mint.key, user.key, quote_received, base_received, lp_tokens);
         
         msg!("✅ AddCookLiquidity completed: {} LP tokens minted to user", lp_tokens);
         Ok(())
     }
 
-    fn process_remove_cook_liquidity(_program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::RemoveLiquidityArgs) -> ProgramResult {
+    fn process_remove_cook_liquidity<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::RemoveLiquidityArgs) -> ProgramResult {
         msg!("Processing RemoveCookLiquidity instruction");
         
-        if accounts.len() < 5 {
+        if accounts.len() < 14 {
+            msg!("❌ Error: Not enough account keys provided. Expected: 14, Got: {}", accounts.len());
+            msg!("  Required accounts: user, token_mint, amm_account, user_token_account, user_quote_account, lp_token_mint, token_program, quote_token_mint, amm_base, user_lp_token_account, amm_quote, quote_token_program, cook_data, launch_data");
             return Err(ProgramError::NotEnoughAccountKeys);
         }
         
         let user = &accounts[0];
-        let _token_mint = &accounts[1];
-        let _amm_account = &accounts[2];
-        let _user_token_account = &accounts[3];
-        let _user_sol_account = &accounts[4];
+        let token_mint = &accounts[1];
+        let amm_account = &accounts[2];
+        let user_token_account = &accounts[3]; // User's base token account
+        let user_quote_account = &accounts[4]; // User's WSOL account
+        let lp_token_mint = &accounts[5];
+        let token_program = &accounts[6]; // Base token and LP token program
+        let quote_token_mint = &accounts[7];
+        let amm_base = &accounts[8];
+        let user_lp_token_account = &accounts[9];
+        let amm_quote = &accounts[10];
+        let quote_token_program = &accounts[11];
+        let cook_data = &accounts[12];
+        let launch_data = &accounts[13];
         
         if !user.is_signer {
+            msg!("❌ Error: User must be a signer");
             return Err(ProgramError::MissingRequiredSignature);
         }
         
+        common::check_not_paused(program_id, Some(cook_data))?;
+        
+        // the SOL in a bonding curve's vaults belongs to the curve until it graduates
+        let launch_layout = Self::load_swap_launch(program_id, launch_data, token_mint.key)?;
+        common::check_launch_not_paused(launch_data)?;
+        if Self::is_on_bonding_curve(&launch_layout, launch_data)? {
+            msg!("❌ Error: Launch is on its bonding curve, liquidity can be removed once it graduates");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
         let lp_tokens_to_burn = args.amount;
         
         if lp_tokens_to_burn == 0 {
+            msg!("❌ Error: LP token amount is zero");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        
+        accounts::check_wrapped_sol_key(quote_token_mint)?;
+        if token_mint.owner != token_program.key || lp_token_mint.owner != token_program.key || quote_token_mint.owner != quote_token_program.key {
+            msg!("❌ Error: Token program does not own mint");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        let cook_venue = crate::amm::CookVenue {
+            amm: amm_account,
+            amm_base,
+            amm_quote,
+        };
+        let (reserve_base, reserve_quote) = cook_venue.checked_reserves(program_id, token_mint.key)?;
+        
+        let (expected_lp_mint, _) = Pubkey::find_program_address(&[&amm_account.key.to_bytes(), b"LP"], program_id);
+        if lp_token_mint.key != &expected_lp_mint {
+            msg!("❌ Error: Expected LP mint {}", expected_lp_mint);
             return Err(ProgramError::InvalidAccountData);
         }
         
-        msg!("RemoveCookLiquidity: LP tokens burned: {}", lp_tokens_to_burn);
+        let (lp_supply, lp_decimals) = {
+            let lp_mint_data = lp_token_mint.try_borrow_data()?;
+            let lp_mint = spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&lp_mint_data)?;
+            (lp_mint.base.supply, lp_mint.base.decimals)
+        };
+        // The locked minimum liquidity is part of the supply but can never be burned
+        let total_liquidity = cook_venue.total_liquidity()?.max(lp_supply);
+        
+        let (base_out, quote_out) = match crate::amm::get_withdraw_amounts(lp_tokens_to_burn, total_liquidity, reserve_base, reserve_quote) {
+            Some(amounts) => amounts,
+            None => {
+                msg!("❌ Error: Cannot burn {} LP tokens out of a supply of {}", lp_tokens_to_burn, lp_supply);
+                return Err(ProgramError::InsufficientFunds);
+            }
+        };
+        
+        // Slippage is checked on what actually lands in the user's accounts
+        let tokens_received = token::get_amount_post_transfer_fee(base_out, token_mint)?;
+        let sol_received = token::get_amount_post_transfer_fee(quote_out, quote_token_mint)?;
+        
+        msg!("💰 Removing liquidity: {} LP tokens for {} tokens, {} SOL", lp_tokens_to_burn, tokens_received, sol_received);
+        
+        if tokens_received < args.min_base_out || sol_received < args.min_quote_out {
+            msg!("❌ Error: Slippage exceeded. Received {} tokens (min {}), {} SOL (min {})", tokens_received, args.min_base_out, sol_received, args.min_quote_out);
+            return Err(ProgramError::Custom(1));
+        }
+        
+        invoke(
+            &spl_token_2022::instruction::burn_checked(
+                token_program.key,
+                user_lp_token_account.key,
+                lp_token_mint.key,
+                user.key,
+                &[],
+                lp_tokens_to_burn,
+                lp_decimals,
+            )?,
+            &[user_lp_token_account.clone(), lp_token_mint.clone(), user.clone(), token_program.clone()],
+        )?;
+        msg!("✅ Burned {} LP tokens", lp_tokens_to_burn);
+        cook_venue.set_total_liquidity(total_liquidity - lp_tokens_to_burn)?;
+        
+        cook_venue.withdraw(
+            program_id,
+            token_mint,
+            quote_token_mint,
+            user_token_account,
+            user_quote_account,
+            token_program,
+            quote_token_program,
+            base_out,
+            quote_out,
+        )?;
+        
+        crate::events::emit_liquidity_removed_event(token_mint.key, user.key, lp_tokens_to_burn, sol_received, tokens_received);
+        
+        msg!("✅ RemoveCookLiquidity completed: {} tokens and {} SOL returned to user", tokens_received, sol_received);
         Ok(())
     }
 
@@ -2828,120 +3234,10 @@ impl Processor {
         Ok(())
     }
 
-    fn process_create_instant_launch(_program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::InstantLaunchArgs) -> ProgramResult {
-        msg!("Processing CreateInstantLaunch instruction");
-        msg!("Name: {}", args.name);
-        msg!("Symbol: {}", args.symbol);
-        msg!("Total supply: {}", args.total_supply);
-        
-        // Validate we have enough accounts
-        if accounts.len() < 10 {
-            msg!("❌ Error: Not enough account keys provided. Expected: 10+, Got: {}", accounts.len());
-            return Err(ProgramError::NotEnoughAccountKeys);
-        }
-
-        let user = &accounts[0];
-        let listing = &accounts[1];
-        let launch_data = &accounts[2];
-        let quote_token_mint = &accounts[3];
-        let launch_quote = &accounts[4];
-        let _cook_data = &accounts[5];
-        let _cook_pda = &accounts[6];
-        let base_token_mint = &accounts[7];
-        let _cook_base_token = &accounts[8];
-        let _team = &accounts[9];
-
-        // Verify user is signer
-        if !user.is_signer {
-            msg!("❌ Error: User must be a signer");
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        // Verify accounts are writable where needed
-        if !listing.is_writable {
-            msg!("❌ Error: Listing account must be writable");
-            return Err(ProgramError::InvalidAccountData);
-        }
-        
-        if !launch_data.is_writable {
-            msg!("❌ Error: Launch data account must be writable");
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        // Create launch data structure for instant launch
-        let current_time = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-        let launch_data_struct = crate::state::LaunchData {
-            account_type: crate::state::AccountType::Launch,
-            launch_meta: match args.launch_type {
-                0 => crate::state::LaunchMeta::Raffle,
-                1 => crate::state::LaunchMeta::FCFS,
-                2 => crate::state::LaunchMeta::IDO { token_fraction_distributed: 0, tokens_distributed: 0 },
-                _ => crate::state::LaunchMeta::FCFS, // Default to FCFS for instant launches
-            },
-            plugins: vec![],
-            last_interaction: current_time,
-            num_interactions: 0,
-            page_name: args.page_name.clone(),
-            listing: listing.key.to_string(),
-            total_supply: args.total_supply,
-            num_mints: 1000, // Default to 1000 tickets for instant launch
-            ticket_price: args.ticket_price,
-            minimum_liquidity: 0,
-            launch_date: current_time,
-            end_date: current_time + (24 * 60 * 60), // 24 hours from now
-            tickets_sold: 0,
-            ticket_claimed: 0,
-            mints_won: 0,
-            buffer1: args.amm_provider as u64,
-            buffer2: args.transfer_fee as u64,
-            buffer3: args.max_transfer_fee,
-            distribution: vec![],
-            flags: vec![args.launch_type],
-            strings: vec![
-                args.name.clone(),
-                args.symbol.clone(),
-                args.uri.clone(),
-                args.icon.clone(),
-                args.banner.clone(),
-                match args.launch_type {
-                    0 => "raffle".to_string(),
-                    1 => "instant".to_string(),
-                    2 => "ido".to_string(),
-                    _ => "instant".to_string(),
-                },
-            ],
-            keys: vec![
-                base_token_mint.key.to_string(),
-                quote_token_mint.key.to_string(),
-                launch_quote.key.to_string(),
-            ],
-            creator: *user.key,
-            upvotes: 0,
-            downvotes: 0,
-            is_tradable: true, // Instant launches are immediately tradable
-            tokens_sold: 0, // Start with 0 tokens sold (pump.fun bonding curve)
-            is_graduated: false, // Not graduated yet (using bonding curve)
-            graduation_threshold: 30_000_000_000u64, // 30 SOL threshold for Raydium liquidity creation
-        };
-
-        // Serialize and write launch data to account
-        let serialized_data = borsh::to_vec(&launch_data_struct)?;
-        launch_data.try_borrow_mut_data()?[..serialized_data.len()].copy_from_slice(&serialized_data);
-        
-        msg!("✅ CreateInstantLaunch processed successfully");
-        msg!("Launch name: {}", args.name);
-        msg!("Launch symbol: {}", args.symbol);
-        msg!("Ticket price: {}", args.ticket_price);
-        msg!("Total supply: {}", args.total_supply);
-        msg!("Launch data account initialized");
-        
-        Ok(())
-    }
-
-    fn process_add_trade_rewards(_program_id: &Pubkey, _accounts: &[AccountInfo], args: crate::instruction::AddRewardsArgs) -> ProgramResult {
+    fn process_add_trade_rewards<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::AddRewardsArgs) -> ProgramResult {
         msg!("Processing AddTradeRewards instruction");
         msg!("Amount: {}", args.amount);
-        Ok(())
+        crate::trade_to_earn::add_trade_rewards(program_id, accounts, args)
     }
 
     fn process_list_nft(_program_id: &Pubkey, _accounts: &[AccountInfo], args: crate::instruction::ListNFTArgs) -> ProgramResult {
@@ -2990,7 +3286,7 @@ impl Processor {
         }
 
         // Load existing launch data
-        let mut launch_data = crate::state::LaunchData::try_from_slice(&raffle_account.data.borrow())?;
+        let mut launch_data = crate::launch::LaunchData::load(&raffle_account.data.borrow())?;
         
         // Update the image fields
         if !args.icon.is_empty() {
@@ -3031,15 +3327,28 @@ impl Processor {
     }
 
     // Jupiter-like aggregator swap function
-    fn process_best_price_swap(program_id: &Pubkey, accounts: &[AccountInfo], args: crate::instruction::BestPriceSwapArgs) -> ProgramResult {
+    // BestPriceSwap accounts:
+    // 0 user, 1 input_mint, 2 output_mint, 3 user_input_account, 4 user_output_account,
+    // 5 launch_data, 6 input_token_program, 7 output_token_program,
+    // 8..11 Cook AMM venue (amm, amm_base, amm_quote),
+    // 11..18 optional Raydium CPMM venue (program, authority, amm_config, pool_state, input_vault, output_vault, observation_state)
+    fn process_best_price_swap<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::BestPriceSwapArgs) -> ProgramResult {
         msg!("🔄 Processing BestPriceSwap instruction");
         msg!("Input mint: {}", args.input_mint);
         msg!("Output mint: {}", args.output_mint);
         msg!("Amount in: {}", args.amount_in);
         msg!("Minimum amount out: {}", args.minimum_amount_out);
         
-        if accounts.len() < 8 {
-            msg!("❌ Error: Not enough account keys provided. Expected: 8+, Got: {}", accounts.len());
+        if !args.legs.is_empty() {
+            return Self::process_route_swap(program_id, accounts, &args);
+        }
+        
+        // the program data is at index 8, ahead of the venues
+        const COOK_VENUE_START: usize = 9;
+        const RAYDIUM_VENUE_START: usize = COOK_VENUE_START + crate::amm::CookVenue::NUM_ACCOUNTS;
+        
+        if accounts.len() < RAYDIUM_VENUE_START {
+            msg!("❌ Error: Not enough account keys provided. Expected: {}+, Got: {}", RAYDIUM_VENUE_START, accounts.len());
             return Err(ProgramError::NotEnoughAccountKeys);
         }
 
@@ -3049,55 +3358,108 @@ impl Processor {
         let user_input_account = &accounts[3];
         let user_output_account = &accounts[4];
         let launch_data = &accounts[5];
-        let token_program = &accounts[6];
-        let system_program = &accounts[7];
+        let input_token_program = &accounts[6];
+        let output_token_program = &accounts[7];
+        let cook_data = &accounts[8];
 
         if !user.is_signer {
             msg!("❌ Error: User must be a signer");
             return Err(ProgramError::MissingRequiredSignature);
         }
 
+        common::check_not_paused(program_id, Some(cook_data))?;
+
+        if input_mint.key != &args.input_mint || output_mint.key != &args.output_mint {
+            msg!("❌ Error: Mint accounts do not match instruction args");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if input_mint.owner != input_token_program.key || output_mint.owner != output_token_program.key {
+            msg!("❌ Error: Token program does not own mint");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // The launch is for whichever side of the pair is not wrapped SOL
+        let wsol_mint = accounts::wrapped_sol_mint_account::ID;
+        let launch_mint = if *input_mint.key == wsol_mint { output_mint.key } else { input_mint.key };
+        let launch_layout = Self::load_swap_launch(program_id, launch_data, launch_mint)?;
+        common::check_launch_not_paused(launch_data)?;
+
         // Check if launch is tradable (trading gate enforcement)
         if !Self::is_launch_tradable(launch_data)? {
             msg!("❌ Error: Token is not yet tradable. Raffle must graduate first.");
             return Err(ProgramError::InvalidAccountData);
         }
 
+        // Bonding curve launches are priced by the curve, not the vault ratio, so their Cook pool is only tradable via SwapCookAMMV2
+        let on_bonding_curve = Self::is_on_bonding_curve(&launch_layout, launch_data)?;
+
+        let cook_venue = if on_bonding_curve {
+            msg!("ℹ️ Launch is on its bonding curve, skipping CookDEX");
+            None
+        } else {
+            Some(crate::amm::CookVenue::from_accounts(&accounts[COOK_VENUE_START..])?)
+        };
+        let raydium_venue = if accounts.len() >= RAYDIUM_VENUE_START + crate::raydium::RaydiumVenue::NUM_ACCOUNTS {
+            Some(crate::raydium::RaydiumVenue::from_accounts(&accounts[RAYDIUM_VENUE_START..])?)
+        } else {
+            None
+        };
+
         // Find best route using aggregator
-        let input_mint_pubkey = input_mint.key;
-        let output_mint_pubkey = output_mint.key;
-        
         let (best_route, estimated_output) = Self::find_best_route(
-            &input_mint_pubkey,
-            &output_mint_pubkey,
+            program_id,
+            input_mint,
+            output_mint,
             args.amount_in,
-            accounts,
+            cook_venue.as_ref(),
+            raydium_venue.as_ref(),
         )?;
 
         msg!("🎯 Best route found: {} (estimated output: {})", best_route, estimated_output);
 
-        // Apply slippage protection
+        // Apply slippage protection, the tighter of the caller's minimum and the slippage from the quote
         let slippage_amount = (estimated_output as u128 * args.slippage_bps as u128) / 10000;
-        let minimum_output_with_slippage = estimated_output - slippage_amount as u64;
+        let minimum_output_with_slippage = std::cmp::max(estimated_output - slippage_amount as u64, args.minimum_amount_out);
         
-        if minimum_output_with_slippage < args.minimum_amount_out {
-            msg!("❌ Error: Slippage too high. Expected min: {}, Got: {}", args.minimum_amount_out, minimum_output_with_slippage);
-            return Err(ProgramError::InvalidAccountData);
+        if estimated_output < args.minimum_amount_out {
+            msg!("❌ Error: Slippage too high. Expected min: {}, Got: {}", args.minimum_amount_out, estimated_output);
+            return Err(ProgramError::Custom(1));
         }
 
+        let output_before = token::get_token_balance(user_output_account);
+
         // Execute swap based on best route
-        match best_route {
-            0 => {
+        match (best_route, cook_venue, raydium_venue) {
+            (0, Some(cook_venue), _) => {
                 msg!("🔄 Executing CookDEX swap");
-                Self::execute_cook_dex_swap(user, input_mint, output_mint, user_input_account, user_output_account, args.amount_in, minimum_output_with_slippage, token_program, system_program)?;
+                Self::execute_cook_dex_swap(
+                    program_id,
+                    &cook_venue,
+                    user,
+                    input_mint,
+                    output_mint,
+                    user_input_account,
+                    user_output_account,
+                    input_token_program,
+                    output_token_program,
+                    args.amount_in,
+                )?;
             },
-            1 => {
+            (1, _, Some(raydium_venue)) => {
                 msg!("🔄 Executing RaydiumDEX swap");
-                Self::execute_raydium_dex_swap(user, accounts, args.amount_in, minimum_output_with_slippage)?;
-            },
-            2 => {
-                msg!("🔄 Executing Jupiter swap");
-                Self::execute_jupiter_swap(user, accounts, args.amount_in, minimum_output_with_slippage)?;
+                Self::execute_raydium_dex_swap(
+                    &raydium_venue,
+                    user,
+                    input_mint,
+                    output_mint,
+                    user_input_account,
+                    user_output_account,
+                    input_token_program,
+                    output_token_program,
+                    args.amount_in,
+                    minimum_output_with_slippage,
+                )?;
             },
             _ => {
                 msg!("❌ Error: Invalid route selected");
@@ -3105,91 +3467,303 @@ impl Processor {
             }
         }
 
-        msg!("✅ BestPriceSwap completed successfully");
+        // Enforce the minimum on what actually arrived in the user's account
+        let delivered = token::get_token_balance(user_output_account).saturating_sub(output_before);
+        if delivered < minimum_output_with_slippage {
+            msg!("❌ Error: Delivered {} below minimum {}", delivered, minimum_output_with_slippage);
+            return Err(ProgramError::Custom(1));
+        }
+
+        msg!("✅ BestPriceSwap completed successfully: {} in, {} out", args.amount_in, delivered);
+        Ok(())
+    }
+
+    // BestPriceSwap with an explicit route (split and/or two-hop). Accounts:
+    // 0 user, 1 input_mint, 2 output_mint, 3 user_input_account, 4 user_output_account,
+    // 5 input_token_program, 6 output_token_program,
+    // two-hop only: 7 intermediate_mint (WSOL), 8 user_intermediate_account, 9 intermediate_token_program,
+    // then one group per leg, in leg order: launch_data followed by the venue accounts
+    fn process_route_swap<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: &crate::instruction::BestPriceSwapArgs) -> ProgramResult {
+        const MAX_ROUTE_LEGS: usize = 4;
+        
+        if args.legs.len() > MAX_ROUTE_LEGS {
+            msg!("❌ Error: At most {} route legs are supported", MAX_ROUTE_LEGS);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        
+        // Legs must be grouped by hop and each hop's shares must cover its whole input
+        let is_two_hop = args.legs.iter().any(|leg| leg.hop == 1);
+        let mut previous_hop = 0u8;
+        for leg in &args.legs {
+            if leg.hop > 1 || leg.hop < previous_hop || leg.venue > 1 || leg.amount_bps == 0 {
+                msg!("❌ Error: Invalid route leg {:?}", leg);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            previous_hop = leg.hop;
+        }
+        for hop in 0..=(is_two_hop as u8) {
+            let total_bps: u32 = args.legs.iter().filter(|leg| leg.hop == hop).map(|leg| leg.amount_bps as u32).sum();
+            if total_bps != 10000 {
+                msg!("❌ Error: Hop {} legs cover {} bps, expected 10000", hop, total_bps);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        }
+        
+        // user, mints, token accounts, token programs and the program data, then the WSOL hop's accounts on two-hop routes
+        let header_len = if is_two_hop { 11 } else { 8 };
+        if accounts.len() < header_len {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        
+        let user = &accounts[0];
+        let input_mint = &accounts[1];
+        let output_mint = &accounts[2];
+        let user_input_account = &accounts[3];
+        let user_output_account = &accounts[4];
+        let input_token_program = &accounts[5];
+        let output_token_program = &accounts[6];
+        let cook_data = &accounts[7];
+        
+        if !user.is_signer {
+            msg!("❌ Error: User must be a signer");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        
+        common::check_not_paused(program_id, Some(cook_data))?;
+        
+        if input_mint.key != &args.input_mint || output_mint.key != &args.output_mint {
+            msg!("❌ Error: Mint accounts do not match instruction args");
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        if input_mint.owner != input_token_program.key || output_mint.owner != output_token_program.key {
+            msg!("❌ Error: Token program does not own mint");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        
+        // Two-hop routes go token -> SOL -> token through the user's WSOL account
+        let intermediate = if is_two_hop {
+            let intermediate_mint = &accounts[8];
+            let user_intermediate_account = &accounts[9];
+            let intermediate_token_program = &accounts[10];
+            accounts::check_wrapped_sol_key(intermediate_mint)?;
+            if intermediate_mint.owner != intermediate_token_program.key {
+                msg!("❌ Error: Token program does not own intermediate mint");
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            if input_mint.key == intermediate_mint.key || output_mint.key == intermediate_mint.key {
+                msg!("❌ Error: Two-hop routes must start and end on launch tokens");
+                return Err(ProgramError::InvalidArgument);
+            }
+            Some((intermediate_mint, user_intermediate_account, intermediate_token_program))
+        } else {
+            None
+        };
+        
+        let output_before = token::get_token_balance(user_output_account);
+        let mut next_account = header_len;
+        let mut hop_input = args.amount_in;
+        
+        for hop in 0..=(is_two_hop as u8) {
+            // accounts for the side of each hop that the legs trade between
+            let (leg_input_mint, leg_user_input, leg_input_program, leg_output_mint, leg_user_output, leg_output_program) =
+                match (hop, intermediate) {
+                    (0, Some((mid_mint, user_mid, mid_program))) => {
+                        (input_mint, user_input_account, input_token_program, mid_mint, user_mid, mid_program)
+                    }
+                    (_, Some((mid_mint, user_mid, mid_program))) => {
+                        (mid_mint, user_mid, mid_program, output_mint, user_output_account, output_token_program)
+                    }
+                    _ => (input_mint, user_input_account, input_token_program, output_mint, user_output_account, output_token_program),
+                };
+            
+            let hop_legs: Vec<&crate::instruction::RouteLeg> = args.legs.iter().filter(|leg| leg.hop == hop).collect();
+            let hop_output_before = token::get_token_balance(leg_user_output);
+            let mut remaining_input = hop_input;
+            
+            for (index, leg) in hop_legs.iter().enumerate() {
+                // the last leg takes the remainder so rounding never strands input
+                let leg_amount_in = if index + 1 == hop_legs.len() {
+                    remaining_input
+                } else {
+                    ((hop_input as u128 * leg.amount_bps as u128) / 10000) as u64
+                };
+                remaining_input -= leg_amount_in;
+                
+                let venue_len = if leg.venue == 0 {
+                    crate::amm::CookVenue::NUM_ACCOUNTS
+                } else {
+                    crate::raydium::RaydiumVenue::NUM_ACCOUNTS
+                };
+                if accounts.len() < next_account + 1 + venue_len {
+                    msg!("❌ Error: Missing accounts for hop {} leg {}", hop, index);
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                }
+                let launch_data = &accounts[next_account];
+                let venue_accounts = &accounts[next_account + 1..next_account + 1 + venue_len];
+                next_account += 1 + venue_len;
+                
+                // Every leg trades a launch token against SOL or another pair on Raydium, check its launch
+                let leg_launch_mint = if accounts::wrapped_sol_mint_account::ID == *leg_input_mint.key {
+                    leg_output_mint.key
+                } else {
+                    leg_input_mint.key
+                };
+                let launch_layout = Self::load_swap_launch(program_id, launch_data, leg_launch_mint)?;
+                common::check_launch_not_paused(launch_data)?;
+                if !Self::is_launch_tradable(launch_data)? {
+                    msg!("❌ Error: Token {} is not yet tradable. Raffle must graduate first.", leg_launch_mint);
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                
+                let leg_output_before = token::get_token_balance(leg_user_output);
+                match leg.venue {
+                    0 => {
+                        if Self::is_on_bonding_curve(&launch_layout, launch_data)? {
+                            msg!("❌ Error: Token {} is on its bonding curve, use SwapCookAMMV2", leg_launch_mint);
+                            return Err(ProgramError::InvalidAccountData);
+                        }
+                        
+                        let cook_venue = crate::amm::CookVenue::from_accounts(venue_accounts)?;
+                        msg!("🔄 Hop {} leg {}: CookDEX {} in", hop, index, leg_amount_in);
+                        Self::execute_cook_dex_swap(
+                            program_id,
+                            &cook_venue,
+                            user,
+                            leg_input_mint,
+                            leg_output_mint,
+                            leg_user_input,
+                            leg_user_output,
+                            leg_input_program,
+                            leg_output_program,
+                            leg_amount_in,
+                        )?;
+                    }
+                    _ => {
+                        let raydium_venue = crate::raydium::RaydiumVenue::from_accounts(venue_accounts)?;
+                        // quoting checks every venue account against the pool state
+                        let now = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+                        raydium_venue.quote(leg_input_mint, leg_output_mint, leg_amount_in, now)?;
+                        msg!("🔄 Hop {} leg {}: RaydiumDEX {} in", hop, index, leg_amount_in);
+                        Self::execute_raydium_dex_swap(
+                            &raydium_venue,
+                            user,
+                            leg_input_mint,
+                            leg_output_mint,
+                            leg_user_input,
+                            leg_user_output,
+                            leg_input_program,
+                            leg_output_program,
+                            leg_amount_in,
+                            leg.minimum_amount_out,
+                        )?;
+                    }
+                }
+                
+                let leg_delivered = token::get_token_balance(leg_user_output).saturating_sub(leg_output_before);
+                if leg_delivered < leg.minimum_amount_out {
+                    msg!("❌ Error: Hop {} leg {} delivered {} below minimum {}", hop, index, leg_delivered, leg.minimum_amount_out);
+                    return Err(ProgramError::Custom(1));
+                }
+            }
+            
+            // the next hop trades exactly what this hop delivered
+            hop_input = token::get_token_balance(leg_user_output).saturating_sub(hop_output_before);
+        }
+        
+        if next_account != accounts.len() {
+            msg!("❌ Error: {} unexpected accounts after route legs", accounts.len() - next_account);
+            return Err(ProgramError::InvalidArgument);
+        }
+        
+        let delivered = token::get_token_balance(user_output_account).saturating_sub(output_before);
+        if delivered < args.minimum_amount_out {
+            msg!("❌ Error: Delivered {} below minimum {}", delivered, args.minimum_amount_out);
+            return Err(ProgramError::Custom(1));
+        }
+        
+        msg!("✅ Route swap completed: {} legs, {} in, {} out", args.legs.len(), args.amount_in, delivered);
         Ok(())
     }
 
-    // Execute CookDEX swap
+    // Execute CookDEX swap against the pool vaults
+    #[allow(clippy::too_many_arguments)]
     fn execute_cook_dex_swap<'a>(
+        program_id: &Pubkey,
+        cook_venue: &crate::amm::CookVenue<'a, '_>,
         user: &AccountInfo<'a>,
         input_mint: &AccountInfo<'a>,
         output_mint: &AccountInfo<'a>,
         user_input_account: &AccountInfo<'a>,
         user_output_account: &AccountInfo<'a>,
+        input_token_program: &AccountInfo<'a>,
+        output_token_program: &AccountInfo<'a>,
         amount_in: u64,
-        minimum_amount_out: u64,
-        token_program: &AccountInfo<'a>,
-        system_program: &AccountInfo<'a>,
     ) -> ProgramResult {
-        // Simplified CookDEX swap execution
-        // In a real implementation, this would interact with the CookDEX AMM
-        
-        // Calculate output amount using sqrt formula
-        let tokens_out = ((amount_in as f64) * 1000000.0).sqrt() as u64;
-        
-        if tokens_out < minimum_amount_out {
-            msg!("❌ Error: Output amount {} below minimum {}", tokens_out, minimum_amount_out);
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        // Mint tokens to user (simplified - would need proper AMM interaction)
-        let mint_instruction = token_instruction::mint_to(
-            token_program.key,
-            output_mint.key,
-            user_output_account.key,
-            user.key,
-            &[],
-            tokens_out,
-        )?;
-
-        invoke_signed(
-            &mint_instruction,
-            &[
-                output_mint.clone(),
-                user_output_account.clone(),
-                user.clone(),
-                token_program.clone(),
-            ],
-            &[],
+        let tokens_out = cook_venue.swap(
+            program_id,
+            user,
+            input_mint,
+            output_mint,
+            user_input_account,
+            user_output_account,
+            input_token_program,
+            output_token_program,
+            amount_in,
         )?;
 
-        msg!("✅ CookDEX swap executed: {} tokens minted", tokens_out);
+        msg!("✅ CookDEX swap executed: {} in, {} out", amount_in, tokens_out);
         Ok(())
     }
 
-    // Execute RaydiumDEX swap
-    fn execute_raydium_dex_swap(
-        user: &AccountInfo,
-        accounts: &[AccountInfo],
+    // Execute RaydiumDEX swap via CPI to the CPMM program
+    #[allow(clippy::too_many_arguments)]
+    fn execute_raydium_dex_swap<'a>(
+        raydium_venue: &crate::raydium::RaydiumVenue<'a, '_>,
+        user: &AccountInfo<'a>,
+        input_mint: &AccountInfo<'a>,
+        output_mint: &AccountInfo<'a>,
+        user_input_account: &AccountInfo<'a>,
+        user_output_account: &AccountInfo<'a>,
+        input_token_program: &AccountInfo<'a>,
+        output_token_program: &AccountInfo<'a>,
         amount_in: u64,
         minimum_amount_out: u64,
     ) -> ProgramResult {
-        // Simplified RaydiumDEX swap execution
-        // In a real implementation, this would use proper Raydium CPI
-        
-        msg!("🔄 Executing RaydiumDEX swap (simplified)");
-        msg!("Amount in: {}, Minimum out: {}", amount_in, minimum_amount_out);
-        
-        // For now, just log the swap - would need actual Raydium integration
-        msg!("✅ RaydiumDEX swap executed (placeholder)");
-        Ok(())
-    }
+        let swap_instruction = raydium_venue.swap_base_input_instruction(
+            user.key,
+            user_input_account.key,
+            user_output_account.key,
+            input_token_program.key,
+            output_token_program.key,
+            input_mint.key,
+            output_mint.key,
+            amount_in,
+            minimum_amount_out,
+        );
 
-    // Execute Jupiter swap
-    fn execute_jupiter_swap(
-        user: &AccountInfo,
-        accounts: &[AccountInfo],
-        amount_in: u64,
-        minimum_amount_out: u64,
-    ) -> ProgramResult {
-        // Simplified Jupiter swap execution
-        // In a real implementation, this would use Jupiter API
-        
-        msg!("🔄 Executing Jupiter swap (simplified)");
-        msg!("Amount in: {}, Minimum out: {}", amount_in, minimum_amount_out);
-        
-        // For now, just log the swap - would need actual Jupiter integration
-        msg!("✅ Jupiter swap executed (placeholder)");
+        invoke(
+            &swap_instruction,
+            &[
+                user.clone(),
+                raydium_venue.authority.clone(),
+                raydium_venue.amm_config.clone(),
+                raydium_venue.pool_state.clone(),
+                user_input_account.clone(),
+                user_output_account.clone(),
+                raydium_venue.input_vault.clone(),
+                raydium_venue.output_vault.clone(),
+                input_token_program.clone(),
+                output_token_program.clone(),
+                input_mint.clone(),
+                output_mint.clone(),
+                raydium_venue.observation_state.clone(),
+                raydium_venue.program.clone(),
+            ],
+        )?;
+
+        msg!("✅ RaydiumDEX swap executed: {} in, minimum {} out", amount_in, minimum_amount_out);
         Ok(())
     }
+
 }
\ No newline at end of file
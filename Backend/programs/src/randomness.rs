@@ -0,0 +1,329 @@
+/**
+ * Pluggable randomness for raffles and NFT draws.
+ *
+ * Consumers hand `load_provider` the randomness account they were given together with what that
+ * randomness has to be bound to, and read 64 bytes through the returned `RandomnessProvider`.
+ * The source is picked from the account:
+ *  - Orao VRF: an Orao owned request account, which must be the request the user committed to
+ *    and must be fulfilled (see orao.rs).
+ *  - Commit-reveal: for networks without Orao such as Eclipse. The randomness authority commits
+ *    sha256(secret) for a launch with CommitRandomness before any tickets are sold, and reveals
+ *    the secret with RevealRandomness once the launch has ended. The account lives at
+ *    [launch, b"Randomness"] and every consumer mixes the secret with its own key.
+ *  - Deterministic: derived from the launch and consumer keys alone. Only compiled into tests and
+ *    builds with the `test-randomness` feature, where it is selected by passing the system program.
+ */
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, hash::hashv, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{accounts, launch, orao, state::AccountType, utils};
+
+pub const COMMIT_REVEAL_SEED: &[u8] = b"Randomness";
+
+/// Commit-reveal randomness of a launch
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct CommitReveal {
+    pub account_type: AccountType,
+    pub launch: Pubkey,
+    pub authority: Pubkey,
+    pub commitment: [u8; 32],
+    pub secret: [u8; 32],
+    pub revealed: bool,
+}
+
+impl CommitReveal {
+    pub const LEN: usize = 1 + 32 + 32 + 32 + 32 + 1;
+}
+
+/// What a consumer's randomness has to be bound to
+pub struct RandomnessRequest {
+    /// Orao request address the consumer committed to
+    pub orao_request: Pubkey,
+    /// launch the commit-reveal randomness belongs to
+    pub launch: Pubkey,
+    /// key the shared commit-reveal secret is mixed with, so consumers get independent randomness
+    pub consumer: Pubkey,
+}
+
+pub trait RandomnessProvider {
+    /// 64 bytes of randomness, failing until the source has produced them
+    fn randomness(&self) -> Result<[u8; 64], ProgramError>;
+}
+
+pub struct OraoProvider<'a, 'b> {
+    pub request: &'a AccountInfo<'b>,
+    pub expected_address: Pubkey,
+}
+
+impl RandomnessProvider for OraoProvider<'_, '_> {
+    fn randomness(&self) -> Result<[u8; 64], ProgramError> {
+        orao::get_fulfilled_randomness(self.request, &self.expected_address)
+    }
+}
+
+pub struct CommitRevealProvider {
+    pub commit_reveal: CommitReveal,
+    pub consumer: Pubkey,
+}
+
+impl RandomnessProvider for CommitRevealProvider {
+    fn randomness(&self) -> Result<[u8; 64], ProgramError> {
+        if !self.commit_reveal.revealed {
+            msg!("Randomness for launch {} has not been revealed yet", self.commit_reveal.launch);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(expand_randomness(&self.commit_reveal.secret, &self.consumer))
+    }
+}
+
+#[cfg(any(test, feature = "test-randomness"))]
+pub struct DeterministicProvider {
+    pub seed: [u8; 32],
+    pub consumer: Pubkey,
+}
+
+#[cfg(any(test, feature = "test-randomness"))]
+impl RandomnessProvider for DeterministicProvider {
+    fn randomness(&self) -> Result<[u8; 64], ProgramError> {
+        Ok(expand_randomness(&self.seed, &self.consumer))
+    }
+}
+
+/// 64 bytes of randomness for `consumer` from a shared 32 byte secret
+pub fn expand_randomness(secret: &[u8; 32], consumer: &Pubkey) -> [u8; 64] {
+    let mut randomness = [0u8; 64];
+    randomness[..32].copy_from_slice(hashv(&[secret, consumer.as_ref(), &[0]]).as_ref());
+    randomness[32..].copy_from_slice(hashv(&[secret, consumer.as_ref(), &[1]]).as_ref());
+    randomness
+}
+
+pub fn get_commit_reveal_address(launch: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&launch.to_bytes(), COMMIT_REVEAL_SEED], program_id)
+}
+
+/// The randomness source behind `account`
+pub fn load_provider<'a, 'b>(
+    program_id: &Pubkey,
+    account: &'a AccountInfo<'b>,
+    request: &RandomnessRequest,
+) -> Result<Box<dyn RandomnessProvider + 'a>, ProgramError> {
+    if account.owner == &accounts::orao_program::ID {
+        return Ok(Box::new(OraoProvider {
+            request: account,
+            expected_address: request.orao_request,
+        }));
+    }
+
+    #[cfg(feature = "test-randomness")]
+    if account.key == &solana_program::system_program::ID {
+        return Ok(Box::new(DeterministicProvider {
+            seed: request.launch.to_bytes(),
+            consumer: request.consumer,
+        }));
+    }
+
+    if account.owner == program_id {
+        let (expected_address, _) = get_commit_reveal_address(&request.launch, program_id);
+        if account.key != &expected_address {
+            msg!("expected commit-reveal randomness {} {}", expected_address, account.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let commit_reveal = CommitReveal::try_from_slice(&account.try_borrow_data()?)?;
+        if commit_reveal.account_type != AccountType::RandomnessCommit {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        return Ok(Box::new(CommitRevealProvider {
+            commit_reveal,
+            consumer: request.consumer,
+        }));
+    }
+
+    msg!("Account {} is not a randomness source", account.key);
+    Err(ProgramError::InvalidAccountData)
+}
+
+fn check_authority(authority: &AccountInfo, expected: &Pubkey) -> ProgramResult {
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if authority.key != expected {
+        msg!("expected randomness authority {} {}", expected, authority.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// CommitRandomness: the randomness authority commits to sha256(secret) for a launch that has not sold any tickets yet.
+/// Accounts: 0 authority, 1 launch_data, 2 randomness, 3 system_program
+pub fn commit_randomness<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::CommitRandomnessArgs) -> ProgramResult {
+    if accounts.len() < 4 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let authority = &accounts[0];
+    let launch_data = &accounts[1];
+    let randomness = &accounts[2];
+    let system_program = &accounts[3];
+
+    check_authority(authority, &accounts::randomness_authority::ID)?;
+    accounts::check_system_program_key(system_program)?;
+
+    if launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    {
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = launch::LaunchAccessor::load(&launch_data_bytes)?;
+        if launch_layout.tickets_sold(&launch_data_bytes) > 0 {
+            msg!("Randomness has to be committed before any tickets are sold");
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let (expected_address, bump) = get_commit_reveal_address(launch_data.key, program_id);
+    if randomness.key != &expected_address {
+        msg!("expected commit-reveal randomness {} {}", expected_address, randomness.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if **randomness.try_borrow_lamports()? > 0 {
+        msg!("Randomness has already been committed for this launch");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    utils::create_program_account(
+        authority,
+        randomness,
+        program_id,
+        bump,
+        CommitReveal::LEN,
+        vec![&launch_data.key.to_bytes(), COMMIT_REVEAL_SEED],
+    )?;
+
+    let commit_reveal = CommitReveal {
+        account_type: AccountType::RandomnessCommit,
+        launch: *launch_data.key,
+        authority: *authority.key,
+        commitment: args.commitment,
+        secret: [0; 32],
+        revealed: false,
+    };
+    commit_reveal.serialize(&mut &mut randomness.data.borrow_mut()[..])?;
+
+    msg!("✅ Committed randomness for launch {}", launch_data.key);
+    Ok(())
+}
+
+/// RevealRandomness: the committing authority reveals the secret once the launch has ended.
+/// Accounts: 0 authority, 1 launch_data, 2 randomness
+pub fn reveal_randomness<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::RevealRandomnessArgs) -> ProgramResult {
+    if accounts.len() < 3 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let authority = &accounts[0];
+    let launch_data = &accounts[1];
+    let randomness = &accounts[2];
+
+    if randomness.owner != program_id || launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if randomness.key != &get_commit_reveal_address(launch_data.key, program_id).0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut commit_reveal = CommitReveal::try_from_slice(&randomness.try_borrow_data()?)?;
+    check_authority(authority, &commit_reveal.authority)?;
+
+    if commit_reveal.revealed {
+        msg!("Randomness has already been revealed");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let end_date = {
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        launch::LaunchAccessor::load(&launch_data_bytes)?.end_date(&launch_data_bytes)
+    };
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time <= end_date {
+        msg!("Randomness can be revealed once the launch has ended (end_date: {}, current: {})", end_date, current_time);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if !check_reveal(&commit_reveal.commitment, &args.secret) {
+        msg!("Secret does not match the commitment");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    commit_reveal.secret = args.secret;
+    commit_reveal.revealed = true;
+    commit_reveal.serialize(&mut &mut randomness.data.borrow_mut()[..])?;
+
+    msg!("✅ Revealed randomness for launch {}", launch_data.key);
+    Ok(())
+}
+
+pub fn check_reveal(commitment: &[u8; 32], secret: &[u8; 32]) -> bool {
+    hashv(&[secret]).to_bytes() == *commitment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_reveal_len() {
+        let commit_reveal = CommitReveal {
+            account_type: AccountType::RandomnessCommit,
+            launch: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            commitment: [1; 32],
+            secret: [2; 32],
+            revealed: true,
+        };
+        assert_eq!(borsh::to_vec(&commit_reveal).unwrap().len(), CommitReveal::LEN);
+    }
+
+    #[test]
+    fn test_commit_reveal_provider() {
+        let secret = [7u8; 32];
+        let commitment = hashv(&[&secret]).to_bytes();
+        assert!(check_reveal(&commitment, &secret));
+        assert!(!check_reveal(&commitment, &[8u8; 32]));
+
+        let mut provider = CommitRevealProvider {
+            commit_reveal: CommitReveal {
+                account_type: AccountType::RandomnessCommit,
+                launch: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+                commitment,
+                secret: [0; 32],
+                revealed: false,
+            },
+            consumer: Pubkey::new_unique(),
+        };
+        assert!(provider.randomness().is_err());
+
+        provider.commit_reveal.secret = secret;
+        provider.commit_reveal.revealed = true;
+        assert_eq!(provider.randomness().unwrap(), expand_randomness(&secret, &provider.consumer));
+    }
+
+    #[test]
+    fn test_consumers_get_independent_randomness() {
+        let launch = Pubkey::new_unique();
+        let first = DeterministicProvider { seed: launch.to_bytes(), consumer: Pubkey::new_unique() };
+        let second = DeterministicProvider { seed: launch.to_bytes(), consumer: Pubkey::new_unique() };
+
+        // the same inputs always give the same draw
+        assert_eq!(first.randomness().unwrap(), first.randomness().unwrap());
+        assert_ne!(first.randomness().unwrap(), second.randomness().unwrap());
+
+        let randomness = first.randomness().unwrap();
+        assert_ne!(randomness[..32], randomness[32..]);
+    }
+}
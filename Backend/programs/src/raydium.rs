@@ -0,0 +1,335 @@
+/**
+ * Raydium CPMM integration
+ *
+ * Decodes the CPMM pool and config accounts so swaps can be quoted from the
+ * pool's real vault balances, and builds the `swap_base_input` CPI.
+ * Account layouts follow raydium-cp-swap (Anchor accounts, 8 byte discriminator).
+ */
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{accounts, utils::token};
+
+pub const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+pub const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+pub const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+pub const AUTH_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+/// Fee rates are expressed over this denominator
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+// pool status bit that disables swaps
+const STATUS_SWAP_DISABLED: u8 = 1 << 2;
+
+/// The fields of a CPMM `PoolState` needed to quote and execute a swap
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpmmPool {
+    pub amm_config: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    pub status: u8,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+impl CpmmPool {
+    pub const LEN: usize = 389;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::LEN || data[..8] != POOL_STATE_DISCRIMINATOR {
+            msg!("Invalid Raydium CPMM pool state");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(CpmmPool {
+            amm_config: read_pubkey(data, 8),
+            token_0_vault: read_pubkey(data, 72),
+            token_1_vault: read_pubkey(data, 104),
+            token_0_mint: read_pubkey(data, 168),
+            token_1_mint: read_pubkey(data, 200),
+            token_0_program: read_pubkey(data, 232),
+            token_1_program: read_pubkey(data, 264),
+            observation_key: read_pubkey(data, 296),
+            status: data[329],
+            protocol_fees_token_0: read_u64(data, 341),
+            protocol_fees_token_1: read_u64(data, 349),
+            fund_fees_token_0: read_u64(data, 357),
+            fund_fees_token_1: read_u64(data, 365),
+            open_time: read_u64(data, 373),
+        })
+    }
+
+    pub fn swap_enabled(&self, now: u64) -> bool {
+        self.status & STATUS_SWAP_DISABLED == 0 && now >= self.open_time
+    }
+
+    /// Vaults in swap direction, or None if the mints are not the pool's pair
+    pub fn vaults_for(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Option<(Pubkey, Pubkey)> {
+        if *input_mint == self.token_0_mint && *output_mint == self.token_1_mint {
+            Some((self.token_0_vault, self.token_1_vault))
+        } else if *input_mint == self.token_1_mint && *output_mint == self.token_0_mint {
+            Some((self.token_1_vault, self.token_0_vault))
+        } else {
+            None
+        }
+    }
+
+    /// Pool reserves exclude the protocol and fund fees that sit in the vaults
+    pub fn reserves(&self, vault_0_amount: u64, vault_1_amount: u64) -> (u64, u64) {
+        (
+            vault_0_amount.saturating_sub(self.protocol_fees_token_0).saturating_sub(self.fund_fees_token_0),
+            vault_1_amount.saturating_sub(self.protocol_fees_token_1).saturating_sub(self.fund_fees_token_1),
+        )
+    }
+}
+
+pub fn unpack_trade_fee_rate(data: &[u8]) -> Result<u64, ProgramError> {
+    // bump(1), disable_create_pool(1), index(2), trade_fee_rate(8)
+    if data.len() < 20 || data[..8] != AMM_CONFIG_DISCRIMINATOR {
+        msg!("Invalid Raydium CPMM amm config");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(read_u64(data, 12))
+}
+
+/// Constant product output for `swap_base_input`, charging the trade fee on the input (rounded up)
+pub fn get_amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, trade_fee_rate: u64) -> Option<u64> {
+    if reserve_in == 0 || reserve_out == 0 || trade_fee_rate >= FEE_RATE_DENOMINATOR {
+        return None;
+    }
+
+    let amount_in = amount_in as u128;
+    let denominator = FEE_RATE_DENOMINATOR as u128;
+    let fee = (amount_in * trade_fee_rate as u128).div_ceil(denominator);
+    let amount_in_after_fee = amount_in.checked_sub(fee)?;
+
+    let amount_out = (reserve_out as u128 * amount_in_after_fee) / (reserve_in as u128 + amount_in_after_fee);
+    u64::try_from(amount_out).ok()
+}
+
+/// Accounts of a Raydium CPMM venue, in the order they are passed to the program
+pub struct RaydiumVenue<'a, 'b> {
+    pub program: &'b AccountInfo<'a>,
+    pub authority: &'b AccountInfo<'a>,
+    pub amm_config: &'b AccountInfo<'a>,
+    pub pool_state: &'b AccountInfo<'a>,
+    pub input_vault: &'b AccountInfo<'a>,
+    pub output_vault: &'b AccountInfo<'a>,
+    pub observation_state: &'b AccountInfo<'a>,
+}
+
+impl<'a, 'b> RaydiumVenue<'a, 'b> {
+    pub const NUM_ACCOUNTS: usize = 7;
+
+    pub fn from_accounts(venue_accounts: &'b [AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        if venue_accounts.len() < Self::NUM_ACCOUNTS {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(RaydiumVenue {
+            program: &venue_accounts[0],
+            authority: &venue_accounts[1],
+            amm_config: &venue_accounts[2],
+            pool_state: &venue_accounts[3],
+            input_vault: &venue_accounts[4],
+            output_vault: &venue_accounts[5],
+            observation_state: &venue_accounts[6],
+        })
+    }
+
+    /// Checks every account against the pool state and quotes `amount_in`,
+    /// returning the pool and the amount the user would receive
+    pub fn quote(&self, input_mint: &AccountInfo<'a>, output_mint: &AccountInfo<'a>, amount_in: u64, now: u64) -> Result<(CpmmPool, u64), ProgramError> {
+        if self.program.key != &accounts::get_expected_raydium_key() {
+            msg!("expected raydium {} {}", accounts::get_expected_raydium_key(), self.program.key);
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if self.pool_state.owner != self.program.key || self.amm_config.owner != self.program.key {
+            msg!("Raydium pool accounts are not owned by the CPMM program");
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let (expected_authority, _) = Pubkey::find_program_address(&[AUTH_SEED], self.program.key);
+        if self.authority.key != &expected_authority {
+            msg!("expected raydium authority {}", expected_authority);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let pool = CpmmPool::unpack(&self.pool_state.try_borrow_data()?)?;
+        if self.amm_config.key != &pool.amm_config || self.observation_state.key != &pool.observation_key {
+            msg!("Raydium config or observation account does not match the pool");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (input_vault, output_vault) = pool.vaults_for(input_mint.key, output_mint.key).ok_or_else(|| {
+            msg!("Raydium pool does not trade {} -> {}", input_mint.key, output_mint.key);
+            ProgramError::InvalidAccountData
+        })?;
+        if self.input_vault.key != &input_vault || self.output_vault.key != &output_vault {
+            msg!("Raydium vaults do not match the pool");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !pool.swap_enabled(now) {
+            msg!("Raydium pool swaps are disabled");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let trade_fee_rate = unpack_trade_fee_rate(&self.amm_config.try_borrow_data()?)?;
+
+        let input_is_0 = *input_mint.key == pool.token_0_mint;
+        let input_balance = token::get_token_balance(self.input_vault);
+        let output_balance = token::get_token_balance(self.output_vault);
+        let (reserve_in, reserve_out) = if input_is_0 {
+            pool.reserves(input_balance, output_balance)
+        } else {
+            let (reserve_out, reserve_in) = pool.reserves(output_balance, input_balance);
+            (reserve_in, reserve_out)
+        };
+
+        // token 2022 transfer fees are taken on the way into and out of the pool
+        let amount_in = token::get_amount_post_transfer_fee(amount_in, input_mint)?;
+        let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, trade_fee_rate).ok_or(ProgramError::ArithmeticOverflow)?;
+        let amount_out = token::get_amount_post_transfer_fee(amount_out, output_mint)?;
+
+        Ok((pool, amount_out))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_base_input_instruction(
+        &self,
+        payer: &Pubkey,
+        input_token_account: &Pubkey,
+        output_token_account: &Pubkey,
+        input_token_program: &Pubkey,
+        output_token_program: &Pubkey,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Instruction {
+        let mut data = SWAP_BASE_INPUT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+        Instruction {
+            program_id: *self.program.key,
+            accounts: vec![
+                AccountMeta::new_readonly(*payer, true),
+                AccountMeta::new_readonly(*self.authority.key, false),
+                AccountMeta::new_readonly(*self.amm_config.key, false),
+                AccountMeta::new(*self.pool_state.key, false),
+                AccountMeta::new(*input_token_account, false),
+                AccountMeta::new(*output_token_account, false),
+                AccountMeta::new(*self.input_vault.key, false),
+                AccountMeta::new(*self.output_vault.key, false),
+                AccountMeta::new_readonly(*input_token_program, false),
+                AccountMeta::new_readonly(*output_token_program, false),
+                AccountMeta::new_readonly(*input_mint, false),
+                AccountMeta::new_readonly(*output_mint, false),
+                AccountMeta::new(*self.observation_state.key, false),
+            ],
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_data(pool: &CpmmPool) -> Vec<u8> {
+        let mut data = vec![0u8; CpmmPool::LEN];
+        data[..8].copy_from_slice(&POOL_STATE_DISCRIMINATOR);
+        data[8..40].copy_from_slice(pool.amm_config.as_ref());
+        data[72..104].copy_from_slice(pool.token_0_vault.as_ref());
+        data[104..136].copy_from_slice(pool.token_1_vault.as_ref());
+        data[168..200].copy_from_slice(pool.token_0_mint.as_ref());
+        data[200..232].copy_from_slice(pool.token_1_mint.as_ref());
+        data[232..264].copy_from_slice(pool.token_0_program.as_ref());
+        data[264..296].copy_from_slice(pool.token_1_program.as_ref());
+        data[296..328].copy_from_slice(pool.observation_key.as_ref());
+        data[329] = pool.status;
+        data[341..349].copy_from_slice(&pool.protocol_fees_token_0.to_le_bytes());
+        data[349..357].copy_from_slice(&pool.protocol_fees_token_1.to_le_bytes());
+        data[357..365].copy_from_slice(&pool.fund_fees_token_0.to_le_bytes());
+        data[365..373].copy_from_slice(&pool.fund_fees_token_1.to_le_bytes());
+        data[373..381].copy_from_slice(&pool.open_time.to_le_bytes());
+        data
+    }
+
+    fn test_pool() -> CpmmPool {
+        CpmmPool {
+            amm_config: Pubkey::new_unique(),
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            token_0_mint: Pubkey::new_unique(),
+            token_1_mint: Pubkey::new_unique(),
+            token_0_program: Pubkey::new_unique(),
+            token_1_program: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+            status: 0,
+            protocol_fees_token_0: 10,
+            protocol_fees_token_1: 20,
+            fund_fees_token_0: 1,
+            fund_fees_token_1: 2,
+            open_time: 100,
+        }
+    }
+
+    #[test]
+    fn test_pool_state_round_trip() {
+        let pool = test_pool();
+        assert_eq!(CpmmPool::unpack(&pool_data(&pool)).unwrap(), pool);
+
+        let mut bad = pool_data(&pool);
+        bad[0] = 0;
+        assert!(CpmmPool::unpack(&bad).is_err());
+        assert!(CpmmPool::unpack(&pool_data(&pool)[..100]).is_err());
+    }
+
+    #[test]
+    fn test_vaults_and_reserves() {
+        let pool = test_pool();
+        assert_eq!(pool.vaults_for(&pool.token_1_mint, &pool.token_0_mint), Some((pool.token_1_vault, pool.token_0_vault)));
+        assert_eq!(pool.vaults_for(&pool.token_0_mint, &Pubkey::new_unique()), None);
+        assert_eq!(pool.reserves(1_000, 1_000), (989, 978));
+
+        assert!(!pool.swap_enabled(99));
+        assert!(pool.swap_enabled(100));
+        let disabled = CpmmPool { status: STATUS_SWAP_DISABLED, ..pool };
+        assert!(!disabled.swap_enabled(100));
+    }
+
+    #[test]
+    fn test_amount_out() {
+        // 0.25% fee, 1:1 pool
+        let out = get_amount_out(1_000_000, 1_000_000_000, 1_000_000_000, 2_500).unwrap();
+        assert_eq!(out, 996_505);
+        // output can never drain the pool
+        let out = get_amount_out(u64::MAX, 1_000, 1_000, 2_500).unwrap();
+        assert!(out < 1_000);
+        assert_eq!(get_amount_out(1_000, 0, 1_000, 2_500), None);
+    }
+}
@@ -1,12 +1,7 @@
 use borsh::{to_vec, BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
-pub const fn get_fee_amount(network: Network) -> u64 {
-    match network {
-        Network::Eclipse => 100_000,
-        _ => 2_000_000,
-    }
-}
+use crate::accounts;
 
 #[derive(PartialEq)]
 pub enum Network {
@@ -26,8 +21,6 @@ pub const NETWORK: Network = Network::Eclipse;
 #[cfg(not(any(feature = "mainnet", feature = "eclipse")))]
 pub const NETWORK: Network = Network::Devnet;
 
-pub const FEE_AMOUNT: u64 = get_fee_amount(NETWORK);
-
 pub enum Extensions {
     None = 0,
     TransferFee = 1,
@@ -42,6 +35,24 @@ pub struct SeedStruct {
 
 pub const N_RANDOMS: usize = 200;
 
+/// Share of every ticket purchase paid to the ledger wallet, in basis points, until the admin sets a FeeConfig
+pub const PLATFORM_FEE_BPS: u16 = 50;
+
+/// Share of every SwapCookAMM trade paid to the ledger wallet, in basis points, until the admin sets a FeeConfig
+pub const SWAP_FEE_BPS: u16 = 25;
+
+/// Most a launch creator can take from their launch's swaps, in basis points, until the admin sets a FeeConfig
+pub const MAX_CREATOR_FEE_BPS: u16 = 100;
+
+/// Highest any fee in a FeeConfig can be set to, in basis points
+pub const FEE_BPS_CAP: u16 = 1_000;
+
+/// Lamports collected per ticket at purchase and paid to whoever settles the ticket with SettleRaffle
+pub const RAFFLE_CRANK_FEE: u64 = 10_000;
+
+/// Lamports of a bonding curve's SOL paid to whoever graduates it with GraduateLaunch
+pub const GRADUATION_BOUNTY: u64 = 20_000_000;
+
 pub struct RollResult {
     pub rolls: [f64; N_RANDOMS],
 }
@@ -83,6 +94,10 @@ pub enum AccountType {
     NFTLookup,
     Listing,
     UnverifiedListing,
+    RandomnessCommit,
+    LiquidityLock,
+    CreatorFees,
+    RaffleDraw,
 }
 
 #[derive(Default, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Copy)]
@@ -92,10 +107,83 @@ pub enum GameSpeed {
     Slow,
 }
 
+/// Fee of `bps` basis points on `amount`
+pub fn get_fee(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / 10_000) as u64
+}
+
+/// The protocol's fee schedule, kept in ProgramData and set by its admin with UpdateFeeConfig
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct FeeConfig {
+    /// Share of every ticket purchase, IDO contribution and dutch auction bid, in basis points
+    pub ticket_fee_bps: u16,
+    /// Share of every SwapCookAMM trade, in basis points
+    pub swap_fee_bps: u16,
+    /// Most a launch creator can take from their launch's swaps, in basis points
+    pub max_creator_fee_bps: u16,
+    /// Wallet every protocol fee has to be paid to
+    pub recipient: Pubkey,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        FeeConfig {
+            ticket_fee_bps: PLATFORM_FEE_BPS,
+            swap_fee_bps: SWAP_FEE_BPS,
+            max_creator_fee_bps: MAX_CREATOR_FEE_BPS,
+            recipient: accounts::get_expected_fees_key(),
+        }
+    }
+}
+
 #[derive(Default, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct ProgramData {
     pub account_type: AccountType,
     pub num_launches: u64,
+    /// Authority allowed to update the fee schedule, pause the program and close finished accounts, daoplays_account while unset
+    pub admin: Pubkey,
+    /// Zeroed in accounts created before it, which charge the default schedule until UpdateFeeConfig sets one
+    pub fee_config: FeeConfig,
+    /// Admin proposed with ProposeAdmin, who takes over once they sign AcceptAdmin, the default key while none is
+    pub pending_admin: Pubkey,
+    /// Set with SetPause to stop ticket sales, swaps, liquidity and claims across every launch
+    pub paused: bool,
+}
+
+impl ProgramData {
+    /// Reads the program data, treating the admin, fee schedule and pause of accounts created before them as unset
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut padded = data.to_vec();
+        if padded.len() < get_arena_data_size() {
+            padded.resize(get_arena_data_size(), 0);
+        }
+        Ok(Self::deserialize(&mut &padded[..])?)
+    }
+
+    /// Writes the program data back, only as much of it as the account holds.
+    /// The admin instructions grow accounts created before the fields they set before writing them.
+    pub fn store(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let encoded = to_vec(self)?;
+        let len = data.len().min(encoded.len());
+        data[..len].copy_from_slice(&encoded[..len]);
+        Ok(())
+    }
+
+    /// The admin, which is daoplays_account until one has been set
+    pub fn get_admin(&self) -> Pubkey {
+        if self.admin == Pubkey::default() {
+            return accounts::daoplays_account::ID;
+        }
+        self.admin
+    }
+
+    /// The fee schedule in force, which is the default one until the admin has set one
+    pub fn get_fee_config(&self) -> FeeConfig {
+        if self.fee_config.recipient == Pubkey::default() {
+            return FeeConfig::default();
+        }
+        self.fee_config.clone()
+    }
 }
 
 
@@ -107,7 +195,7 @@ pub enum Achievement32 {
 }
 
 #[derive(Default, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
-pub enum LaunchMeta {
+pub enum NativeLaunchMeta {
     #[default]
     Raffle,
     FCFS,
@@ -117,10 +205,12 @@ pub enum LaunchMeta {
     },
 }
 
+/// Launch layout the native CreateLaunch handler wrote before `launch::LaunchData` was versioned,
+/// only kept so MigrateLaunch can read old accounts
 #[derive(Default, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
-pub struct LaunchData {
+pub struct NativeLaunchData {
     pub account_type: AccountType,
-    pub launch_meta: LaunchMeta,
+    pub launch_meta: NativeLaunchMeta,
     pub plugins: Vec<u8>, // Simplified for now
     pub last_interaction: u64,
     pub num_interactions: u16,
@@ -236,3 +326,53 @@ pub fn get_mm_launch_data_size() -> usize {
     let encoded = to_vec(&MMLaunchData::default()).unwrap();
     encoded.len()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_data_fee_config() {
+        // accounts created before the fee schedule only hold the account type and launch count
+        let legacy = to_vec(&(AccountType::Program, 12_u64)).unwrap();
+        let program_data = ProgramData::load(&legacy).unwrap();
+        assert_eq!(program_data.num_launches, 12);
+        assert_eq!(program_data.get_admin(), accounts::daoplays_account::ID);
+        assert_eq!(program_data.get_fee_config(), FeeConfig::default());
+
+        let mut data = legacy.clone();
+        program_data.store(&mut data).unwrap();
+        assert_eq!(data, legacy);
+
+        let admin = Pubkey::new_unique();
+        let fee_config = FeeConfig {
+            ticket_fee_bps: 100,
+            swap_fee_bps: 30,
+            max_creator_fee_bps: 50,
+            recipient: Pubkey::new_unique(),
+        };
+        let updated = ProgramData {
+            admin,
+            fee_config: fee_config.clone(),
+            ..program_data
+        };
+        let mut data = vec![0; get_arena_data_size()];
+        updated.store(&mut data).unwrap();
+        let loaded = ProgramData::load(&data).unwrap();
+        assert_eq!(loaded.get_admin(), admin);
+        assert_eq!(loaded.get_fee_config(), fee_config);
+
+        assert_eq!(loaded.pending_admin, Pubkey::default());
+        assert!(!loaded.paused);
+
+        // accounts written before the admin transfer and pause read them as unset
+        let pre_governance = to_vec(&(AccountType::Program, 12_u64, admin, fee_config.clone())).unwrap();
+        let loaded = ProgramData::load(&pre_governance).unwrap();
+        assert_eq!(loaded.get_admin(), admin);
+        assert_eq!(loaded.pending_admin, Pubkey::default());
+        assert!(!loaded.paused);
+
+        assert_eq!(get_fee(1_000_000_000, PLATFORM_FEE_BPS), 5_000_000);
+        assert_eq!(get_fee(u64::MAX, 10_000), u64::MAX);
+    }
+}
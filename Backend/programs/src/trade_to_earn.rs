@@ -0,0 +1,450 @@
+/**
+ * Trade to earn rewards for Cook AMM pools.
+ *
+ * A launch's Distribution::MMRewards share is moved into a token vault owned by the AMM
+ * ([amm, b"TradeToEarn"]) by AddTradeRewards. For REWARD_DAYS days after that, every
+ * SwapCookAMM records the trader's volume for the day in an MMUserData account
+ * ([amm, user, day]) and the day's total in an MMLaunchData account ([amm, day, b"LaunchDate"]).
+ * The first trade of a day opens its reward pool from the schedule, rolling in any days
+ * nobody traded on. Once a day is over, GetMMRewardTokens pays each trader their share of
+ * that day's pool in proportion to the tokens they bought.
+ */
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token_2022::extension::StateWithExtensions;
+
+use crate::{accounts, amm, common, launch, state, utils};
+
+/// Days after funding during which trades earn rewards
+pub const REWARD_DAYS: u32 = 30;
+/// last_reward_date while no daily pool has been opened yet
+const NO_REWARD_DATE: u32 = 100;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Trade to earn state of a Cook AMM pool, stored in the AMM account
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct TradeToEarn {
+    pub total_tokens: u64,
+    pub first_reward_date: u32,
+    pub last_reward_date: u32,
+}
+
+/// Share of the rewarded tokens paid out for trades on `day`, in basis points
+pub fn reward_schedule(day: u32) -> u64 {
+    match day {
+        0..=9 => 500,
+        10..=19 => 300,
+        20..=29 => 200,
+        _ => 0,
+    }
+}
+
+pub fn get_date(unix_timestamp: i64) -> u32 {
+    (unix_timestamp as u64 / SECONDS_PER_DAY) as u32
+}
+
+impl TradeToEarn {
+    /// Reward day for `date`, None when the pool has no rewards or the reward period is over
+    pub fn reward_day(&self, date: u32) -> Option<u32> {
+        if self.total_tokens == 0 {
+            return None;
+        }
+        let day = date.checked_sub(self.first_reward_date)?;
+        (day < REWARD_DAYS).then_some(day)
+    }
+
+    /// Opens the reward pool of `day`, which also takes the share of any earlier days without trades
+    pub fn open_day(&mut self, day: u32) -> u64 {
+        let first_day = if self.last_reward_date == NO_REWARD_DATE {
+            0
+        } else {
+            self.last_reward_date + 1
+        };
+        let schedule: u64 = (first_day..=day).map(reward_schedule).sum();
+        self.last_reward_date = day;
+
+        (self.total_tokens as u128 * schedule as u128 / 10_000) as u64
+    }
+}
+
+/// A trader's share of a daily pool, rounded down so the pool can always pay everyone
+pub fn get_user_rewards(token_rewards: u64, launch_buy_amount: u64, user_buy_amount: u64) -> u64 {
+    if launch_buy_amount == 0 {
+        return 0;
+    }
+    (token_rewards as u128 * user_buy_amount.min(launch_buy_amount) as u128 / launch_buy_amount as u128) as u64
+}
+
+pub fn get_vault_address(amm: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&amm.to_bytes(), b"TradeToEarn"], program_id)
+}
+
+pub fn get_launch_rewards_address(amm: &Pubkey, day: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&amm.to_bytes(), &day.to_le_bytes(), b"LaunchDate"], program_id)
+}
+
+pub fn get_user_rewards_address(amm: &Pubkey, user: &Pubkey, day: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&amm.to_bytes(), &user.to_bytes(), &day.to_le_bytes()], program_id)
+}
+
+fn check_address(account: &AccountInfo, expected: &Pubkey) -> ProgramResult {
+    if account.key != expected {
+        msg!("expected program data account {}", expected);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Adds a Cook AMM trade to the trader's and the pool's volume for the day.
+/// side 0 = buy, 1 = sell, `token_amount` is the base token amount traded.
+#[allow(clippy::too_many_arguments)]
+pub fn record_trade<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    amm_account: &AccountInfo<'a>,
+    launch_rewards: &AccountInfo<'a>,
+    user_rewards: &AccountInfo<'a>,
+    now: i64,
+    side: u8,
+    token_amount: u64,
+) -> ProgramResult {
+    let mut amm_state = amm::AMM::load(&amm_account.try_borrow_data()?)?;
+    let day = match amm_state.trade_to_earn.reward_day(get_date(now)) {
+        Some(day) => day,
+        None => return Ok(()),
+    };
+
+    let (launch_rewards_key, launch_rewards_bump) = get_launch_rewards_address(amm_account.key, day, program_id);
+    check_address(launch_rewards, &launch_rewards_key)?;
+    let (user_rewards_key, user_rewards_bump) = get_user_rewards_address(amm_account.key, user.key, day, program_id);
+    check_address(user_rewards, &user_rewards_key)?;
+
+    if launch_rewards.data_is_empty() {
+        utils::create_program_account(
+            user,
+            launch_rewards,
+            program_id,
+            launch_rewards_bump,
+            state::get_mm_launch_data_size(),
+            vec![&amm_account.key.to_bytes(), &day.to_le_bytes(), b"LaunchDate"],
+        )?;
+
+        let token_rewards = amm_state.trade_to_earn.open_day(day);
+        msg!("Opening trade to earn day {} with {} tokens", day, token_rewards);
+
+        let launch_mm_data = state::MMLaunchData {
+            account_type: state::AccountType::MMLaunchData,
+            amm_key: *amm_account.key,
+            date: day,
+            token_rewards,
+            ..Default::default()
+        };
+        launch_mm_data.serialize(&mut &mut launch_rewards.data.borrow_mut()[..])?;
+        amm_state.store(&mut amm_account.try_borrow_mut_data()?)?;
+    }
+
+    if user_rewards.data_is_empty() {
+        utils::create_program_account(
+            user,
+            user_rewards,
+            program_id,
+            user_rewards_bump,
+            state::get_mm_user_data_size(),
+            vec![&amm_account.key.to_bytes(), &user.key.to_bytes(), &day.to_le_bytes()],
+        )?;
+
+        let user_mm_data = state::MMUserData {
+            account_type: state::AccountType::MMUserData,
+            user_key: *user.key,
+            amm_key: *amm_account.key,
+            date: day,
+            ..Default::default()
+        };
+        user_mm_data.serialize(&mut &mut user_rewards.data.borrow_mut()[..])?;
+    }
+
+    let mut launch_mm_data = state::MMLaunchData::try_from_slice(&launch_rewards.data.borrow()[..])?;
+    let mut user_mm_data = state::MMUserData::try_from_slice(&user_rewards.data.borrow()[..])?;
+
+    if side == 0 {
+        launch_mm_data.buy_amount = launch_mm_data.buy_amount.saturating_add(token_amount);
+        user_mm_data.buy_amount = user_mm_data.buy_amount.saturating_add(token_amount);
+    } else {
+        user_mm_data.sell_amount = user_mm_data.sell_amount.saturating_add(token_amount);
+    }
+
+    launch_mm_data.serialize(&mut &mut launch_rewards.data.borrow_mut()[..])?;
+    user_mm_data.serialize(&mut &mut user_rewards.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// AddTradeRewards: moves the launch's MMRewards allocation from the cook_pda into the pool's
+/// trade to earn vault and starts the reward schedule. Only the creator can do this, once.
+/// Accounts: 0 user, 1 base_token_mint, 2 amm, 3 trade_to_earn, 4 launch_data, 5 cook_pda,
+/// 6 cook_base_token, 7 base_token_program, 8 system_program
+pub fn add_trade_rewards<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::AddRewardsArgs) -> ProgramResult {
+    if accounts.len() < 9 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let user = &accounts[0];
+    let base_token_mint = &accounts[1];
+    let amm_account = &accounts[2];
+    let trade_to_earn = &accounts[3];
+    let launch_data = &accounts[4];
+    let cook_pda = &accounts[5];
+    let cook_base_token = &accounts[6];
+    let base_token_program = &accounts[7];
+    let system_program = &accounts[8];
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    accounts::check_system_program_key(system_program)?;
+    accounts::check_token_program_key(base_token_program)?;
+
+    let (_, expected_amm, _) = amm::get_cook_amm(base_token_mint.key, program_id);
+    check_address(amm_account, &expected_amm)?;
+    if amm_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (expected_vault, vault_bump) = get_vault_address(amm_account.key, program_id);
+    check_address(trade_to_earn, &expected_vault)?;
+
+    let cook_pda_bump = accounts::check_program_data_account(cook_pda, program_id, vec![&accounts::SOL_SEED.to_le_bytes()])?;
+
+    // the rewards come out of the verified launch of this mint
+    if launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let (rewards_percent, total_supply) = {
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = launch::LaunchAccessor::load(&launch_data_bytes)?;
+
+        if !launch_layout.is_launch_of(&launch_data_bytes, launch_data.key, base_token_mint.key, program_id) {
+            msg!("Launch account does not belong to mint {}", base_token_mint.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if launch_layout.creator(&launch_data_bytes) != Some(*user.key) {
+            msg!("Only the launch creator can add trade rewards");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        (
+            launch_layout.distribution(&launch_data_bytes, launch::Distribution::MMRewards as usize).unwrap_or(0),
+            launch_layout.total_supply(&launch_data_bytes),
+        )
+    };
+
+    let decimals = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&base_token_mint.try_borrow_data()?)?.base.decimals;
+    let rewards_amount = (total_supply as u128 * 10u128.pow(decimals as u32) * rewards_percent as u128 / 100) as u64;
+    if rewards_amount == 0 {
+        msg!("Launch has no MMRewards allocation");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if args.amount != rewards_amount {
+        msg!("Expected to add {} reward tokens, the MMRewards allocation is {}", args.amount, rewards_amount);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    amm::resize_amm_data(user, amm_account, system_program)?;
+    let mut amm_state = amm::AMM::load(&amm_account.try_borrow_data()?)?;
+    if amm_state.trade_to_earn.total_tokens > 0 {
+        msg!("Trade rewards have already been added to this pool");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    utils::create_token_account(
+        user,
+        trade_to_earn,
+        base_token_mint,
+        base_token_program,
+        amm_account,
+        vault_bump,
+        vec![&amm_account.key.to_bytes(), b"TradeToEarn"],
+    )?;
+
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            base_token_program.key,
+            cook_base_token.key,
+            base_token_mint.key,
+            trade_to_earn.key,
+            cook_pda.key,
+            &[],
+            rewards_amount,
+            decimals,
+        )?,
+        &[cook_base_token.clone(), base_token_mint.clone(), trade_to_earn.clone(), cook_pda.clone(), base_token_program.clone()],
+        &[&[&accounts::SOL_SEED.to_le_bytes(), &[cook_pda_bump]]],
+    )?;
+
+    // rewards are paid from what actually arrived in the vault
+    amm_state.trade_to_earn = TradeToEarn {
+        total_tokens: utils::get_token_balance(trade_to_earn),
+        first_reward_date: get_date(Clock::get()?.unix_timestamp),
+        last_reward_date: NO_REWARD_DATE,
+    };
+    amm_state.store(&mut amm_account.try_borrow_mut_data()?)?;
+
+    msg!("✅ Added {} trade to earn tokens", amm_state.trade_to_earn.total_tokens);
+    Ok(())
+}
+
+/// GetMMRewardTokens: pays the trader's share of a completed day and closes their MMUserData account.
+/// Accounts: 0 user, 1 base_token_mint, 2 amm, 3 trade_to_earn, 4 launch_rewards, 5 user_rewards,
+/// 6 user_base_token, 7 base_token_program, 8 cook_data, 9 launch_data
+pub fn get_mm_rewards<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], args: crate::instruction::GetMMRewardArgs) -> ProgramResult {
+    if accounts.len() < 10 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let user = &accounts[0];
+    let base_token_mint = &accounts[1];
+    let amm_account = &accounts[2];
+    let trade_to_earn = &accounts[3];
+    let launch_rewards = &accounts[4];
+    let user_rewards = &accounts[5];
+    let user_base_token = &accounts[6];
+    let base_token_program = &accounts[7];
+    let cook_data = &accounts[8];
+    let launch_data = &accounts[9];
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // rewards are paused with the launch of this mint
+    if launch_data.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    {
+        let launch_data_bytes = launch_data.try_borrow_data()?;
+        let launch_layout = launch::LaunchAccessor::load(&launch_data_bytes)?;
+        if !launch_layout.is_launch_of(&launch_data_bytes, launch_data.key, base_token_mint.key, program_id) {
+            msg!("Launch account does not belong to mint {}", base_token_mint.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    common::check_not_paused(program_id, Some(cook_data))?;
+    common::check_launch_not_paused(launch_data)?;
+
+    accounts::check_token_program_key(base_token_program)?;
+    accounts::check_token_account(user, base_token_mint, user_base_token, base_token_program)?;
+
+    let (amm_seed_keys, expected_amm, amm_bump) = amm::get_cook_amm(base_token_mint.key, program_id);
+    check_address(amm_account, &expected_amm)?;
+    check_address(trade_to_earn, &get_vault_address(amm_account.key, program_id).0)?;
+    check_address(launch_rewards, &get_launch_rewards_address(amm_account.key, args.date, program_id).0)?;
+    check_address(user_rewards, &get_user_rewards_address(amm_account.key, user.key, args.date, program_id).0)?;
+
+    if launch_rewards.owner != program_id || user_rewards.owner != program_id {
+        msg!("No trades recorded for day {}", args.date);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let amm_state = amm::AMM::load(&amm_account.try_borrow_data()?)?;
+    let today = get_date(Clock::get()?.unix_timestamp);
+    let current_day = today.saturating_sub(amm_state.trade_to_earn.first_reward_date);
+    if args.date >= current_day {
+        msg!("Rewards for day {} can be claimed once the day is over", args.date);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut launch_mm_data = state::MMLaunchData::try_from_slice(&launch_rewards.data.borrow()[..])?;
+    let user_mm_data = state::MMUserData::try_from_slice(&user_rewards.data.borrow()[..])?;
+    if launch_mm_data.account_type != state::AccountType::MMLaunchData || user_mm_data.account_type != state::AccountType::MMUserData {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let user_reward = get_user_rewards(launch_mm_data.token_rewards, launch_mm_data.buy_amount, user_mm_data.buy_amount);
+    msg!("Day {} rewards: {} of {} tokens", args.date, user_reward, launch_mm_data.token_rewards);
+
+    if user_reward > 0 {
+        let decimals = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&base_token_mint.try_borrow_data()?)?.base.decimals;
+        invoke_signed(
+            &spl_token_2022::instruction::transfer_checked(
+                base_token_program.key,
+                trade_to_earn.key,
+                base_token_mint.key,
+                user_base_token.key,
+                amm_account.key,
+                &[],
+                user_reward,
+                decimals,
+            )?,
+            &[trade_to_earn.clone(), base_token_mint.clone(), user_base_token.clone(), amm_account.clone(), base_token_program.clone()],
+            &[&[&amm_seed_keys[0].to_bytes(), &amm_seed_keys[1].to_bytes(), b"CookAMM", &[amm_bump]]],
+        )?;
+    }
+
+    launch_mm_data.amount_distributed = launch_mm_data.amount_distributed.saturating_add(user_reward);
+    if launch_mm_data.buy_amount > 0 {
+        launch_mm_data.fraction_distributed += user_mm_data.buy_amount as f64 / launch_mm_data.buy_amount as f64;
+    }
+    launch_mm_data.serialize(&mut &mut launch_rewards.data.borrow_mut()[..])?;
+
+    // closing the user's day account is what stops a second claim
+    let user_rewards_lamports = **user_rewards.try_borrow_lamports()?;
+    **user_rewards.try_borrow_mut_lamports()? = 0;
+    **user.try_borrow_mut_lamports()? += user_rewards_lamports;
+    user_rewards.realloc(0, false)?;
+    user_rewards.assign(&solana_program::system_program::ID);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_pays_out_everything() {
+        let total: u64 = (0..REWARD_DAYS).map(reward_schedule).sum();
+        assert_eq!(total, 10_000);
+        assert_eq!(reward_schedule(REWARD_DAYS), 0);
+    }
+
+    #[test]
+    fn test_open_day_rolls_in_quiet_days() {
+        let mut trade_to_earn = TradeToEarn {
+            total_tokens: 1_000_000,
+            first_reward_date: 19_000,
+            last_reward_date: NO_REWARD_DATE,
+        };
+
+        assert_eq!(trade_to_earn.reward_day(18_999), None);
+        assert_eq!(trade_to_earn.reward_day(19_002), Some(2));
+        assert_eq!(trade_to_earn.reward_day(19_000 + REWARD_DAYS), None);
+
+        // nobody traded on days 0 and 1
+        assert_eq!(trade_to_earn.open_day(2), 150_000);
+        assert_eq!(trade_to_earn.open_day(3), 50_000);
+        assert_eq!(trade_to_earn.open_day(12), 6 * 50_000 + 3 * 30_000);
+        assert_eq!(trade_to_earn.last_reward_date, 12);
+
+        assert_eq!(TradeToEarn::default().reward_day(0), None);
+    }
+
+    #[test]
+    fn test_user_rewards() {
+        assert_eq!(get_user_rewards(1_000, 3, 1), 333);
+        assert_eq!(get_user_rewards(1_000, 3, 2), 666);
+        assert_eq!(get_user_rewards(1_000, 3, 5), 1_000);
+        assert_eq!(get_user_rewards(1_000, 0, 0), 0);
+        assert_eq!(get_user_rewards(u64::MAX, u64::MAX, u64::MAX - 1), u64::MAX - 1);
+    }
+}
@@ -580,7 +580,7 @@ pub fn shift_seed(mut seed: u64) -> u64 {
     seed ^= seed >> 12;
     seed ^= seed << 25;
     seed ^= seed >> 27;
-    seed *= 0x2545F4914F6CDD1D;
+    seed = seed.wrapping_mul(0x2545F4914F6CDD1D);
 
     return seed;
 }
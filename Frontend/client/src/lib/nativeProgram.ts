@@ -78,6 +78,12 @@ export interface CreateArgs {
   launch_type: number;
   whitelist_tokens: number;
   whitelist_end: number;
+  allowlist?: MerkleAllowlist | null; // null = no allowlist phase
+  soft_cap?: number; // lamports
+  hard_cap?: number; // IDO only, lamports, 0 = uncapped
+  auction_floor_price?: number; // dutch auction only
+  auction_half_life?: number; // dutch auction only, seconds, 0 = linear decay
+  creator_fee_bps?: number; // share of every SwapCookAMM trade paid to the creator
 }
 
 // InstantLaunchArgs interface matching Rust struct (for instant launches)
@@ -98,6 +104,16 @@ export interface InstantLaunchArgs {
   launch_type: number;
   whitelist_tokens: number;
   whitelist_end: number;
+  liquidity_scaling?: number; // 1 to scale down fills while the pool holds less than 10 SOL
+  allowlist?: MerkleAllowlist | null; // null = no allowlist phase
+  creator_fee_bps?: number; // share of every SwapCookAMM trade paid to the creator
+}
+
+// MerkleAllowlist matching the Rust struct in launch/plugins
+export interface MerkleAllowlist {
+  root: Uint8Array | number[]; // 32 bytes
+  phase_end: number;
+  default_cap: number; // lamports, 0 = uncapped
 }
 
 export interface BuyTicketsArgs {
@@ -257,6 +273,17 @@ export interface CreateListingArgs {
   provider: number;
 }
 
+// Borsh schema for Option<MerkleAllowlist>
+const merkleAllowlistSchema = {
+  option: {
+    struct: {
+      root: { array: { type: 'u8', len: 32 } },
+      phase_end: 'u64',
+      default_cap: 'u64',
+    }
+  }
+};
+
 // Borsh schema for CreateArgs - using the standard borsh library
 const createArgsSchema = {
   struct: {
@@ -279,6 +306,12 @@ const createArgsSchema = {
     launch_type: 'u8',
     whitelist_tokens: 'u64',
     whitelist_end: 'u64',
+    allowlist: merkleAllowlistSchema,
+    soft_cap: 'u64',
+    hard_cap: 'u64',
+    auction_floor_price: 'u64',
+    auction_half_life: 'u64',
+    creator_fee_bps: 'u16',
   }
 };
 
@@ -306,6 +339,9 @@ const instantLaunchArgsSchema = {
     twitter: 'string',
     telegram: 'string',
     discord: 'string',
+    liquidity_scaling: 'u8',
+    allowlist: merkleAllowlistSchema,
+    creator_fee_bps: 'u16',
   }
 };
 
@@ -331,7 +367,15 @@ function serializeInstruction(instruction: LaunchInstruction, args?: any): Buffe
         console.log('🔍 Args type check:', typeof args);
         console.log('🔍 Args keys:', Object.keys(args));
         try {
-          const argsBuffer = Buffer.from(serialize(createArgsSchema, args));
+          const argsBuffer = Buffer.from(serialize(createArgsSchema, {
+            allowlist: null,
+            soft_cap: 0,
+            hard_cap: 0,
+            auction_floor_price: 0,
+            auction_half_life: 0,
+            creator_fee_bps: 0,
+            ...args,
+          }));
           console.log('🔍 Serialized buffer length:', argsBuffer.length);
           console.log('🔍 Serialized buffer (first 50 bytes):', Array.from(argsBuffer.slice(0, 50)).map(b => b.toString(16).padStart(2, '0')).join(' '));
           const finalBuffer = Buffer.concat([instructionIndex, argsBuffer]);
@@ -386,7 +430,7 @@ function serializeInstruction(instruction: LaunchInstruction, args?: any): Buffe
         }
         
         try {
-          const argsBuffer = Buffer.from(serialize(instantLaunchArgsSchema, args));
+          const argsBuffer = Buffer.from(serialize(instantLaunchArgsSchema, { liquidity_scaling: 0, allowlist: null, creator_fee_bps: 0, ...args }));
           console.log('🔍 Serialized buffer length:', argsBuffer.length);
           
           // Extract and verify total_supply from serialized buffer
@@ -767,7 +811,8 @@ export class LetsCookProgram {
       systemProgram: PublicKey;
     }
   ): TransactionInstruction {
-    const data = serializeInstruction(LaunchInstruction.InitCookAMM);
+    // InitCookAMMArgs { liquidity_scaling: u8 }, 0 leaves the pool unscaled
+    const data = Buffer.from([LaunchInstruction.InitCookAMM, 0]);
     
     return new TransactionInstruction({
       keys: [
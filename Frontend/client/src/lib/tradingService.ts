@@ -3584,7 +3584,7 @@ If the button doesn't appear, you can also use the helper function. The error to
         { pubkey: new PublicKey('11111111111111111111111111111111'), isSigner: false, isWritable: false }, // system_program (3)
       ],
       programId: PROGRAM_ID,
-      data: Buffer.from([4]), // InitCookAMM discriminator (5th variant, 0-indexed)
+      data: Buffer.from([4, 0]), // InitCookAMM discriminator (5th variant, 0-indexed), liquidity_scaling off
     });
     
     transaction.add(initAMMInstruction);